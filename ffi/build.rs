@@ -0,0 +1,22 @@
+use std::env;
+use std::path::Path;
+
+// The header is generated into OUT_DIR, the tests check that the committed
+// include/nes_rs.h matches it.
+fn main() {
+    let crate_dir = env::var("CARGO_MANIFEST_DIR").unwrap();
+    let out_dir = env::var("OUT_DIR").unwrap();
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+    let config = cbindgen::Config::from_root_or_default(&crate_dir);
+    match cbindgen::Builder::new()
+        .with_crate(&crate_dir)
+        .with_config(config)
+        .generate()
+    {
+        Ok(bindings) => {
+            bindings.write_to_file(Path::new(&out_dir).join("nes_rs.h"));
+        }
+        Err(e) => println!("cargo:warning=Unable to generate C header: {}", e),
+    }
+}
@@ -0,0 +1,185 @@
+#ifndef NES_RS_H
+#define NES_RS_H
+
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#define NES_VIDEO_WIDTH 256
+
+#define NES_VIDEO_HEIGHT 240
+
+/**
+ * Size in bytes of the RGB24 video buffer.
+ */
+#define NES_VIDEO_BUFFER_SIZE ((NES_VIDEO_WIDTH * NES_VIDEO_HEIGHT) * 3)
+
+#define NES_SAMPLING_RATE 44100
+
+/**
+ * Result codes, new codes are only ever appended to keep the ABI stable.
+ * `Panic` reports an internal emulator failure, the handle should be destroyed after it.
+ */
+typedef enum NesResult {
+  NES_RESULT_OK,
+  NES_RESULT_NULL_POINTER,
+  NES_RESULT_BUFFER_TOO_SMALL,
+  NES_RESULT_NES_ROM_HEADER_TOO_SHORT,
+  NES_RESULT_NES_ROM_TRAINER_TOO_SHORT,
+  NES_RESULT_NES_PRG_ROM_TOO_SHORT,
+  NES_RESULT_NES_CHR_ROM_TOO_SHORT,
+  NES_RESULT_NES_PLAY_CHOICE_ROM_TOO_SHORT,
+  NES_RESULT_NES_UNSUPPORTED_MAPPER,
+  NES_RESULT_UNKNOWN_NES_FORMAT,
+  NES_RESULT_LOAD_STATE_VERSION_MISMATCH,
+  NES_RESULT_LOAD_STATE_INTERNAL_ERROR,
+  NES_RESULT_SAVE_STATE_INTERNAL_ERROR,
+  NES_RESULT_LOAD_STATE_DECOMPRESSION_ERROR,
+  NES_RESULT_LOAD_STATE_COMPRESSION_ERROR,
+  NES_RESULT_NES_CPU_INVALID_OPCODE,
+  NES_RESULT_UNKNOWN_FDS_FORMAT,
+  NES_RESULT_FDS_BIOS_WRONG_SIZE,
+  NES_RESULT_FDS_DISK_SIDE_TOO_SHORT,
+  NES_RESULT_FDS_DISK_SIDE_UNAVAILABLE,
+  NES_RESULT_FDS_NOT_LOADED,
+  NES_RESULT_UNKNOWN_NSF_FORMAT,
+  NES_RESULT_NSF_HEADER_TOO_SHORT,
+  NES_RESULT_NSF_INVALID_LOAD_ADDRESS,
+  NES_RESULT_NSF_DATA_TOO_LARGE,
+  NES_RESULT_NSFE_MISSING_CHUNK,
+  NES_RESULT_NSFE_CHUNK_CORRUPTED,
+  NES_RESULT_NSF_TRACK_UNAVAILABLE,
+  NES_RESULT_NSF_NOT_LOADED,
+  NES_RESULT_INVALID_BARCODE,
+  NES_RESULT_BARCODE_READER_UNAVAILABLE,
+  NES_RESULT_PLAY_CHOICE_NOT_LOADED,
+  NES_RESULT_INVALID_PALETTE_SIZE,
+  NES_RESULT_IPS_PATCH_CORRUPTED,
+  NES_RESULT_SCRIPT_ERROR,
+  NES_RESULT_LOAD_STATE_ROM_MISMATCH,
+  NES_RESULT_INVALID_ARGUMENT,
+  NES_RESULT_PANIC,
+} NesResult;
+
+typedef enum NesControllerId {
+  NES_CONTROLLER_ID_CONTROLLER1,
+  NES_CONTROLLER_ID_CONTROLLER2,
+} NesControllerId;
+
+typedef enum NesButton {
+  NES_BUTTON_A,
+  NES_BUTTON_B,
+  NES_BUTTON_SELECT,
+  NES_BUTTON_START,
+  NES_BUTTON_UP,
+  NES_BUTTON_DOWN,
+  NES_BUTTON_LEFT,
+  NES_BUTTON_RIGHT,
+} NesButton;
+
+/**
+ * Opaque emulator handle.
+ */
+typedef struct Nes Nes;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Creates an emulator, it has to be freed with `nes_destroy`.
+ */
+struct Nes *nes_create(void);
+
+/**
+ * # Safety
+ * `nes` must be null or a handle returned by `nes_create` that was not destroyed yet.
+ */
+void nes_destroy(struct Nes *nes);
+
+/**
+ * Loads an iNES/NES 2.0 ROM image and power cycles the console.
+ *
+ * # Safety
+ * `nes` must be a valid handle and `data` must point to `size` readable bytes.
+ */
+enum NesResult nes_load_rom(struct Nes *nes, const uint8_t *data, size_t size);
+
+/**
+ * Emulates a single frame using the current button state.
+ *
+ * # Safety
+ * `nes` must be a valid handle.
+ */
+enum NesResult nes_run_frame(struct Nes *nes);
+
+/**
+ * Returns the RGB24 pixels of the last frame, `NES_VIDEO_BUFFER_SIZE` bytes long.
+ * The pointer stays valid until the handle is destroyed.
+ *
+ * # Safety
+ * `nes` must be a valid handle.
+ */
+const uint8_t *nes_get_video_buffer(const struct Nes *nes);
+
+/**
+ * Returns the mono audio samples of the last frame and stores their count in `sample_count`.
+ * The pointer is valid until the next `nes_run_frame` call.
+ *
+ * # Safety
+ * `nes` must be a valid handle and `sample_count` must be writable.
+ */
+const float *nes_get_audio_buffer(const struct Nes *nes, size_t *sample_count);
+
+/**
+ * Presses `button`, a `NesButton` value, on the `NesControllerId` controller `id`.
+ * Values outside of these enums return `NES_RESULT_INVALID_ARGUMENT`.
+ *
+ * # Safety
+ * `nes` must be a valid handle.
+ */
+enum NesResult nes_set_button(struct Nes *nes, uint32_t id, uint32_t button);
+
+/**
+ * Releases a button pressed with `nes_set_button`.
+ *
+ * # Safety
+ * `nes` must be a valid handle.
+ */
+enum NesResult nes_clear_button(struct Nes *nes, uint32_t id, uint32_t button);
+
+/**
+ * Saves the state into `buffer` and stores its size in `size`.
+ * If `buffer` is null or `capacity` is too small, `NES_RESULT_BUFFER_TOO_SMALL` is returned
+ * and `size` holds the required capacity.
+ *
+ * # Safety
+ * `nes` must be a valid handle, `buffer` must be null or point to `capacity` writable bytes
+ * and `size` must be writable.
+ */
+enum NesResult nes_save_state(struct Nes *nes, uint8_t *buffer, size_t capacity, size_t *size);
+
+/**
+ * # Safety
+ * `nes` must be a valid handle and `data` must point to `size` readable bytes.
+ */
+enum NesResult nes_load_state(struct Nes *nes, const uint8_t *data, size_t size);
+
+/**
+ * Returns the message of the last error reported by the handle, or an empty string.
+ * The pointer is valid until the next failing call.
+ *
+ * # Safety
+ * `nes` must be a valid handle.
+ */
+const char *nes_get_last_error(const struct Nes *nes);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* NES_RS_H */
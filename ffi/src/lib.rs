@@ -0,0 +1,393 @@
+use nes_rs::{ControllerCallback, ControllerId, Error, StdNesControllerButton, ZapperTarget};
+use std::ffi::{CString, c_char};
+use std::ptr;
+
+// Literals so that cbindgen can emit them, checked against the library below.
+pub const NES_VIDEO_WIDTH: usize = 256;
+pub const NES_VIDEO_HEIGHT: usize = 240;
+/// Size in bytes of the RGB24 video buffer.
+pub const NES_VIDEO_BUFFER_SIZE: usize = NES_VIDEO_WIDTH * NES_VIDEO_HEIGHT * 3;
+pub const NES_SAMPLING_RATE: usize = 44100;
+
+const _: () = assert!(
+    NES_VIDEO_WIDTH == nes_rs::VIDEO_FRAME_WIDTH
+        && NES_VIDEO_HEIGHT == nes_rs::VIDEO_FRAME_HEIGHT
+        && NES_VIDEO_BUFFER_SIZE == nes_rs::VIDEO_FRAME_SIZE
+        && NES_SAMPLING_RATE == nes_rs::SAMPLING_RATE
+);
+
+const BUTTON_COUNT: usize = StdNesControllerButton::Right as usize + 1;
+
+/// Result codes, new codes are only ever appended to keep the ABI stable.
+/// `Panic` reports an internal emulator failure, the handle should be destroyed after it.
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum NesResult {
+    Ok,
+    NullPointer,
+    BufferTooSmall,
+    NesRomHeaderTooShort,
+    NesRomTrainerTooShort,
+    NesPrgRomTooShort,
+    NesChrRomTooShort,
+    NesPlayChoiceRomTooShort,
+    NesUnsupportedMapper,
+    UnknownNesFormat,
+    LoadStateVersionMismatch,
+    LoadStateInternalError,
+    SaveStateInternalError,
+    LoadStateDecompressionError,
+    LoadStateCompressionError,
+    NesCpuInvalidOpcode,
+    UnknownFdsFormat,
+    FdsBiosWrongSize,
+    FdsDiskSideTooShort,
+    FdsDiskSideUnavailable,
+    FdsNotLoaded,
+    UnknownNsfFormat,
+    NsfHeaderTooShort,
+    NsfInvalidLoadAddress,
+    NsfDataTooLarge,
+    NsfeMissingChunk,
+    NsfeChunkCorrupted,
+    NsfTrackUnavailable,
+    NsfNotLoaded,
+    InvalidBarcode,
+    BarcodeReaderUnavailable,
+    PlayChoiceNotLoaded,
+    InvalidPaletteSize,
+    IpsPatchCorrupted,
+    ScriptError,
+    LoadStateRomMismatch,
+    InvalidArgument,
+    Panic,
+}
+
+impl From<&Error> for NesResult {
+    fn from(error: &Error) -> Self {
+        match error {
+            Error::NesRomHeaderTooShort(_) => NesResult::NesRomHeaderTooShort,
+            Error::NesRomTrainerTooShort(_) => NesResult::NesRomTrainerTooShort,
+            Error::NesPrgRomTooShort(_, _) => NesResult::NesPrgRomTooShort,
+            Error::NesChrRomTooShort(_, _) => NesResult::NesChrRomTooShort,
+            Error::NesPlayChoiceRomTooShort(_) => NesResult::NesPlayChoiceRomTooShort,
+            Error::NesUnsupportedMapper(_) => NesResult::NesUnsupportedMapper,
+            Error::UnknownNesFormat => NesResult::UnknownNesFormat,
+            Error::LoadStateVersionMismatch(_, _) => NesResult::LoadStateVersionMismatch,
+            Error::LoadStateInternalError(_) => NesResult::LoadStateInternalError,
+            Error::SaveStateInternalError(_) => NesResult::SaveStateInternalError,
+            Error::LoadStateDecompressionError(_) => NesResult::LoadStateDecompressionError,
+            Error::LoadStateCompressionError(_) => NesResult::LoadStateCompressionError,
+            Error::NesCpuInvalidOpcode(_, _) => NesResult::NesCpuInvalidOpcode,
+            Error::UnknownFdsFormat => NesResult::UnknownFdsFormat,
+            Error::FdsBiosWrongSize(_) => NesResult::FdsBiosWrongSize,
+            Error::FdsDiskSideTooShort(_, _) => NesResult::FdsDiskSideTooShort,
+            Error::FdsDiskSideUnavailable(_) => NesResult::FdsDiskSideUnavailable,
+            Error::FdsNotLoaded => NesResult::FdsNotLoaded,
+            Error::UnknownNsfFormat => NesResult::UnknownNsfFormat,
+            Error::NsfHeaderTooShort(_) => NesResult::NsfHeaderTooShort,
+            Error::NsfInvalidLoadAddress(_) => NesResult::NsfInvalidLoadAddress,
+            Error::NsfDataTooLarge(_) => NesResult::NsfDataTooLarge,
+            Error::NsfeMissingChunk(_) => NesResult::NsfeMissingChunk,
+            Error::NsfeChunkCorrupted(_) => NesResult::NsfeChunkCorrupted,
+            Error::NsfTrackUnavailable(_) => NesResult::NsfTrackUnavailable,
+            Error::NsfNotLoaded => NesResult::NsfNotLoaded,
+            Error::InvalidBarcode(_) => NesResult::InvalidBarcode,
+            Error::BarcodeReaderUnavailable => NesResult::BarcodeReaderUnavailable,
+            Error::PlayChoiceNotLoaded => NesResult::PlayChoiceNotLoaded,
+            Error::InvalidPaletteSize(_) => NesResult::InvalidPaletteSize,
+            Error::IpsPatchCorrupted(_) => NesResult::IpsPatchCorrupted,
+            Error::ScriptError(_) => NesResult::ScriptError,
+            Error::LoadStateRomMismatch(_, _) => NesResult::LoadStateRomMismatch,
+        }
+    }
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum NesControllerId {
+    Controller1,
+    Controller2,
+}
+
+#[repr(C)]
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum NesButton {
+    A,
+    B,
+    Select,
+    Start,
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl TryFrom<u32> for NesControllerId {
+    type Error = NesResult;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(NesControllerId::Controller1),
+            1 => Ok(NesControllerId::Controller2),
+            _ => Err(NesResult::InvalidArgument),
+        }
+    }
+}
+
+impl TryFrom<u32> for NesButton {
+    type Error = NesResult;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(NesButton::A),
+            1 => Ok(NesButton::B),
+            2 => Ok(NesButton::Select),
+            3 => Ok(NesButton::Start),
+            4 => Ok(NesButton::Up),
+            5 => Ok(NesButton::Down),
+            6 => Ok(NesButton::Left),
+            7 => Ok(NesButton::Right),
+            _ => Err(NesResult::InvalidArgument),
+        }
+    }
+}
+
+impl From<NesButton> for StdNesControllerButton {
+    fn from(button: NesButton) -> Self {
+        match button {
+            NesButton::A => StdNesControllerButton::A,
+            NesButton::B => StdNesControllerButton::B,
+            NesButton::Select => StdNesControllerButton::Select,
+            NesButton::Start => StdNesControllerButton::Start,
+            NesButton::Up => StdNesControllerButton::Up,
+            NesButton::Down => StdNesControllerButton::Down,
+            NesButton::Left => StdNesControllerButton::Left,
+            NesButton::Right => StdNesControllerButton::Right,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Buttons([[bool; BUTTON_COUNT]; 2]);
+
+impl ControllerCallback for Buttons {
+    fn is_button_pressed(&self, id: ControllerId, button: StdNesControllerButton) -> bool {
+        self.0[id as usize][button as usize]
+    }
+
+    fn is_zapper_trigger_pressed(&self, _id: ControllerId) -> Option<ZapperTarget> {
+        None
+    }
+}
+
+/// Opaque emulator handle.
+pub struct Nes {
+    nes: nes_rs::Nes,
+    buttons: Buttons,
+    last_error: CString,
+}
+
+impl Nes {
+    fn handle_result(&mut self, result: Result<(), Error>) -> NesResult {
+        match result {
+            Ok(()) => NesResult::Ok,
+            Err(e) => {
+                self.last_error = CString::new(e.to_string()).unwrap_or_default();
+                NesResult::from(&e)
+            }
+        }
+    }
+
+    fn set_button(&mut self, id: u32, button: u32, pressed: bool) -> NesResult {
+        let (id, button) = match (NesControllerId::try_from(id), NesButton::try_from(button)) {
+            (Ok(id), Ok(button)) => (id, button),
+            (Err(e), _) | (_, Err(e)) => return e,
+        };
+        self.buttons.0[id as usize][StdNesControllerButton::from(button) as usize] = pressed;
+        NesResult::Ok
+    }
+}
+
+// Unwinding into C is undefined behavior, so every entry point runs its body through this and
+// returns `on_panic` instead.
+fn catch_panic<T>(on_panic: T, body: impl FnOnce() -> T) -> T {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(body)).unwrap_or(on_panic)
+}
+
+/// Creates an emulator, it has to be freed with `nes_destroy`.
+#[unsafe(no_mangle)]
+pub extern "C" fn nes_create() -> *mut Nes {
+    catch_panic(ptr::null_mut(), || {
+        Box::into_raw(Box::new(Nes {
+            nes: nes_rs::Nes::new(),
+            buttons: Buttons::default(),
+            last_error: CString::default(),
+        }))
+    })
+}
+
+/// # Safety
+/// `nes` must be null or a handle returned by `nes_create` that was not destroyed yet.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_destroy(nes: *mut Nes) {
+    if !nes.is_null() {
+        catch_panic((), || drop(unsafe { Box::from_raw(nes) }));
+    }
+}
+
+/// Loads an iNES/NES 2.0 ROM image and power cycles the console.
+///
+/// # Safety
+/// `nes` must be a valid handle and `data` must point to `size` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_load_rom(nes: *mut Nes, data: *const u8, size: usize) -> NesResult {
+    let Some(nes) = (unsafe { nes.as_mut() }) else {
+        return NesResult::NullPointer;
+    };
+    if data.is_null() {
+        return NesResult::NullPointer;
+    }
+    let rom = unsafe { std::slice::from_raw_parts(data, size) };
+    catch_panic(NesResult::Panic, || {
+        let result = nes.nes.load_rom(rom);
+        nes.handle_result(result)
+    })
+}
+
+/// Emulates a single frame using the current button state.
+///
+/// # Safety
+/// `nes` must be a valid handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_run_frame(nes: *mut Nes) -> NesResult {
+    let Some(nes) = (unsafe { nes.as_mut() }) else {
+        return NesResult::NullPointer;
+    };
+    catch_panic(NesResult::Panic, || {
+        let result = nes.nes.run_single_frame(&nes.buttons).map(|_| ());
+        nes.handle_result(result)
+    })
+}
+
+/// Returns the RGB24 pixels of the last frame, `NES_VIDEO_BUFFER_SIZE` bytes long.
+/// The pointer stays valid until the handle is destroyed.
+///
+/// # Safety
+/// `nes` must be a valid handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_get_video_buffer(nes: *const Nes) -> *const u8 {
+    let Some(nes) = (unsafe { nes.as_ref() }) else {
+        return ptr::null();
+    };
+    catch_panic(ptr::null(), || {
+        nes.nes.get_emulation_frame().video.get_pixels().as_ptr()
+    })
+}
+
+/// Returns the mono audio samples of the last frame and stores their count in `sample_count`.
+/// The pointer is valid until the next `nes_run_frame` call.
+///
+/// # Safety
+/// `nes` must be a valid handle and `sample_count` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_get_audio_buffer(
+    nes: *const Nes,
+    sample_count: *mut usize,
+) -> *const f32 {
+    let (Some(nes), Some(sample_count)) =
+        (unsafe { nes.as_ref() }, unsafe { sample_count.as_mut() })
+    else {
+        return ptr::null();
+    };
+    catch_panic(ptr::null(), || {
+        let samples = nes.nes.get_emulation_frame().audio.get_samples();
+        *sample_count = samples.len();
+        samples.as_ptr()
+    })
+}
+
+/// Presses `button`, a `NesButton` value, on the `NesControllerId` controller `id`.
+/// Values outside of these enums return `NES_RESULT_INVALID_ARGUMENT`.
+///
+/// # Safety
+/// `nes` must be a valid handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_set_button(nes: *mut Nes, id: u32, button: u32) -> NesResult {
+    let Some(nes) = (unsafe { nes.as_mut() }) else {
+        return NesResult::NullPointer;
+    };
+    catch_panic(NesResult::Panic, || nes.set_button(id, button, true))
+}
+
+/// Releases a button pressed with `nes_set_button`.
+///
+/// # Safety
+/// `nes` must be a valid handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_clear_button(nes: *mut Nes, id: u32, button: u32) -> NesResult {
+    let Some(nes) = (unsafe { nes.as_mut() }) else {
+        return NesResult::NullPointer;
+    };
+    catch_panic(NesResult::Panic, || nes.set_button(id, button, false))
+}
+
+/// Saves the state into `buffer` and stores its size in `size`.
+/// If `buffer` is null or `capacity` is too small, `NES_RESULT_BUFFER_TOO_SMALL` is returned
+/// and `size` holds the required capacity.
+///
+/// # Safety
+/// `nes` must be a valid handle, `buffer` must be null or point to `capacity` writable bytes
+/// and `size` must be writable.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_save_state(
+    nes: *mut Nes,
+    buffer: *mut u8,
+    capacity: usize,
+    size: *mut usize,
+) -> NesResult {
+    let (Some(nes), Some(size)) = (unsafe { nes.as_mut() }, unsafe { size.as_mut() }) else {
+        return NesResult::NullPointer;
+    };
+    catch_panic(NesResult::Panic, || {
+        let state = match nes.nes.save_state() {
+            Ok(state) => state,
+            Err(e) => return nes.handle_result(Err(e)),
+        };
+        *size = state.len();
+        if buffer.is_null() || capacity < state.len() {
+            return NesResult::BufferTooSmall;
+        }
+        unsafe { ptr::copy_nonoverlapping(state.as_ptr(), buffer, state.len()) };
+        NesResult::Ok
+    })
+}
+
+/// # Safety
+/// `nes` must be a valid handle and `data` must point to `size` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_load_state(nes: *mut Nes, data: *const u8, size: usize) -> NesResult {
+    let Some(nes) = (unsafe { nes.as_mut() }) else {
+        return NesResult::NullPointer;
+    };
+    if data.is_null() {
+        return NesResult::NullPointer;
+    }
+    let state = unsafe { std::slice::from_raw_parts(data, size) };
+    catch_panic(NesResult::Panic, || {
+        let result = nes.nes.load_state(state.to_vec());
+        nes.handle_result(result)
+    })
+}
+
+/// Returns the message of the last error reported by the handle, or an empty string.
+/// The pointer is valid until the next failing call.
+///
+/// # Safety
+/// `nes` must be a valid handle.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn nes_get_last_error(nes: *const Nes) -> *const c_char {
+    unsafe { nes.as_ref() }.map_or(ptr::null(), |nes| {
+        catch_panic(ptr::null(), || nes.last_error.as_ptr())
+    })
+}
@@ -0,0 +1,79 @@
+use nes_rs_ffi::*;
+use std::ffi::CStr;
+use std::ptr;
+
+#[test]
+fn ffi_test() {
+    let rom = std::fs::read("../tests/nestest/nestest.nes").unwrap();
+    unsafe {
+        let nes = nes_create();
+        assert_eq!(
+            nes_load_rom(nes, rom.as_ptr(), 8),
+            NesResult::NesRomHeaderTooShort
+        );
+        assert!(!CStr::from_ptr(nes_get_last_error(nes)).is_empty());
+        assert_eq!(nes_load_rom(nes, rom.as_ptr(), rom.len()), NesResult::Ok);
+
+        let controller1 = NesControllerId::Controller1 as u32;
+        let start = NesButton::Start as u32;
+        assert_eq!(nes_set_button(nes, controller1, start), NesResult::Ok);
+        for _ in 0..10 {
+            assert_eq!(nes_run_frame(nes), NesResult::Ok);
+        }
+        assert_eq!(nes_clear_button(nes, controller1, start), NesResult::Ok);
+        assert_eq!(
+            nes_set_button(nes, NesControllerId::Controller2 as u32 + 1, start),
+            NesResult::InvalidArgument
+        );
+        assert_eq!(
+            nes_set_button(nes, controller1, NesButton::Right as u32 + 1),
+            NesResult::InvalidArgument
+        );
+        assert_eq!(
+            nes_set_button(ptr::null_mut(), controller1, start),
+            NesResult::NullPointer
+        );
+
+        let mut sample_count = 0;
+        assert!(!nes_get_audio_buffer(nes, &mut sample_count).is_null());
+        assert!(sample_count > 0);
+
+        let mut size = 0;
+        assert_eq!(
+            nes_save_state(nes, ptr::null_mut(), 0, &mut size),
+            NesResult::BufferTooSmall
+        );
+        let mut state = vec![0; size];
+        assert_eq!(
+            nes_save_state(nes, state.as_mut_ptr(), state.len(), &mut size),
+            NesResult::Ok
+        );
+
+        assert_eq!(nes_run_frame(nes), NesResult::Ok);
+        let video = std::slice::from_raw_parts(nes_get_video_buffer(nes), NES_VIDEO_BUFFER_SIZE);
+        let frame = video.to_vec();
+        assert!(frame.iter().any(|&byte| byte != frame[0]));
+
+        assert_eq!(nes_load_state(nes, state.as_ptr(), size), NesResult::Ok);
+        assert_eq!(nes_run_frame(nes), NesResult::Ok);
+        let video = std::slice::from_raw_parts(nes_get_video_buffer(nes), NES_VIDEO_BUFFER_SIZE);
+        assert_eq!(video, frame.as_slice());
+
+        assert_eq!(
+            nes_load_state(nes, state.as_ptr(), 4),
+            NesResult::LoadStateDecompressionError
+        );
+        nes_destroy(nes);
+    }
+}
+
+#[test]
+fn header_up_to_date_test() {
+    let generated = std::fs::read_to_string(concat!(env!("OUT_DIR"), "/nes_rs.h")).unwrap();
+    let committed = std::fs::read_to_string("include/nes_rs.h").unwrap();
+    assert!(
+        generated == committed,
+        "include/nes_rs.h is out of date, copy it from {}",
+        env!("OUT_DIR")
+    );
+}
@@ -0,0 +1,261 @@
+/*
+ * Minimal libretro host used to smoke test the core without RetroArch.
+ *
+ *   cargo build --release -p nes-rs-libretro
+ *   cc -o retro_host libretro/host/retro_host.c -ldl
+ *   ./retro_host target/release/libnes_rs_libretro.so game.nes [frames]
+ */
+#include <dlfcn.h>
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stdint.h>
+#include <stdio.h>
+#include <stdlib.h>
+#include <string.h>
+
+#define RETRO_ENVIRONMENT_SET_PIXEL_FORMAT 10
+#define RETRO_ENVIRONMENT_GET_VARIABLE 15
+#define RETRO_ENVIRONMENT_SET_VARIABLES 16
+#define RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE 17
+#define RETRO_ENVIRONMENT_GET_LOG_INTERFACE 27
+#define RETRO_ENVIRONMENT_SET_MEMORY_MAPS (36 | 0x10000)
+#define RETRO_MEMORY_SYSTEM_RAM 2
+
+struct retro_variable {
+    const char *key;
+    const char *value;
+};
+
+struct retro_log_callback {
+    void (*log)(int level, const char *fmt, ...);
+};
+
+struct retro_game_info {
+    const char *path;
+    const void *data;
+    size_t size;
+    const char *meta;
+};
+
+struct retro_memory_map {
+    const void *descriptors;
+    unsigned num_descriptors;
+};
+
+struct retro_system_info {
+    const char *library_name;
+    const char *library_version;
+    const char *valid_extensions;
+    bool need_fullpath;
+    bool block_extract;
+};
+
+struct retro_game_geometry {
+    unsigned base_width;
+    unsigned base_height;
+    unsigned max_width;
+    unsigned max_height;
+    float aspect_ratio;
+};
+
+struct retro_system_timing {
+    double fps;
+    double sample_rate;
+};
+
+struct retro_system_av_info {
+    struct retro_game_geometry geometry;
+    struct retro_system_timing timing;
+};
+
+static unsigned frame_count;
+static size_t audio_frame_count;
+static uint32_t frame_checksum;
+
+static void log_printf(int level, const char *fmt, ...)
+{
+    va_list args;
+    va_start(args, fmt);
+    fprintf(stderr, "log %d: ", level);
+    vfprintf(stderr, fmt, args);
+    va_end(args);
+}
+
+static bool environment(unsigned cmd, void *data)
+{
+    switch (cmd) {
+    case RETRO_ENVIRONMENT_SET_PIXEL_FORMAT:
+        return true;
+    case RETRO_ENVIRONMENT_SET_VARIABLES:
+        for (const struct retro_variable *var = data; var->key; var++)
+            printf("option %s: %s\n", var->key, var->value);
+        return true;
+    case RETRO_ENVIRONMENT_GET_VARIABLE: {
+        struct retro_variable *var = data;
+        if (strcmp(var->key, "nesrs_sprite_limit") == 0) {
+            var->value = "disabled";
+            return true;
+        }
+        return false;
+    }
+    case RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE:
+        *(bool *)data = false;
+        return true;
+    case RETRO_ENVIRONMENT_GET_LOG_INTERFACE:
+        ((struct retro_log_callback *)data)->log = log_printf;
+        return true;
+    case RETRO_ENVIRONMENT_SET_MEMORY_MAPS:
+        printf("memory descriptors: %u\n", ((struct retro_memory_map *)data)->num_descriptors);
+        return true;
+    default:
+        return false;
+    }
+}
+
+static void video_refresh(const void *data, unsigned width, unsigned height, size_t pitch)
+{
+    const uint32_t *pixels = data;
+    frame_checksum = 0;
+    for (size_t i = 0; i < pitch / 4 * height; i++)
+        frame_checksum = frame_checksum * 31 + pixels[i];
+    (void)width;
+    frame_count++;
+}
+
+static size_t audio_sample_batch(const int16_t *data, size_t frames)
+{
+    (void)data;
+    audio_frame_count += frames;
+    return frames;
+}
+
+static void input_poll(void) {}
+
+static int16_t input_state(unsigned port, unsigned device, unsigned index, unsigned id)
+{
+    (void)port, (void)device, (void)index, (void)id;
+    return 0;
+}
+
+static void *load_symbol(void *core, const char *name)
+{
+    void *symbol = dlsym(core, name);
+    if (!symbol) {
+        fprintf(stderr, "missing symbol %s\n", name);
+        exit(1);
+    }
+    return symbol;
+}
+
+#define LOAD(name) name##_t name = (name##_t)load_symbol(core, #name)
+
+typedef unsigned (*retro_api_version_t)(void);
+typedef void (*retro_set_environment_t)(bool (*)(unsigned, void *));
+typedef void (*retro_set_video_refresh_t)(void (*)(const void *, unsigned, unsigned, size_t));
+typedef void (*retro_set_audio_sample_batch_t)(size_t (*)(const int16_t *, size_t));
+typedef void (*retro_set_input_poll_t)(void (*)(void));
+typedef void (*retro_set_input_state_t)(int16_t (*)(unsigned, unsigned, unsigned, unsigned));
+typedef void (*retro_init_t)(void);
+typedef void (*retro_deinit_t)(void);
+typedef void (*retro_get_system_info_t)(struct retro_system_info *);
+typedef void (*retro_get_system_av_info_t)(struct retro_system_av_info *);
+typedef bool (*retro_load_game_t)(const struct retro_game_info *);
+typedef void (*retro_unload_game_t)(void);
+typedef void (*retro_run_t)(void);
+typedef size_t (*retro_serialize_size_t)(void);
+typedef bool (*retro_serialize_t)(void *, size_t);
+typedef bool (*retro_unserialize_t)(const void *, size_t);
+typedef size_t (*retro_get_memory_size_t)(unsigned);
+
+int main(int argc, char **argv)
+{
+    if (argc < 3) {
+        fprintf(stderr, "Usage: %s <core> <game> [frames]\n", argv[0]);
+        return 1;
+    }
+    unsigned frames = argc > 3 ? (unsigned)atoi(argv[3]) : 60;
+
+    void *core = dlopen(argv[1], RTLD_NOW);
+    if (!core) {
+        fprintf(stderr, "%s\n", dlerror());
+        return 1;
+    }
+    LOAD(retro_api_version);
+    LOAD(retro_set_environment);
+    LOAD(retro_set_video_refresh);
+    LOAD(retro_set_audio_sample_batch);
+    LOAD(retro_set_input_poll);
+    LOAD(retro_set_input_state);
+    LOAD(retro_init);
+    LOAD(retro_deinit);
+    LOAD(retro_get_system_info);
+    LOAD(retro_get_system_av_info);
+    LOAD(retro_load_game);
+    LOAD(retro_unload_game);
+    LOAD(retro_run);
+    LOAD(retro_serialize_size);
+    LOAD(retro_serialize);
+    LOAD(retro_unserialize);
+    LOAD(retro_get_memory_size);
+
+    FILE *file = fopen(argv[2], "rb");
+    if (!file) {
+        perror(argv[2]);
+        return 1;
+    }
+    fseek(file, 0, SEEK_END);
+    long size = ftell(file);
+    fseek(file, 0, SEEK_SET);
+    void *data = malloc(size);
+    if (fread(data, 1, size, file) != (size_t)size) {
+        perror(argv[2]);
+        return 1;
+    }
+    fclose(file);
+
+    struct retro_system_info system_info;
+    retro_get_system_info(&system_info);
+    printf("%s %s (api %u)\n", system_info.library_name, system_info.library_version,
+           retro_api_version());
+
+    retro_set_environment(environment);
+    retro_set_video_refresh(video_refresh);
+    retro_set_audio_sample_batch(audio_sample_batch);
+    retro_set_input_poll(input_poll);
+    retro_set_input_state(input_state);
+    retro_init();
+
+    struct retro_game_info game = {argv[2], data, (size_t)size, NULL};
+    if (!retro_load_game(&game)) {
+        fprintf(stderr, "unable to load %s\n", argv[2]);
+        return 1;
+    }
+    struct retro_system_av_info av_info;
+    retro_get_system_av_info(&av_info);
+    printf("video %ux%u aspect %.3f, %.2f fps, %.0f Hz\n", av_info.geometry.base_width,
+           av_info.geometry.base_height, av_info.geometry.aspect_ratio, av_info.timing.fps,
+           av_info.timing.sample_rate);
+    printf("system ram: %zu bytes\n", retro_get_memory_size(RETRO_MEMORY_SYSTEM_RAM));
+
+    for (unsigned i = 0; i < frames; i++)
+        retro_run();
+
+    size_t state_size = retro_serialize_size();
+    void *state = malloc(state_size);
+    bool is_serialized = retro_serialize(state, state_size);
+    retro_run();
+    uint32_t checksum = frame_checksum;
+    bool is_unserialized = retro_unserialize(state, state_size);
+    retro_run();
+    bool is_state_restored = is_serialized && is_unserialized && checksum == frame_checksum;
+
+    printf("frames: %u, audio frames: %zu, state: %zu bytes, restored: %s\n", frame_count,
+           audio_frame_count, state_size, is_state_restored ? "yes" : "no");
+
+    retro_unload_game();
+    retro_deinit();
+    free(state);
+    free(data);
+    dlclose(core);
+    return is_state_restored ? 0 : 1;
+}
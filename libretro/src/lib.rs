@@ -0,0 +1,512 @@
+pub mod ffi;
+
+use ffi::*;
+use nes_rs::{
+    BuiltinPalette, ControllerCallback, ControllerId, DEFAULT_FPS, EmulationFrame, Nes, PIXEL_SIZE,
+    PixelAspectRatio, SAMPLING_RATE, StdNesControllerButton, VIDEO_FRAME_HEIGHT, VIDEO_FRAME_WIDTH,
+    ZapperTarget,
+};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString, c_char, c_uint, c_void};
+use std::ptr;
+
+const LIBRARY_NAME: &CStr = c"nes-rs";
+const LIBRARY_VERSION: &str = concat!(env!("CARGO_PKG_VERSION"), "\0");
+const VALID_EXTENSIONS: &CStr = c"nes|nsf|nsfe";
+const NSF_MAGICS: [&[u8]; 2] = [b"NESM\x1A", b"NSFE"];
+
+const REGION_KEY: &CStr = c"nesrs_region";
+// PAL timing is not emulated, so NTSC is the only region on offer.
+const REGION_VALUE: &CStr = c"Region; NTSC";
+const PALETTE_KEY: &CStr = c"nesrs_palette";
+const SPRITE_LIMIT_KEY: &CStr = c"nesrs_sprite_limit";
+const SPRITE_LIMIT_VALUE: &CStr = c"Sprite limit; enabled|disabled";
+
+const INTERNAL_RAM_SIZE: usize = 0x800;
+const STATE_LENGTH_SIZE: usize = 4;
+// Save states are compressed, so their size varies from frame to frame.
+const STATE_SIZE_HEADROOM: usize = 0x10000;
+
+#[derive(Default)]
+struct Callbacks {
+    environment: Option<RetroEnvironmentFn>,
+    video_refresh: Option<RetroVideoRefreshFn>,
+    audio_sample_batch: Option<RetroAudioSampleBatchFn>,
+    input_poll: Option<RetroInputPollFn>,
+    input_state: Option<RetroInputStateFn>,
+    log: Option<RetroLogPrintfFn>,
+}
+
+struct Core {
+    callbacks: Callbacks,
+    nes: Option<Box<Nes>>,
+    video_buffer: Vec<u32>,
+    audio_buffer: Vec<i16>,
+    state_size: usize,
+    palette_value: CString,
+    memory_descriptors: Vec<RetroMemoryDescriptor>,
+}
+
+thread_local! {
+    static CORE: RefCell<Core> = RefCell::new(Core::new());
+}
+
+fn with_core<R>(f: impl FnOnce(&mut Core) -> R) -> R {
+    CORE.with_borrow_mut(f)
+}
+
+struct LibretroInput {
+    input_state: Option<RetroInputStateFn>,
+}
+
+impl ControllerCallback for LibretroInput {
+    fn is_button_pressed(&self, id: ControllerId, button: StdNesControllerButton) -> bool {
+        self.input_state.is_some_and(|input_state| {
+            let state =
+                unsafe { input_state(id as c_uint, RETRO_DEVICE_JOYPAD, 0, get_joypad_id(button)) };
+            state != 0
+        })
+    }
+
+    fn is_zapper_trigger_pressed(&self, _id: ControllerId) -> Option<ZapperTarget> {
+        None
+    }
+}
+
+fn get_joypad_id(button: StdNesControllerButton) -> c_uint {
+    match button {
+        StdNesControllerButton::A => RETRO_DEVICE_ID_JOYPAD_A,
+        StdNesControllerButton::B => RETRO_DEVICE_ID_JOYPAD_B,
+        StdNesControllerButton::Select => RETRO_DEVICE_ID_JOYPAD_SELECT,
+        StdNesControllerButton::Start => RETRO_DEVICE_ID_JOYPAD_START,
+        StdNesControllerButton::Up => RETRO_DEVICE_ID_JOYPAD_UP,
+        StdNesControllerButton::Down => RETRO_DEVICE_ID_JOYPAD_DOWN,
+        StdNesControllerButton::Left => RETRO_DEVICE_ID_JOYPAD_LEFT,
+        StdNesControllerButton::Right => RETRO_DEVICE_ID_JOYPAD_RIGHT,
+    }
+}
+
+impl Core {
+    fn new() -> Self {
+        let palette_names: Vec<&str> = BuiltinPalette::ALL
+            .iter()
+            .map(|palette| palette.get_name())
+            .collect();
+        Self {
+            callbacks: Default::default(),
+            nes: None,
+            video_buffer: vec![0; VIDEO_FRAME_WIDTH * VIDEO_FRAME_HEIGHT],
+            audio_buffer: Vec::new(),
+            state_size: 0,
+            palette_value: CString::new(format!("Palette; {}", palette_names.join("|"))).unwrap(),
+            memory_descriptors: Vec::new(),
+        }
+    }
+
+    fn environment(&self, cmd: c_uint, data: *mut c_void) -> bool {
+        self.callbacks
+            .environment
+            .is_some_and(|environment| unsafe { environment(cmd, data) })
+    }
+
+    fn get_log_interface(&self) -> Option<RetroLogPrintfFn> {
+        let mut callback = RetroLogCallback { log: None };
+        let is_set = self.environment(
+            RETRO_ENVIRONMENT_GET_LOG_INTERFACE,
+            &mut callback as *mut RetroLogCallback as *mut c_void,
+        );
+        callback.log.filter(|_| is_set)
+    }
+
+    // Falls back to stderr for frontends without a log interface.
+    fn log(&self, level: c_uint, message: &str) {
+        match (self.callbacks.log, CString::new(message)) {
+            (Some(log), Ok(message)) => unsafe {
+                log(level, c"nes-rs: %s\n".as_ptr(), message.as_ptr())
+            },
+            _ => eprintln!("nes-rs: {}", message),
+        }
+    }
+
+    fn set_variables(&self) {
+        let variables = [
+            RetroVariable {
+                key: REGION_KEY.as_ptr(),
+                value: REGION_VALUE.as_ptr(),
+            },
+            RetroVariable {
+                key: PALETTE_KEY.as_ptr(),
+                value: self.palette_value.as_ptr(),
+            },
+            RetroVariable {
+                key: SPRITE_LIMIT_KEY.as_ptr(),
+                value: SPRITE_LIMIT_VALUE.as_ptr(),
+            },
+            RetroVariable {
+                key: ptr::null(),
+                value: ptr::null(),
+            },
+        ];
+        self.environment(
+            RETRO_ENVIRONMENT_SET_VARIABLES,
+            variables.as_ptr() as *mut c_void,
+        );
+    }
+
+    fn get_variable(&self, key: &CStr) -> Option<String> {
+        let mut variable = RetroVariable {
+            key: key.as_ptr(),
+            value: ptr::null(),
+        };
+        let is_set = self.environment(
+            RETRO_ENVIRONMENT_GET_VARIABLE,
+            &mut variable as *mut RetroVariable as *mut c_void,
+        );
+        (is_set && !variable.value.is_null()).then(|| {
+            unsafe { CStr::from_ptr(variable.value) }
+                .to_string_lossy()
+                .into_owned()
+        })
+    }
+
+    fn is_variable_updated(&self) -> bool {
+        let mut is_updated = false;
+        self.environment(
+            RETRO_ENVIRONMENT_GET_VARIABLE_UPDATE,
+            &mut is_updated as *mut bool as *mut c_void,
+        ) && is_updated
+    }
+
+    fn apply_options(&mut self) {
+        let palette = self.get_variable(PALETTE_KEY).and_then(|name| {
+            BuiltinPalette::ALL
+                .into_iter()
+                .find(|palette| palette.get_name() == name)
+        });
+        let sprite_limit = self.get_variable(SPRITE_LIMIT_KEY);
+        let Some(nes) = self.nes.as_mut() else {
+            return;
+        };
+        let mut config = nes.config();
+        config.set_builtin_palette(palette.unwrap_or_default());
+        config.set_sprite_limit_enabled(sprite_limit.as_deref() != Some("disabled"));
+    }
+
+    fn set_memory_maps(&mut self) {
+        let Some(nes) = self.nes.as_mut() else {
+            return;
+        };
+        self.memory_descriptors = vec![RetroMemoryDescriptor {
+            flags: RETRO_MEMDESC_SYSTEM_RAM,
+            ptr: nes.get_internal_ram_mut().as_mut_ptr() as *mut c_void,
+            offset: 0,
+            start: 0,
+            select: 0,
+            disconnect: 0,
+            len: INTERNAL_RAM_SIZE,
+            addrspace: ptr::null(),
+        }];
+        let mut memory_map = RetroMemoryMap {
+            descriptors: self.memory_descriptors.as_ptr(),
+            num_descriptors: self.memory_descriptors.len() as c_uint,
+        };
+        self.environment(
+            RETRO_ENVIRONMENT_SET_MEMORY_MAPS,
+            &mut memory_map as *mut RetroMemoryMap as *mut c_void,
+        );
+    }
+
+    fn load_game(&mut self, data: &[u8]) -> bool {
+        let mut nes = Box::new(Nes::new());
+        let result = if NSF_MAGICS.iter().any(|magic| data.starts_with(magic)) {
+            nes.load_nsf(data)
+        } else {
+            nes.load_rom(data)
+        };
+        if let Err(e) = result {
+            self.log(RETRO_LOG_ERROR, &format!("unable to load game: {}", e));
+            return false;
+        }
+        let mut pixel_format = RETRO_PIXEL_FORMAT_XRGB8888;
+        if !self.environment(
+            RETRO_ENVIRONMENT_SET_PIXEL_FORMAT,
+            &mut pixel_format as *mut c_uint as *mut c_void,
+        ) {
+            self.log(
+                RETRO_LOG_ERROR,
+                "XRGB8888 pixel format is not supported by the frontend",
+            );
+            return false;
+        }
+        self.nes = Some(nes);
+        self.state_size = 0;
+        self.apply_options();
+        self.set_memory_maps();
+        true
+    }
+
+    fn run(&mut self) {
+        if let Some(input_poll) = self.callbacks.input_poll {
+            unsafe { input_poll() };
+        }
+        if self.is_variable_updated() {
+            self.apply_options();
+        }
+        let Some(nes) = self.nes.as_mut() else {
+            return;
+        };
+        let input = LibretroInput {
+            input_state: self.callbacks.input_state,
+        };
+        let frame = match nes.run_single_frame(&input) {
+            Ok(frame) => frame,
+            Err(e) => {
+                self.log(RETRO_LOG_ERROR, &format!("emulation error: {}", e));
+                return;
+            }
+        };
+        convert_video(frame, &mut self.video_buffer);
+        convert_audio(frame, &mut self.audio_buffer);
+        if let Some(video_refresh) = self.callbacks.video_refresh {
+            unsafe {
+                video_refresh(
+                    self.video_buffer.as_ptr() as *const c_void,
+                    VIDEO_FRAME_WIDTH as c_uint,
+                    VIDEO_FRAME_HEIGHT as c_uint,
+                    VIDEO_FRAME_WIDTH * size_of::<u32>(),
+                )
+            };
+        }
+        if let Some(audio_sample_batch) = self.callbacks.audio_sample_batch {
+            unsafe { audio_sample_batch(self.audio_buffer.as_ptr(), self.audio_buffer.len() / 2) };
+        }
+    }
+
+    fn get_state_size(&mut self) -> usize {
+        let Some(state) = self.nes.as_ref().and_then(|nes| nes.save_state().ok()) else {
+            return 0;
+        };
+        self.state_size = self
+            .state_size
+            .max(STATE_LENGTH_SIZE + state.len() + STATE_SIZE_HEADROOM);
+        self.state_size
+    }
+
+    fn serialize(&self, data: &mut [u8]) -> bool {
+        let Some(state) = self.nes.as_ref().and_then(|nes| nes.save_state().ok()) else {
+            return false;
+        };
+        if STATE_LENGTH_SIZE + state.len() > data.len() {
+            return false;
+        }
+        let (length, rest) = data.split_at_mut(STATE_LENGTH_SIZE);
+        length.copy_from_slice(&(state.len() as u32).to_le_bytes());
+        rest[..state.len()].copy_from_slice(&state);
+        rest[state.len()..].fill(0);
+        true
+    }
+
+    fn unserialize(&mut self, data: &[u8]) -> bool {
+        let Some(nes) = self.nes.as_mut() else {
+            return false;
+        };
+        let Some((length, rest)) = data.split_first_chunk::<STATE_LENGTH_SIZE>() else {
+            return false;
+        };
+        let Some(state) = rest.get(..u32::from_le_bytes(*length) as usize) else {
+            return false;
+        };
+        nes.load_state(state.to_vec()).is_ok()
+    }
+}
+
+fn convert_video(frame: &EmulationFrame, buffer: &mut [u32]) {
+    for (pixel, rgb) in buffer
+        .iter_mut()
+        .zip(frame.video.get_pixels().chunks_exact(PIXEL_SIZE))
+    {
+        *pixel = u32::from_be_bytes([0, rgb[0], rgb[1], rgb[2]]);
+    }
+}
+
+fn convert_audio(frame: &EmulationFrame, buffer: &mut Vec<i16>) {
+    buffer.clear();
+    for sample in frame.audio.get_samples() {
+        let sample = (sample.clamp(-1.0, 1.0) * i16::MAX as f32) as i16;
+        buffer.extend([sample, sample]);
+    }
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_api_version() -> c_uint {
+    RETRO_API_VERSION
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_set_environment(environment: RetroEnvironmentFn) {
+    with_core(|core| {
+        core.callbacks.environment = Some(environment);
+        core.callbacks.log = core.get_log_interface();
+        core.set_variables();
+    });
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_set_video_refresh(video_refresh: RetroVideoRefreshFn) {
+    with_core(|core| core.callbacks.video_refresh = Some(video_refresh));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_set_audio_sample(_audio_sample: RetroAudioSampleFn) {}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_set_audio_sample_batch(audio_sample_batch: RetroAudioSampleBatchFn) {
+    with_core(|core| core.callbacks.audio_sample_batch = Some(audio_sample_batch));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_set_input_poll(input_poll: RetroInputPollFn) {
+    with_core(|core| core.callbacks.input_poll = Some(input_poll));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_set_input_state(input_state: RetroInputStateFn) {
+    with_core(|core| core.callbacks.input_state = Some(input_state));
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_init() {}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_deinit() {
+    with_core(|core| core.nes = None);
+}
+
+/// # Safety
+/// `info` must point to a writable `retro_system_info`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn retro_get_system_info(info: *mut RetroSystemInfo) {
+    let info = unsafe { &mut *info };
+    info.library_name = LIBRARY_NAME.as_ptr();
+    info.library_version = LIBRARY_VERSION.as_ptr() as *const c_char;
+    info.valid_extensions = VALID_EXTENSIONS.as_ptr();
+    info.need_fullpath = false;
+    info.block_extract = false;
+}
+
+/// # Safety
+/// `info` must point to a writable `retro_system_av_info`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn retro_get_system_av_info(info: *mut RetroSystemAvInfo) {
+    let info = unsafe { &mut *info };
+    info.geometry = RetroGameGeometry {
+        base_width: VIDEO_FRAME_WIDTH as c_uint,
+        base_height: VIDEO_FRAME_HEIGHT as c_uint,
+        max_width: VIDEO_FRAME_WIDTH as c_uint,
+        max_height: VIDEO_FRAME_HEIGHT as c_uint,
+        aspect_ratio: VIDEO_FRAME_WIDTH as f32 * PixelAspectRatio::Ntsc.get_ratio()
+            / VIDEO_FRAME_HEIGHT as f32,
+    };
+    info.timing = RetroSystemTiming {
+        fps: DEFAULT_FPS as f64,
+        sample_rate: SAMPLING_RATE as f64,
+    };
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_set_controller_port_device(_port: c_uint, _device: c_uint) {}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_reset() {
+    with_core(|core| {
+        if let Some(nes) = core.nes.as_mut() {
+            nes.power_cycle();
+        }
+    });
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_run() {
+    with_core(|core| core.run());
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_serialize_size() -> usize {
+    with_core(|core| core.get_state_size())
+}
+
+/// # Safety
+/// `data` must point to `size` writable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn retro_serialize(data: *mut c_void, size: usize) -> bool {
+    let data = unsafe { std::slice::from_raw_parts_mut(data as *mut u8, size) };
+    with_core(|core| core.serialize(data))
+}
+
+/// # Safety
+/// `data` must point to `size` readable bytes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn retro_unserialize(data: *const c_void, size: usize) -> bool {
+    let data = unsafe { std::slice::from_raw_parts(data as *const u8, size) };
+    with_core(|core| core.unserialize(data))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_cheat_reset() {}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_cheat_set(_index: c_uint, _enabled: bool, _code: *const c_char) {}
+
+/// # Safety
+/// `game` must be null or point to a valid `retro_game_info`.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn retro_load_game(game: *const RetroGameInfo) -> bool {
+    let Some(game) = (unsafe { game.as_ref() }) else {
+        return false;
+    };
+    if game.data.is_null() {
+        return false;
+    }
+    let data = unsafe { std::slice::from_raw_parts(game.data as *const u8, game.size) };
+    with_core(|core| core.load_game(data))
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_load_game_special(
+    _game_type: c_uint,
+    _info: *const RetroGameInfo,
+    _num_info: usize,
+) -> bool {
+    false
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_unload_game() {
+    with_core(|core| {
+        core.nes = None;
+        core.memory_descriptors.clear();
+    });
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_get_region() -> c_uint {
+    RETRO_REGION_NTSC
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_get_memory_data(id: c_uint) -> *mut c_void {
+    with_core(|core| match (id, core.nes.as_mut()) {
+        (RETRO_MEMORY_SYSTEM_RAM, Some(nes)) => {
+            nes.get_internal_ram_mut().as_mut_ptr() as *mut c_void
+        }
+        _ => ptr::null_mut(),
+    })
+}
+
+#[unsafe(no_mangle)]
+pub extern "C" fn retro_get_memory_size(id: c_uint) -> usize {
+    with_core(|core| match (id, core.nes.is_some()) {
+        (RETRO_MEMORY_SYSTEM_RAM, true) => INTERNAL_RAM_SIZE,
+        _ => 0,
+    })
+}
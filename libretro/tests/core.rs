@@ -0,0 +1,103 @@
+use nes_rs_libretro::ffi::*;
+use nes_rs_libretro::*;
+use std::cell::RefCell;
+use std::ffi::{c_uint, c_void};
+use std::ptr;
+
+#[derive(Default)]
+struct Host {
+    memory_descriptor_count: Option<c_uint>,
+    video_frames: Vec<(Vec<u32>, c_uint, c_uint)>,
+    audio_frames: usize,
+}
+
+thread_local! {
+    static HOST: RefCell<Host> = RefCell::new(Host::default());
+}
+
+unsafe extern "C" fn environment(cmd: c_uint, data: *mut c_void) -> bool {
+    if cmd == RETRO_ENVIRONMENT_SET_MEMORY_MAPS {
+        let count = unsafe { (*(data as *const RetroMemoryMap)).num_descriptors };
+        HOST.with_borrow_mut(|host| host.memory_descriptor_count = Some(count));
+    }
+    true
+}
+
+unsafe extern "C" fn video_refresh(
+    data: *const c_void,
+    width: c_uint,
+    height: c_uint,
+    pitch: usize,
+) {
+    let pixels =
+        unsafe { std::slice::from_raw_parts(data as *const u32, pitch / 4 * height as usize) };
+    HOST.with_borrow_mut(|host| host.video_frames.push((pixels.to_vec(), width, height)));
+}
+
+unsafe extern "C" fn audio_sample_batch(_data: *const i16, frames: usize) -> usize {
+    HOST.with_borrow_mut(|host| host.audio_frames += frames);
+    frames
+}
+
+unsafe extern "C" fn input_poll() {}
+
+unsafe extern "C" fn input_state(
+    _port: c_uint,
+    _device: c_uint,
+    _index: c_uint,
+    _id: c_uint,
+) -> i16 {
+    0
+}
+
+fn run_frame() -> Vec<u32> {
+    retro_run();
+    let (pixels, width, height) = HOST.with_borrow_mut(|host| host.video_frames.pop().unwrap());
+    assert_eq!((width, height), (256, 240));
+    pixels
+}
+
+#[test]
+fn libretro_core_test() {
+    let rom = std::fs::read("../tests/nestest/nestest.nes").unwrap();
+    assert_eq!(retro_api_version(), 1);
+    retro_set_environment(environment);
+    retro_set_video_refresh(video_refresh);
+    retro_set_audio_sample_batch(audio_sample_batch);
+    retro_set_input_poll(input_poll);
+    retro_set_input_state(input_state);
+    retro_init();
+
+    let mut game_info = RetroGameInfo {
+        path: ptr::null(),
+        data: rom.as_ptr() as *const c_void,
+        size: 8,
+        meta: ptr::null(),
+    };
+    assert!(!unsafe { retro_load_game(&game_info) });
+    game_info.size = rom.len();
+    assert!(unsafe { retro_load_game(&game_info) });
+    HOST.with_borrow(|host| assert_eq!(host.memory_descriptor_count, Some(1)));
+    assert_eq!(retro_get_memory_size(RETRO_MEMORY_SYSTEM_RAM), 0x800);
+    assert!(!retro_get_memory_data(RETRO_MEMORY_SYSTEM_RAM).is_null());
+
+    for _ in 0..10 {
+        run_frame();
+    }
+    HOST.with_borrow(|host| {
+        assert!(host.audio_frames > 0);
+        assert!(host.video_frames.is_empty());
+    });
+
+    let state_size = retro_serialize_size();
+    let mut state = vec![0u8; state_size];
+    assert!(unsafe { retro_serialize(state.as_mut_ptr() as *mut c_void, state.len()) });
+    let frame = run_frame();
+    assert!(frame.iter().any(|&pixel| pixel != frame[0]));
+    assert!(unsafe { retro_unserialize(state.as_ptr() as *const c_void, state.len()) });
+    assert_eq!(run_frame(), frame);
+
+    retro_unload_game();
+    assert!(retro_get_memory_data(RETRO_MEMORY_SYSTEM_RAM).is_null());
+    retro_deinit();
+}
@@ -0,0 +1,116 @@
+use nes_rs::{
+    ControllerCallback, ControllerId, Error, Nes, Snapshot, StdNesControllerButton, ZapperTarget,
+};
+
+// Bits 0-7 hold the buttons of the first controller and bits 8-15 the buttons of the second one,
+// in StdNesControllerButton order.
+#[derive(Default, Clone, Copy)]
+struct Action(u16);
+
+impl ControllerCallback for Action {
+    fn is_button_pressed(&self, id: ControllerId, button: StdNesControllerButton) -> bool {
+        let bit = id as usize * 8 + button as usize;
+        self.0 & (1 << bit) != 0
+    }
+
+    fn is_zapper_trigger_pressed(&self, _id: ControllerId) -> Option<ZapperTarget> {
+        None
+    }
+}
+
+// Restoring a snapshot keeps the video of the frame emulated last, so the observation is stored
+// along with it.
+#[derive(Clone)]
+pub struct State {
+    snapshot: Snapshot,
+    pixels: Vec<u8>,
+}
+
+pub struct Environment {
+    nes: Nes,
+    initial_state: State,
+    restored_pixels: Option<Vec<u8>>,
+    frame_skip: usize,
+    frame: u64,
+}
+
+impl Environment {
+    pub fn new(rom: &[u8], frame_skip: usize) -> Result<Self, Error> {
+        let mut nes = Nes::new();
+        nes.load_rom(rom)?;
+        let mut snapshot = Snapshot::default();
+        nes.save_snapshot(&mut snapshot);
+        let initial_state = State {
+            snapshot,
+            pixels: nes.get_emulation_frame().video.get_pixels().to_vec(),
+        };
+        Ok(Self {
+            nes,
+            initial_state,
+            restored_pixels: None,
+            frame_skip: frame_skip.max(1),
+            frame: 0,
+        })
+    }
+
+    pub fn reset(&mut self) -> Result<(), Error> {
+        self.nes.load_snapshot(&self.initial_state.snapshot)?;
+        self.restored_pixels = Some(self.initial_state.pixels.clone());
+        self.frame = 0;
+        Ok(())
+    }
+
+    pub fn step(&mut self, action: u16) -> Result<(), Error> {
+        self.restored_pixels = None;
+        for _ in 0..self.frame_skip {
+            self.nes.run_single_frame(&Action(action))?;
+            self.frame += 1;
+        }
+        Ok(())
+    }
+
+    pub fn clone_state(&self) -> State {
+        let mut snapshot = Snapshot::default();
+        self.nes.save_snapshot(&mut snapshot);
+        State {
+            snapshot,
+            pixels: self.get_pixels().to_vec(),
+        }
+    }
+
+    pub fn restore_state(&mut self, state: &State) -> Result<(), Error> {
+        self.nes.load_snapshot(&state.snapshot)?;
+        self.restored_pixels = Some(state.pixels.clone());
+        Ok(())
+    }
+
+    pub fn set_initial_state(&mut self, state: State) {
+        self.initial_state = state;
+    }
+
+    pub fn get_pixels(&self) -> &[u8] {
+        self.restored_pixels
+            .as_deref()
+            .unwrap_or_else(|| self.nes.get_emulation_frame().video.get_pixels())
+    }
+
+    pub fn get_ram(&self) -> &[u8] {
+        self.nes.get_internal_ram()
+    }
+
+    pub fn read_memory(&self, address: u16) -> u8 {
+        self.nes.peek_cpu_memory(address)
+    }
+
+    pub fn get_frame_skip(&self) -> usize {
+        self.frame_skip
+    }
+
+    pub fn set_frame_skip(&mut self, frame_skip: usize) {
+        self.frame_skip = frame_skip.max(1);
+    }
+
+    pub fn get_frame(&self) -> u64 {
+        self.frame
+    }
+}
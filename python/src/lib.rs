@@ -0,0 +1,162 @@
+use numpy::{PyArray1, PyArray3, PyArrayMethods};
+use pyo3::create_exception;
+use pyo3::exceptions::PyException;
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+
+pub mod environment;
+
+use environment::Environment;
+
+create_exception!(nes_rs_py, NesError, PyException);
+
+fn to_py_err(error: nes_rs::Error) -> PyErr {
+    NesError::new_err(error.to_string())
+}
+
+/// Snapshot of the emulator taken with `NesEnv.clone_state`.
+#[pyclass(frozen, unsendable, module = "nes_rs_py")]
+struct State(environment::State);
+
+/// Gym style environment, actions are button bitmasks built from the `BUTTON_*` constants
+/// (shifted left by 8 for the second controller).
+#[pyclass(unsendable, module = "nes_rs_py")]
+struct NesEnv {
+    environment: Environment,
+    reward_fn: Option<Py<PyAny>>,
+    done_fn: Option<Py<PyAny>>,
+}
+
+impl NesEnv {
+    fn observation<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        PyArray1::from_slice(py, self.environment.get_pixels()).reshape([
+            nes_rs::VIDEO_FRAME_HEIGHT,
+            nes_rs::VIDEO_FRAME_WIDTH,
+            3,
+        ])
+    }
+
+    fn info<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyDict>> {
+        let info = PyDict::new(py);
+        info.set_item("frame", self.environment.get_frame())?;
+        Ok(info)
+    }
+}
+
+#[pymethods]
+impl NesEnv {
+    #[new]
+    #[pyo3(signature = (rom, frame_skip = 1, reward_fn = None, done_fn = None))]
+    fn new(
+        rom: &[u8],
+        frame_skip: usize,
+        reward_fn: Option<Py<PyAny>>,
+        done_fn: Option<Py<PyAny>>,
+    ) -> PyResult<Self> {
+        Ok(Self {
+            environment: Environment::new(rom, frame_skip).map_err(to_py_err)?,
+            reward_fn,
+            done_fn,
+        })
+    }
+
+    /// Restores the initial state and returns `(observation, info)`.
+    fn reset<'py>(
+        &mut self,
+        py: Python<'py>,
+    ) -> PyResult<(Bound<'py, PyArray3<u8>>, Bound<'py, PyDict>)> {
+        self.environment.reset().map_err(to_py_err)?;
+        Ok((self.observation(py)?, self.info(py)?))
+    }
+
+    /// Holds `action` for `frame_skip` frames and returns
+    /// `(observation, reward, terminated, truncated, info)`.
+    /// `reward_fn` and `done_fn` are called with a copy of the 2KB internal RAM.
+    #[allow(clippy::type_complexity)]
+    fn step<'py>(
+        slf: &Bound<'py, Self>,
+        action: u16,
+    ) -> PyResult<(
+        Bound<'py, PyArray3<u8>>,
+        f64,
+        bool,
+        bool,
+        Bound<'py, PyDict>,
+    )> {
+        let py = slf.py();
+        let (observation, info, ram, reward_fn, done_fn) = {
+            let mut env = slf.borrow_mut();
+            env.environment.step(action).map_err(to_py_err)?;
+            (
+                env.observation(py)?,
+                env.info(py)?,
+                PyArray1::from_slice(py, env.environment.get_ram()),
+                env.reward_fn.as_ref().map(|f| f.clone_ref(py)),
+                env.done_fn.as_ref().map(|f| f.clone_ref(py)),
+            )
+        };
+        let reward = match reward_fn {
+            Some(reward_fn) => reward_fn.call1(py, (ram.clone(),))?.extract(py)?,
+            None => 0.0,
+        };
+        let terminated = match done_fn {
+            Some(done_fn) => done_fn.call1(py, (ram,))?.extract(py)?,
+            None => false,
+        };
+        Ok((observation, reward, terminated, false, info))
+    }
+
+    /// Returns the current frame as a `(240, 256, 3)` RGB array.
+    fn render<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyArray3<u8>>> {
+        self.observation(py)
+    }
+
+    fn clone_state(&self) -> State {
+        State(self.environment.clone_state())
+    }
+
+    fn restore_state(&mut self, state: &State) -> PyResult<()> {
+        self.environment.restore_state(&state.0).map_err(to_py_err)
+    }
+
+    /// Makes `reset` return to `state` instead of the power on state.
+    fn set_initial_state(&mut self, state: &State) {
+        self.environment.set_initial_state(state.0.clone());
+    }
+
+    fn read_memory(&self, address: u16) -> u8 {
+        self.environment.read_memory(address)
+    }
+
+    #[getter]
+    fn ram<'py>(&self, py: Python<'py>) -> Bound<'py, PyArray1<u8>> {
+        PyArray1::from_slice(py, self.environment.get_ram())
+    }
+
+    #[getter]
+    fn frame(&self) -> u64 {
+        self.environment.get_frame()
+    }
+
+    #[getter]
+    fn frame_skip(&self) -> usize {
+        self.environment.get_frame_skip()
+    }
+
+    #[setter]
+    fn set_frame_skip(&mut self, frame_skip: usize) {
+        self.environment.set_frame_skip(frame_skip);
+    }
+}
+
+#[pymodule]
+fn nes_rs_py(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<NesEnv>()?;
+    m.add_class::<State>()?;
+    m.add("NesError", m.py().get_type::<NesError>())?;
+    let buttons = ["A", "B", "SELECT", "START", "UP", "DOWN", "LEFT", "RIGHT"];
+    for (bit, button) in buttons.iter().enumerate() {
+        m.add(format!("BUTTON_{button}"), 1u16 << bit)?;
+    }
+    Ok(())
+}
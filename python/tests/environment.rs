@@ -0,0 +1,54 @@
+use nes_rs_py::environment::Environment;
+
+const START: u16 = 1 << 3;
+
+#[test]
+fn environment_test() {
+    let rom = std::fs::read("../tests/nestest/nestest.nes").unwrap();
+    assert!(Environment::new(&rom[..8], 1).is_err());
+
+    let mut environment = Environment::new(&rom, 4).unwrap();
+    environment.step(0).unwrap();
+    assert_eq!(environment.get_frame(), 4);
+    assert_eq!(environment.get_ram().len(), 0x800);
+
+    let state = environment.clone_state();
+    environment.step(START).unwrap();
+    let pixels = environment.get_pixels().to_vec();
+    let ram = environment.get_ram().to_vec();
+    assert!(pixels.iter().any(|&byte| byte != pixels[0]));
+
+    environment.restore_state(&state).unwrap();
+    environment.step(START).unwrap();
+    assert_eq!(environment.get_pixels(), pixels.as_slice());
+    assert_eq!(environment.get_ram(), ram.as_slice());
+
+    environment.set_initial_state(state);
+    environment.reset().unwrap();
+    assert_eq!(environment.get_frame(), 0);
+    environment.set_frame_skip(1);
+    for _ in 0..4 {
+        environment.step(START).unwrap();
+    }
+    assert_eq!(environment.get_pixels(), pixels.as_slice());
+}
+
+#[test]
+fn reset_test() {
+    let rom = std::fs::read("../tests/nestest/nestest.nes").unwrap();
+    let mut environment = Environment::new(&rom, 10).unwrap();
+    let initial_pixels = environment.get_pixels().to_vec();
+    environment.step(START).unwrap();
+    assert_ne!(environment.get_pixels(), initial_pixels.as_slice());
+
+    environment.reset().unwrap();
+    assert_eq!(environment.get_pixels(), initial_pixels.as_slice());
+
+    environment.step(0).unwrap();
+    let state = environment.clone_state();
+    let pixels = environment.get_pixels().to_vec();
+    environment.set_initial_state(state);
+    environment.step(START).unwrap();
+    environment.reset().unwrap();
+    assert_eq!(environment.get_pixels(), pixels.as_slice());
+}
@@ -1,10 +1,17 @@
+use crate::BuiltinPalette;
 use crate::ControllerCallback;
 use crate::ControllerType;
 use crate::EmulationFrame;
+use crate::NsfInfo;
+use crate::NtscPaletteSettings;
 use crate::StdNesControllerButton;
+use crate::VideoOutputSettings;
+use std::rc::Rc;
 
 pub mod sdl2_imgui_opengl;
 
+pub const STATE_SLOT_COUNT: usize = 10;
+
 #[derive(Clone)]
 pub struct MouseClick {
     pub left_button: bool,
@@ -22,6 +29,39 @@ pub enum Speed {
     Decrease,
 }
 
+#[derive(Clone)]
+pub enum DiskSideSwitch {
+    Next,
+    Eject,
+    Insert(usize),
+}
+
+#[derive(Clone)]
+pub enum NsfTrackSwitch {
+    Next,
+    Previous,
+    Select(usize),
+}
+
+#[derive(Clone, PartialEq)]
+pub enum PaletteSwitch {
+    Builtin(BuiltinPalette),
+    Ntsc(NtscPaletteSettings),
+    File(String),
+}
+
+#[derive(Clone, Copy)]
+pub enum StateSlotAction {
+    Save(usize),
+    Load(usize),
+}
+
+pub struct StateSlot {
+    pub thumbnail: Option<Vec<u8>>,
+    pub timestamp: Option<u64>,
+    pub playtime_frames: Option<u64>,
+}
+
 #[derive(Clone, Default)]
 pub struct FrontendState {
     pub quit: bool,
@@ -29,9 +69,18 @@ pub struct FrontendState {
     pub load_nes_file: Option<String>,
     pub save_state: Option<String>,
     pub load_state: Option<String>,
+    pub state_slot_action: Option<StateSlotAction>,
+    pub load_script: Option<String>,
     pub speed: Option<Speed>,
     pub pause: bool,
     pub switch_controller_type: [Option<ControllerType>; 2],
+    pub switch_disk_side: Option<DiskSideSwitch>,
+    pub switch_nsf_track: Option<NsfTrackSwitch>,
+    pub switch_palette: Option<PaletteSwitch>,
+    pub ntsc_filter: bool,
+    pub run_ahead_frames: u8,
+    pub run_ahead_second_instance: bool,
+    pub video_output_settings: VideoOutputSettings,
     pub audio_volume: f32,
 }
 
@@ -42,6 +91,11 @@ pub struct FrontendControl {
     pub title: Option<String>,
     pub error: Option<String>,
     pub controller_type: [ControllerType; 2],
+    pub disk_side_count: Option<usize>,
+    pub inserted_disk_side: Option<usize>,
+    pub nsf_info: Option<NsfInfo>,
+    pub playchoice_time_expired: bool,
+    pub state_slots: [Option<Rc<StateSlot>>; STATE_SLOT_COUNT],
 }
 
 pub trait Frontend: ControllerCallback {
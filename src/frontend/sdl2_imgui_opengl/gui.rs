@@ -1,8 +1,13 @@
 use super::{MENU_BAR_HEIGHT, MenuBarItem};
 use crate::frontend::sdl2_imgui_opengl::ERROR_BAR_HEIGHT;
 use crate::{
-    ControllerId, ControllerType, DEFAULT_FPS, VIDEO_FRAME_HEIGHT, VIDEO_FRAME_WIDTH,
-    frontend::FrontendControl,
+    BuiltinPalette, ControllerId, ControllerType, DEFAULT_FPS, MAX_RUN_AHEAD_FRAMES,
+    NtscPaletteSettings, Overscan, PixelAspectRatio, SAVE_STATE_THUMBNAIL_HEIGHT,
+    SAVE_STATE_THUMBNAIL_WIDTH, VIDEO_FRAME_HEIGHT, VIDEO_FRAME_WIDTH, VideoOutputSettings,
+    frontend::{
+        DiskSideSwitch, FrontendControl, NsfTrackSwitch, PaletteSwitch, STATE_SLOT_COUNT,
+        StateSlot, StateSlotAction,
+    },
 };
 
 use super::DOUBLE_FPS;
@@ -55,24 +60,15 @@ pub enum VideoSizeControl {
     FullScreen = 5,
 }
 
-impl From<VideoSizeControl> for [u32; 2] {
-    fn from(val: VideoSizeControl) -> Self {
-        if val == VideoSizeControl::FullScreen {
+impl VideoSizeControl {
+    pub fn get_size(self, video_output_settings: &VideoOutputSettings) -> [u32; 2] {
+        if self == VideoSizeControl::FullScreen {
             panic!("VideoSizeControl::FullScreen size can't be converted to [u32;2]")
         }
 
-        let scaling = val as u32;
-        [
-            scaling * VIDEO_FRAME_WIDTH as u32,
-            scaling * VIDEO_FRAME_HEIGHT as u32,
-        ]
-    }
-}
-
-impl From<VideoSizeControl> for [f32; 2] {
-    fn from(val: VideoSizeControl) -> Self {
-        let [width, height]: [u32; 2] = val.into();
-        [width as _, height as _]
+        let scaling = self as u32;
+        let [output_width, output_height] = video_output_settings.get_output_size();
+        [scaling * output_width, scaling * output_height]
     }
 }
 
@@ -133,23 +129,36 @@ impl ControllerConfig {
 }
 pub(super) struct Gui {
     emulation_texture: imgui::TextureId,
+    state_slot_textures: [imgui::TextureId; STATE_SLOT_COUNT],
     fonts: GuiFonts,
     menu_bar_item_selected: [bool; MenuBarItem::Count as usize],
     frontend_control: FrontendControl,
     nes_file_path: Option<String>,
     save_state_path: Option<String>,
     load_state_path: Option<String>,
+    script_path: Option<String>,
+    state_slot_action: Option<StateSlotAction>,
     build_menu_bar: bool,
     fd_load_nes_file: imgui_filedialog::FileDialog,
     fd_save_state: imgui_filedialog::FileDialog,
     fd_load_state: imgui_filedialog::FileDialog,
+    fd_load_palette: imgui_filedialog::FileDialog,
+    fd_load_script: imgui_filedialog::FileDialog,
     pub video_size: [f32; 2],
     pub video_size_control: VideoSizeControl,
     pub previous_video_size_control: VideoSizeControl,
     pub audio_volume: u8,
     pub controllers_setup: bool,
+    pub state_slots_window: bool,
+    pub run_ahead_frames: u8,
     pub controller_configs: [ControllerConfig; 2],
     pub controller_switch: [Option<ControllerType>; 2],
+    pub disk_side_switch: Option<DiskSideSwitch>,
+    pub nsf_track_switch: Option<NsfTrackSwitch>,
+    pub palette_switch: Option<PaletteSwitch>,
+    pub selected_palette: PaletteSwitch,
+    pub ntsc_palette_settings: NtscPaletteSettings,
+    pub overscan: Overscan,
     pub pause: bool,
     pub mouse_click: MouseClick,
     pub crosshair: bool,
@@ -171,33 +180,90 @@ fn create_file_dialog(
         ])
 }
 
+fn format_timestamp(timestamp: u64) -> String {
+    // Civil date from days since the UNIX epoch, see https://howardhinnant.github.io/date_algorithms.html
+    let days = (timestamp / 86400) as i64 + 719468;
+    let seconds = timestamp % 86400;
+    let era = days.div_euclid(146097);
+    let day_of_era = days.rem_euclid(146097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02} {:02}:{:02} UTC",
+        year,
+        month,
+        day,
+        seconds / 3600,
+        seconds % 3600 / 60
+    )
+}
+
+fn format_playtime(playtime_frames: u64) -> String {
+    let seconds = playtime_frames / DEFAULT_FPS as u64;
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds % 3600 / 60,
+        seconds % 60
+    )
+}
+
+fn get_nsf_track_label(nsf_info: &crate::NsfInfo, track: usize) -> String {
+    match nsf_info.track_labels.get(track) {
+        Some(label) if !label.is_empty() => format!("{}. {}", track + 1, label),
+        _ => format!("Track {}", track + 1),
+    }
+}
+
 impl Gui {
-    pub fn new(emulation_texture: imgui::TextureId, fonts: GuiFonts) -> Self {
+    pub fn new(
+        emulation_texture: imgui::TextureId,
+        state_slot_textures: [imgui::TextureId; STATE_SLOT_COUNT],
+        fonts: GuiFonts,
+    ) -> Self {
         let nes_file_label = ImString::new("nes_file");
         let open_nes_file_label = ImString::new("Open NES file");
-        let open_nes_file_filters_label = ImString::new(".nes,.NES");
+        let open_nes_file_filters_label =
+            ImString::new(".nes,.NES,.fds,.FDS,.nsf,.NSF,.nsfe,.NSFE");
         let save_state_label = ImString::new("save_state");
         let save_state_title = ImString::new("Save Emulation state");
         let save_state_filters = ImString::new(".nesrs");
         let load_state_label = ImString::new("load_state");
         let load_state_title = ImString::new("Load Emulation state");
         let load_state_filters = ImString::new(".nesrs,.NESRS");
+        let load_palette_label = ImString::new("load_palette");
+        let load_palette_title = ImString::new("Load Palette file");
+        let load_palette_filters = ImString::new(".pal,.PAL");
+        let load_script_label = ImString::new("load_script");
+        let load_script_title = ImString::new("Load Script");
+        let load_script_filters = ImString::new(".rhai,.RHAI");
         Self {
             emulation_texture,
+            state_slot_textures,
             menu_bar_item_selected: Default::default(),
             fonts,
             nes_file_path: None,
             save_state_path: None,
             load_state_path: None,
+            script_path: None,
+            state_slot_action: None,
             frontend_control: FrontendControl {
                 ..Default::default()
             },
             video_size_control: VideoSizeControl::Double,
             previous_video_size_control: VideoSizeControl::Double,
-            video_size: [
-                VIDEO_FRAME_WIDTH as f32 * 2.0,
-                VIDEO_FRAME_HEIGHT as f32 * 2.0,
-            ],
+            video_size: VideoSizeControl::Double
+                .get_size(&Default::default())
+                .map(|size| size as f32),
             build_menu_bar: Default::default(),
             fd_load_nes_file: create_file_dialog(
                 nes_file_label.as_ref(),
@@ -214,10 +280,28 @@ impl Gui {
                 load_state_title.as_ref(),
                 load_state_filters.as_ref(),
             ),
+            fd_load_palette: create_file_dialog(
+                load_palette_label.as_ref(),
+                load_palette_title.as_ref(),
+                load_palette_filters.as_ref(),
+            ),
+            fd_load_script: create_file_dialog(
+                load_script_label.as_ref(),
+                load_script_title.as_ref(),
+                load_script_filters.as_ref(),
+            ),
             audio_volume: 100,
             controller_configs: [ControllerConfig::new(0), ControllerConfig::new(1)],
             controllers_setup: false,
+            state_slots_window: false,
+            run_ahead_frames: 0,
             controller_switch: [None, None],
+            disk_side_switch: None,
+            nsf_track_switch: None,
+            palette_switch: None,
+            selected_palette: PaletteSwitch::Builtin(BuiltinPalette::Default),
+            ntsc_palette_settings: Default::default(),
+            overscan: Default::default(),
             pause: false,
             mouse_click: MouseClick {
                 left_button: false,
@@ -234,6 +318,26 @@ impl Gui {
         self.controller_switch[player as usize].take()
     }
 
+    pub fn get_disk_side_switch(&mut self) -> Option<DiskSideSwitch> {
+        self.disk_side_switch.take()
+    }
+
+    pub fn get_nsf_track_switch(&mut self) -> Option<NsfTrackSwitch> {
+        self.nsf_track_switch.take()
+    }
+
+    pub fn get_palette_switch(&mut self) -> Option<PaletteSwitch> {
+        self.palette_switch.take()
+    }
+
+    pub fn get_state_slot_action(&mut self) -> Option<StateSlotAction> {
+        self.state_slot_action.take()
+    }
+
+    pub fn get_script_path(&mut self) -> Option<String> {
+        self.script_path.take()
+    }
+
     pub fn get_rom_path(&mut self) -> Option<String> {
         #[cfg(target_os = "emscripten")]
         {
@@ -338,6 +442,17 @@ impl Gui {
                     self.update_menu_item_status(ui, LoadState);
                 }
 
+                ui.menu_item_config("State Slots")
+                    .shortcut("Ctrl+T")
+                    .selected(self.state_slots_window)
+                    .build();
+                self.update_menu_item_status(ui, StateSlots);
+
+                ui.menu_item_config("Load Script").build();
+                if !self.is_menu_bar_item_selected(LoadScript) {
+                    self.update_menu_item_status(ui, LoadScript);
+                }
+
                 ui.menu_item_config("Quit").shortcut("Alt+F5").build();
                 self.update_menu_item_status(ui, Quit);
             }
@@ -355,6 +470,71 @@ impl Gui {
                     .build();
                 self.update_menu_item_status(ui, Pause);
 
+                #[allow(clippy::redundant_pattern_matching)]
+                if let Some(_) = ui.begin_menu_with_enabled(
+                    "Disk System",
+                    self.frontend_control.disk_side_count.is_some(),
+                ) {
+                    let disk_side_count = self.frontend_control.disk_side_count.unwrap_or(0);
+                    let inserted_disk_side = self.frontend_control.inserted_disk_side;
+                    for side in 0..disk_side_count {
+                        let label = format!(
+                            "Disk {} Side {}",
+                            side / 2 + 1,
+                            if side % 2 == 0 { 'A' } else { 'B' }
+                        );
+                        if ui
+                            .menu_item_config(label)
+                            .selected(inserted_disk_side == Some(side))
+                            .build()
+                        {
+                            self.disk_side_switch = Some(DiskSideSwitch::Insert(side));
+                        }
+                    }
+
+                    ui.separator();
+
+                    ui.menu_item_config("Switch Side")
+                        .shortcut("Ctrl+D")
+                        .build();
+                    self.update_menu_item_status(ui, SwitchDiskSide);
+
+                    ui.menu_item_config("Eject")
+                        .enabled(inserted_disk_side.is_some())
+                        .build();
+                    self.update_menu_item_status(ui, EjectDisk);
+                }
+
+                #[allow(clippy::redundant_pattern_matching)]
+                if let Some(_) = ui.begin_menu_with_enabled(
+                    "Music Player",
+                    self.frontend_control.nsf_info.is_some(),
+                ) {
+                    if let Some(nsf_info) = &self.frontend_control.nsf_info {
+                        for track in 0..nsf_info.track_count {
+                            if ui
+                                .menu_item_config(get_nsf_track_label(nsf_info, track))
+                                .selected(nsf_info.current_track == track)
+                                .build()
+                            {
+                                self.nsf_track_switch = Some(NsfTrackSwitch::Select(track));
+                            }
+                        }
+                    }
+
+                    ui.separator();
+
+                    ui.menu_item_config("Next Track")
+                        .shortcut("Ctrl+Right")
+                        .build();
+                    self.update_menu_item_status(ui, NextNsfTrack);
+
+                    ui.menu_item_config("Previous Track")
+                        .shortcut("Ctrl+Left")
+                        .build();
+                    self.update_menu_item_status(ui, PreviousNsfTrack);
+                }
+
                 #[allow(clippy::redundant_pattern_matching)]
                 if let Some(_) = ui.begin_menu("Speed") {
                     let target_fps = self.frontend_control.target_fps;
@@ -396,6 +576,31 @@ impl Gui {
                         .build();
                     self.update_menu_item_status(ui, SpeedDecrease);
                 }
+
+                #[allow(clippy::redundant_pattern_matching)]
+                if let Some(_) = ui.begin_menu("Run-Ahead") {
+                    for frames in 0..=MAX_RUN_AHEAD_FRAMES {
+                        let label = match frames {
+                            0 => "Disabled".to_string(),
+                            1 => "1 Frame".to_string(),
+                            _ => format!("{} Frames", frames),
+                        };
+                        if ui
+                            .menu_item_config(label)
+                            .selected(self.run_ahead_frames == frames)
+                            .build()
+                        {
+                            self.run_ahead_frames = frames;
+                        }
+                    }
+
+                    ui.separator();
+
+                    ui.menu_item_config("Use Second Instance")
+                        .selected(self.is_menu_bar_item_selected(RunAheadSecondInstance))
+                        .build();
+                    self.toggle_menu_bar_item_if_clicked(ui, RunAheadSecondInstance);
+                }
             }
 
             #[allow(clippy::redundant_pattern_matching)]
@@ -426,6 +631,75 @@ impl Gui {
                         .build();
                     self.update_menu_item_status(ui, VideoSizeFullScreen);
                 }
+
+                #[allow(clippy::redundant_pattern_matching)]
+                if let Some(_) = ui.begin_menu("Palette") {
+                    for builtin_palette in BuiltinPalette::ALL {
+                        let palette = PaletteSwitch::Builtin(builtin_palette);
+                        if ui
+                            .menu_item_config(builtin_palette.get_name())
+                            .selected(self.selected_palette == palette)
+                            .build()
+                        {
+                            self.selected_palette = palette.clone();
+                            self.palette_switch = Some(palette);
+                        }
+                    }
+
+                    ui.separator();
+
+                    let is_ntsc_selected = matches!(self.selected_palette, PaletteSwitch::Ntsc(_));
+                    if ui
+                        .menu_item_config("NTSC Generated")
+                        .selected(is_ntsc_selected)
+                        .build()
+                        || (is_ntsc_selected && self.build_ntsc_palette_sliders(ui))
+                    {
+                        let palette = PaletteSwitch::Ntsc(self.ntsc_palette_settings);
+                        self.selected_palette = palette.clone();
+                        self.palette_switch = Some(palette);
+                    }
+
+                    ui.separator();
+
+                    ui.menu_item_config("Load Palette File")
+                        .selected(matches!(self.selected_palette, PaletteSwitch::File(_)))
+                        .build();
+                    if !self.is_menu_bar_item_selected(LoadPaletteFile) {
+                        self.update_menu_item_status(ui, LoadPaletteFile);
+                    }
+                }
+
+                ui.menu_item_config("NTSC Filter")
+                    .selected(self.is_menu_bar_item_selected(NtscFilter))
+                    .build();
+                self.toggle_menu_bar_item_if_clicked(ui, NtscFilter);
+
+                ui.separator();
+
+                #[allow(clippy::redundant_pattern_matching)]
+                if let Some(_) = ui.begin_menu("Overscan") {
+                    self.build_overscan_sliders(ui);
+
+                    ui.separator();
+
+                    if ui.menu_item("NTSC Default") {
+                        self.overscan = Overscan::default();
+                    }
+                    if ui.menu_item("None") {
+                        self.overscan = Overscan::none();
+                    }
+                }
+
+                ui.menu_item_config("8:7 Pixel Aspect Ratio")
+                    .selected(self.is_menu_bar_item_selected(NtscPixelAspectRatio))
+                    .build();
+                self.toggle_menu_bar_item_if_clicked(ui, NtscPixelAspectRatio);
+
+                ui.menu_item_config("Integer Scaling")
+                    .selected(self.is_menu_bar_item_selected(IntegerScaling))
+                    .build();
+                self.toggle_menu_bar_item_if_clicked(ui, IntegerScaling);
             }
 
             #[allow(clippy::redundant_pattern_matching)]
@@ -490,6 +764,10 @@ impl Gui {
         };
 
         self.crosshair = false;
+        let video_output_settings = self.get_video_output_settings();
+        let (image_position, image_size) = video_output_settings.get_viewport(self.video_size);
+        let crop_rect = video_output_settings.get_crop_rect(VIDEO_FRAME_WIDTH);
+        let background = ui.push_style_color(imgui::StyleColor::WindowBg, [0.0, 0.0, 0.0, 1.0]);
         ui.window("emulation")
             .position([0.0, vertical_offset], imgui::Condition::Always)
             .no_decoration()
@@ -497,7 +775,8 @@ impl Gui {
             .scroll_bar(false)
             .bring_to_front_on_focus(false)
             .build(|| {
-                imgui::Image::new(self.emulation_texture, self.video_size).build(ui);
+                ui.set_cursor_pos(image_position);
+                imgui::Image::new(self.emulation_texture, image_size).build(ui);
                 self.mouse_click.left_button = false;
                 self.mouse_click.right_button = false;
                 let zapper_active =
@@ -507,11 +786,18 @@ impl Gui {
                     let io = ui.io();
                     let mouse_pos = io.mouse_pos; // [f32;
                     let window_pos = ui.window_pos();
-                    let rel_pos = [mouse_pos[0] - window_pos[0], mouse_pos[1] - window_pos[1]];
-                    let tex_x = (rel_pos[0] / self.video_size[0] * nes_rs::VIDEO_FRAME_WIDTH as f32)
-                        .floor() as usize;
-                    let tex_y = (rel_pos[1] / self.video_size[1] * crate::VIDEO_FRAME_HEIGHT as f32)
-                        .floor() as usize;
+                    let rel_pos = [
+                        mouse_pos[0] - window_pos[0] - image_position[0],
+                        mouse_pos[1] - window_pos[1] - image_position[1],
+                    ];
+                    let to_texture = |pos: f32, size: f32, offset: usize, length: usize| {
+                        (offset as f32 + (pos / size * length as f32).floor())
+                            .clamp(offset as f32, (offset + length - 1) as f32)
+                            as usize
+                    };
+                    let tex_x = to_texture(rel_pos[0], image_size[0], crop_rect.x, crop_rect.width);
+                    let tex_y =
+                        to_texture(rel_pos[1], image_size[1], crop_rect.y, crop_rect.height);
 
                     if ui.is_mouse_clicked(imgui::MouseButton::Left) {
                         self.mouse_click.left_button = true;
@@ -529,7 +815,7 @@ impl Gui {
                     let base_circle_radius = 5.0;
                     let base_circle_thickness = 1.5;
 
-                    let factor = self.video_size[0] / VIDEO_FRAME_WIDTH as f32;
+                    let factor = image_size[1] / crop_rect.height as f32;
 
                     let line_len = base_line_len * factor;
                     let line_thickness = base_line_thickness * factor;
@@ -562,9 +848,143 @@ impl Gui {
                         .build();
                 }
             });
+        background.pop();
         style.pop();
     }
 
+    fn build_nsf_player_window(&mut self, ui: &imgui::Ui) {
+        let Some(nsf_info) = &self.frontend_control.nsf_info else {
+            return;
+        };
+        let font = ui.push_font(self.fonts[GuiFont::MenuBar as usize]);
+        let vertical_offset = if self.build_menu_bar {
+            MENU_BAR_HEIGHT as f32
+        } else {
+            0.0
+        };
+        let mut track_switch = None;
+        ui.window("nsf_player")
+            .position([0.0, vertical_offset], imgui::Condition::Always)
+            .size(self.video_size, imgui::Condition::Always)
+            .bg_alpha(0.0)
+            .no_decoration()
+            .build(|| {
+                ui.set_cursor_pos([20.0, 40.0]);
+                ui.group(|| {
+                    ui.text(format!("Title: {}", nsf_info.title));
+                    ui.text(format!("Artist: {}", nsf_info.artist));
+                    ui.text(format!("Copyright: {}", nsf_info.copyright));
+                    ui.separator();
+                    ui.text(format!(
+                        "Track {}/{}",
+                        nsf_info.current_track + 1,
+                        nsf_info.track_count
+                    ));
+                    ui.text(get_nsf_track_label(nsf_info, nsf_info.current_track));
+                    if ui.button("<<") {
+                        track_switch = Some(NsfTrackSwitch::Previous);
+                    }
+                    ui.same_line();
+                    if ui.button(">>") {
+                        track_switch = Some(NsfTrackSwitch::Next);
+                    }
+                });
+            });
+        if track_switch.is_some() {
+            self.nsf_track_switch = track_switch;
+        }
+        font.pop();
+    }
+
+    fn build_state_slot(
+        &self,
+        ui: &imgui::Ui,
+        slot: usize,
+        state_slot: Option<&StateSlot>,
+    ) -> Option<StateSlotAction> {
+        let mut action = None;
+        let thumbnail_size = [
+            SAVE_STATE_THUMBNAIL_WIDTH as f32,
+            SAVE_STATE_THUMBNAIL_HEIGHT as f32,
+        ];
+        ui.group(|| {
+            ui.text(format!("Slot {} (Ctrl+{})", slot + 1, (slot + 1) % 10));
+            match state_slot {
+                Some(state_slot) if state_slot.thumbnail.is_some() => {
+                    imgui::Image::new(self.state_slot_textures[slot], thumbnail_size).build(ui)
+                }
+                _ => ui.dummy(thumbnail_size),
+            }
+            match state_slot {
+                Some(state_slot) => {
+                    ui.text(
+                        state_slot
+                            .timestamp
+                            .map_or_else(String::new, format_timestamp),
+                    );
+                    ui.text(format!(
+                        "Playtime {}",
+                        format_playtime(state_slot.playtime_frames.unwrap_or(0))
+                    ));
+                }
+                None => {
+                    ui.text("Empty");
+                    ui.text("");
+                }
+            }
+            if ui.small_button(format!("Save##slot{}", slot)) {
+                action = Some(StateSlotAction::Save(slot));
+            }
+            ui.same_line();
+            ui.disabled(state_slot.is_none(), || {
+                if ui.small_button(format!("Load##slot{}", slot)) {
+                    action = Some(StateSlotAction::Load(slot));
+                }
+            });
+        });
+        action
+    }
+
+    fn build_state_slots_window(&mut self, ui: &imgui::Ui) {
+        if !self.state_slots_window {
+            return;
+        }
+        let vertical_offset = if self.build_menu_bar {
+            MENU_BAR_HEIGHT as f32
+        } else {
+            0.0
+        };
+        let style = ui.push_style_var(imgui::StyleVar::WindowPadding([8.0, 8.0]));
+        let mut opened = true;
+        let mut action = None;
+        ui.window("State Slots")
+            .opened(&mut opened)
+            .position([0.0, vertical_offset], imgui::Condition::Always)
+            .size(self.video_size, imgui::Condition::Always)
+            .collapsible(false)
+            .resizable(false)
+            .movable(false)
+            .build(|| {
+                let slot_width = SAVE_STATE_THUMBNAIL_WIDTH as f32 + 16.0;
+                let columns = ((ui.content_region_avail()[0] / slot_width) as usize).max(1);
+                for (slot, state_slot) in self.frontend_control.state_slots.iter().enumerate() {
+                    if slot % columns != 0 {
+                        ui.same_line_with_pos((slot % columns) as f32 * slot_width + 8.0);
+                    }
+                    if let Some(slot_action) =
+                        self.build_state_slot(ui, slot, state_slot.as_deref())
+                    {
+                        action = Some(slot_action);
+                    }
+                }
+            });
+        style.pop();
+        if action.is_some() {
+            self.state_slot_action = action;
+        }
+        self.state_slots_window = opened && !matches!(action, Some(StateSlotAction::Load(_)));
+    }
+
     fn build_fps_counter(&self, ui: &imgui::Ui) {
         use imgui::ImString;
         let font = ui.push_font(self.fonts[GuiFont::FpsCounter as usize]);
@@ -654,6 +1074,99 @@ impl Gui {
         }
     }
 
+    fn build_load_script_file_explorer(&mut self) {
+        if self.is_menu_bar_item_selected(MenuBarItem::LoadScript) {
+            self.toggle_menu_bar_item(MenuBarItem::LoadScript);
+            self.is_any_file_explorer_open = true;
+            self.fd_load_script.open_modal();
+        }
+        if self.fd_load_script.display() {
+            if self.fd_load_script.is_ok() {
+                let file = &self.fd_load_script.selection().unwrap().files()[0];
+                self.script_path = Some(file.to_str().unwrap().to_owned());
+            }
+            self.fd_load_script.close();
+            self.is_any_file_explorer_open = false;
+        }
+    }
+
+    fn build_ntsc_palette_sliders(&mut self, ui: &imgui::Ui) -> bool {
+        let settings = &mut self.ntsc_palette_settings;
+        let mut changed = false;
+        ui.child_window("ntsc_palette")
+            .size([240.0, 5.0 * (ui.current_font_size() + 8.0)])
+            .border(false)
+            .scroll_bar(false)
+            .build(|| {
+                changed |= ui
+                    .slider_config("Hue", -45.0, 45.0)
+                    .display_format("%.1f")
+                    .build(&mut settings.hue);
+                changed |= ui
+                    .slider_config("Saturation", 0.0, 2.0)
+                    .display_format("%.2f")
+                    .build(&mut settings.saturation);
+                changed |= ui
+                    .slider_config("Contrast", 0.5, 1.5)
+                    .display_format("%.2f")
+                    .build(&mut settings.contrast);
+                changed |= ui
+                    .slider_config("Brightness", -0.5, 0.5)
+                    .display_format("%.2f")
+                    .build(&mut settings.brightness);
+                changed |= ui
+                    .slider_config("Gamma", 0.5, 2.0)
+                    .display_format("%.2f")
+                    .build(&mut settings.gamma);
+            });
+        changed
+    }
+
+    fn build_overscan_sliders(&mut self, ui: &imgui::Ui) {
+        let overscan = &mut self.overscan;
+        ui.child_window("overscan")
+            .size([240.0, 4.0 * (ui.current_font_size() + 8.0)])
+            .border(false)
+            .scroll_bar(false)
+            .build(|| {
+                ui.slider("Top", 0, 32, &mut overscan.top);
+                ui.slider("Bottom", 0, 32, &mut overscan.bottom);
+                ui.slider("Left", 0, 32, &mut overscan.left);
+                ui.slider("Right", 0, 32, &mut overscan.right);
+            });
+    }
+
+    pub fn get_video_output_settings(&self) -> VideoOutputSettings {
+        VideoOutputSettings {
+            overscan: self.overscan,
+            pixel_aspect_ratio: if self.is_menu_bar_item_selected(MenuBarItem::NtscPixelAspectRatio)
+            {
+                PixelAspectRatio::Ntsc
+            } else {
+                PixelAspectRatio::Square
+            },
+            integer_scaling: self.is_menu_bar_item_selected(MenuBarItem::IntegerScaling),
+        }
+    }
+
+    fn build_load_palette_file_explorer(&mut self) {
+        if self.is_menu_bar_item_selected(MenuBarItem::LoadPaletteFile) {
+            self.toggle_menu_bar_item(MenuBarItem::LoadPaletteFile);
+            self.is_any_file_explorer_open = true;
+            self.fd_load_palette.open_modal();
+        }
+        if self.fd_load_palette.display() {
+            if self.fd_load_palette.is_ok() {
+                let file = &self.fd_load_palette.selection().unwrap().files()[0];
+                let palette = PaletteSwitch::File(file.to_str().unwrap().to_owned());
+                self.selected_palette = palette.clone();
+                self.palette_switch = Some(palette);
+            }
+            self.fd_load_palette.close();
+            self.is_any_file_explorer_open = false;
+        }
+    }
+
     pub fn try_get_key_selection(&mut self, event: &sdl2::event::Event) {
         if let sdl2::event::Event::KeyDown {
             scancode, keymod, ..
@@ -735,7 +1248,9 @@ impl Gui {
 
     fn build_error_bar(&mut self, ui: &imgui::Ui) {
         if self.video_size_control != VideoSizeControl::FullScreen {
-            let [video_width, video_height]: [u32; 2] = self.video_size_control.into();
+            let [video_width, video_height] = self
+                .video_size_control
+                .get_size(&self.get_video_output_settings());
             let style = ui.push_style_var(imgui::StyleVar::WindowBorderSize(0.0));
             let _bg_color = [255.0, 0.0, 0.0, 200.0];
             let y_pos = video_height as f32 + MENU_BAR_HEIGHT as f32;
@@ -749,6 +1264,8 @@ impl Gui {
                 .build(|| {
                     if let Some(error) = &self.frontend_control.error {
                         ui.text_colored([255.0, 0.0, 0.0, 255.0], error);
+                    } else if self.frontend_control.playchoice_time_expired {
+                        ui.text("PlayChoice-10 time is up, press 5 to insert a coin");
                     }
                 });
             style.pop();
@@ -766,11 +1283,15 @@ impl Gui {
             self.build_menu_bar(ui);
         }
         self.build_emulation_window(ui);
+        self.build_nsf_player_window(ui);
+        self.build_state_slots_window(ui);
         self.build_fps_counter(ui);
         self.build_error_bar(ui);
         self.build_load_nes_file_explorer();
         self.build_save_state_file_explorer();
         self.build_load_state_file_explorer();
+        self.build_load_palette_file_explorer();
+        self.build_load_script_file_explorer();
 
         style_padding.pop();
         style_border.pop();
@@ -1,11 +1,27 @@
 use super::MenuBarItem;
+use crate::frontend::{STATE_SLOT_COUNT, StateSlotAction};
 use KeyboardShortcut::*;
 use sdl2::keyboard::Mod;
 use sdl2::keyboard::Scancode;
 
+const STATE_SLOT_KEYS: [Scancode; STATE_SLOT_COUNT] = [
+    Scancode::Num1,
+    Scancode::Num2,
+    Scancode::Num3,
+    Scancode::Num4,
+    Scancode::Num5,
+    Scancode::Num6,
+    Scancode::Num7,
+    Scancode::Num8,
+    Scancode::Num9,
+    Scancode::Num0,
+];
+
+#[derive(Clone, Copy)]
 enum KeyboardShortcut {
     Single(Scancode),
     LeftCtrl(Scancode),
+    LeftCtrlShift(Scancode),
 }
 
 fn shortcut_to_menu_bar_item(key: KeyboardShortcut) -> Option<MenuBarItem> {
@@ -14,7 +30,11 @@ fn shortcut_to_menu_bar_item(key: KeyboardShortcut) -> Option<MenuBarItem> {
         LeftCtrl(Scancode::O) => Some(MenuBarItem::LoadNesFile),
         LeftCtrl(Scancode::S) => Some(MenuBarItem::SaveState),
         LeftCtrl(Scancode::L) => Some(MenuBarItem::LoadState),
+        LeftCtrl(Scancode::T) => Some(MenuBarItem::StateSlots),
         LeftCtrl(Scancode::P) => Some(MenuBarItem::Pause),
+        LeftCtrl(Scancode::D) => Some(MenuBarItem::SwitchDiskSide),
+        LeftCtrl(Scancode::Right) => Some(MenuBarItem::NextNsfTrack),
+        LeftCtrl(Scancode::Left) => Some(MenuBarItem::PreviousNsfTrack),
         #[cfg(not(target_os = "emscripten"))]
         LeftCtrl(Scancode::Equals) => Some(MenuBarItem::SpeedIncrease),
         #[cfg(not(target_os = "emscripten"))]
@@ -32,25 +52,48 @@ fn shortcut_to_menu_bar_item(key: KeyboardShortcut) -> Option<MenuBarItem> {
     }
 }
 
-fn is_shortcut(scancode: Scancode, key_mod: Mod) -> Option<MenuBarItem> {
-    let is_left_ctrl = Mod::LCTRLMOD & key_mod == Mod::LCTRLMOD;
-    if is_left_ctrl {
-        shortcut_to_menu_bar_item(LeftCtrl(scancode))
+fn shortcut_to_state_slot_action(key: KeyboardShortcut) -> Option<StateSlotAction> {
+    let (scancode, is_save) = match key {
+        LeftCtrl(scancode) => (scancode, false),
+        LeftCtrlShift(scancode) => (scancode, true),
+        Single(_) => return None,
+    };
+    let slot = STATE_SLOT_KEYS.iter().position(|&key| key == scancode)?;
+    if is_save {
+        Some(StateSlotAction::Save(slot))
     } else {
-        shortcut_to_menu_bar_item(Single(scancode))
+        Some(StateSlotAction::Load(slot))
+    }
+}
+
+fn get_shortcut(scancode: Scancode, key_mod: Mod) -> KeyboardShortcut {
+    let is_left_ctrl = Mod::LCTRLMOD & key_mod == Mod::LCTRLMOD;
+    let is_shift = key_mod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+    match (is_left_ctrl, is_shift) {
+        (true, true) => LeftCtrlShift(scancode),
+        (true, false) => LeftCtrl(scancode),
+        (false, _) => Single(scancode),
     }
 }
 #[derive(Default)]
 pub(super) struct KeyboardShortcuts {
     menu_bar_item_selected: [bool; MenuBarItem::Count as usize],
+    state_slot_action: Option<StateSlotAction>,
 }
 impl KeyboardShortcuts {
     pub(super) fn is_menu_bar_item_selected(&self, item: MenuBarItem) -> bool {
         self.menu_bar_item_selected[item as usize]
     }
 
+    pub(super) fn get_state_slot_action(&self) -> Option<StateSlotAction> {
+        self.state_slot_action
+    }
+
     pub fn update(&mut self, scancode: Scancode, key_mod: Mod) {
-        if let Some(item) = is_shortcut(scancode, key_mod) {
+        let shortcut = get_shortcut(scancode, key_mod);
+        if let Some(action) = shortcut_to_state_slot_action(shortcut) {
+            self.state_slot_action = Some(action);
+        } else if let Some(item) = shortcut_to_menu_bar_item(shortcut) {
             self.menu_bar_item_selected[item as usize] = true;
         }
     }
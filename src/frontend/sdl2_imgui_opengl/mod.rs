@@ -2,11 +2,13 @@ mod gui;
 mod keyboard_shortcuts;
 
 use std::default::Default;
+use std::rc::Rc;
 use std::{borrow::BorrowMut, collections::HashMap};
 
 use self::gui::VideoSizeControl;
 
 use crate::frontend;
+use crate::frontend::{STATE_SLOT_COUNT, StateSlot};
 
 use crate::ControllerId;
 use crate::EmulationFrame;
@@ -28,9 +30,15 @@ pub enum MenuBarItem {
     LoadNesFile,
     SaveState,
     LoadState,
+    StateSlots,
+    LoadScript,
     Quit,
     PowerCycle,
     Pause,
+    SwitchDiskSide,
+    EjectDisk,
+    NextNsfTrack,
+    PreviousNsfTrack,
     SpeedNormal,
     SpeedDouble,
     SpeedHalf,
@@ -44,6 +52,11 @@ pub enum MenuBarItem {
     VideoSizeTriple,
     VideoSizeQuadrupal,
     VideoSizeFullScreen,
+    LoadPaletteFile,
+    NtscFilter,
+    RunAheadSecondInstance,
+    NtscPixelAspectRatio,
+    IntegerScaling,
     ControllersSetup,
     Count,
 }
@@ -60,9 +73,13 @@ pub struct Sdl2ImGuiOpenGlFrontend {
     gui: gui::Gui,
     cancel: bool,
     is_video_size_change_pending: bool,
+    video_output_settings: crate::VideoOutputSettings,
     keyboard_shortcuts: keyboard_shortcuts::KeyboardShortcuts,
     frame: u128,
     sdl2_context: sdl2::Sdl,
+    emulation_texture: GLuint,
+    state_slot_textures: [GLuint; STATE_SLOT_COUNT],
+    state_slots: [Option<Rc<StateSlot>>; STATE_SLOT_COUNT],
 }
 
 impl Sdl2ImGuiOpenGlFrontend {
@@ -94,7 +111,8 @@ impl Sdl2ImGuiOpenGlFrontend {
                 gl_attr.set_context_version(4, 3);
             };
         }
-        let [video_width, video_height]: [u32; 2] = gui::VideoSizeControl::Double.into();
+        let [video_width, video_height] =
+            gui::VideoSizeControl::Double.get_size(&Default::default());
         let mut window = video_subsys
             .window(
                 "NES-RS",
@@ -154,7 +172,22 @@ impl Sdl2ImGuiOpenGlFrontend {
             gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::NEAREST as i32);
         }
 
-        let gui_builder = gui::Gui::new(imgui::TextureId::from(emulation_texture as usize), fonts);
+        let mut state_slot_textures: [GLuint; STATE_SLOT_COUNT] = [0; STATE_SLOT_COUNT];
+        unsafe {
+            gl::GenTextures(STATE_SLOT_COUNT as _, state_slot_textures.as_mut_ptr());
+            for texture in state_slot_textures {
+                gl::BindTexture(gl::TEXTURE_2D, texture);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MIN_FILTER, gl::LINEAR as i32);
+                gl::TexParameteri(gl::TEXTURE_2D, gl::TEXTURE_MAG_FILTER, gl::LINEAR as i32);
+            }
+            gl::BindTexture(gl::TEXTURE_2D, emulation_texture);
+        }
+
+        let gui_builder = gui::Gui::new(
+            imgui::TextureId::from(emulation_texture as usize),
+            state_slot_textures.map(|texture| imgui::TextureId::from(texture as usize)),
+            fonts,
+        );
 
         Sdl2ImGuiOpenGlFrontend {
             maybe_audio_queue,
@@ -169,8 +202,12 @@ impl Sdl2ImGuiOpenGlFrontend {
             keyboard_shortcuts: Default::default(),
             cancel: false,
             is_video_size_change_pending: false,
+            video_output_settings: Default::default(),
             frame: 0,
             sdl2_context,
+            emulation_texture,
+            state_slot_textures,
+            state_slots: Default::default(),
         }
     }
 
@@ -180,10 +217,34 @@ impl Sdl2ImGuiOpenGlFrontend {
         io_state.load_nes_file = self.gui.get_rom_path();
         io_state.save_state = self.gui.get_save_state_path();
         io_state.load_state = self.gui.get_load_state_path();
+        io_state.state_slot_action = self
+            .keyboard_shortcuts
+            .get_state_slot_action()
+            .or(self.gui.get_state_slot_action());
+        io_state.load_script = self.gui.get_script_path();
+        io_state.switch_palette = self.gui.get_palette_switch();
+        io_state.ntsc_filter = self.is_menu_bar_item_selected(MenuBarItem::NtscFilter);
+        io_state.run_ahead_frames = self.gui.run_ahead_frames;
+        io_state.run_ahead_second_instance =
+            self.is_menu_bar_item_selected(MenuBarItem::RunAheadSecondInstance);
         io_state.switch_controller_type = [
             self.gui.get_controller_switch(ControllerId::Controller1),
             self.gui.get_controller_switch(ControllerId::Controller2),
         ];
+        io_state.switch_disk_side = self.gui.get_disk_side_switch();
+        if self.is_menu_bar_item_selected(MenuBarItem::SwitchDiskSide) {
+            io_state.switch_disk_side = Some(frontend::DiskSideSwitch::Next);
+        }
+        if self.is_menu_bar_item_selected(MenuBarItem::EjectDisk) {
+            io_state.switch_disk_side = Some(frontend::DiskSideSwitch::Eject);
+        }
+        io_state.switch_nsf_track = self.gui.get_nsf_track_switch();
+        if self.is_menu_bar_item_selected(MenuBarItem::NextNsfTrack) {
+            io_state.switch_nsf_track = Some(frontend::NsfTrackSwitch::Next);
+        }
+        if self.is_menu_bar_item_selected(MenuBarItem::PreviousNsfTrack) {
+            io_state.switch_nsf_track = Some(frontend::NsfTrackSwitch::Previous);
+        }
 
         io_state.speed = None;
         {
@@ -216,7 +277,10 @@ impl Sdl2ImGuiOpenGlFrontend {
             {
                 self.cancel = true;
             } else if self.gui.video_size_control != VideoSizeControl::FullScreen {
-                let [expected_width, _]: [u32; 2] = self.gui.video_size_control.into();
+                let [expected_width, _] = self
+                    .gui
+                    .video_size_control
+                    .get_size(&self.video_output_settings);
                 let (actual_width, _) = self.window.size();
                 if actual_width as u32 != expected_width {
                     self.is_video_size_change_pending = true;
@@ -251,6 +315,13 @@ impl Sdl2ImGuiOpenGlFrontend {
                 self.gui.video_size_control = self.gui.previous_video_size_control;
                 self.is_video_size_change_pending = true;
             }
+
+            let video_output_settings = self.gui.get_video_output_settings();
+            if video_output_settings != self.video_output_settings {
+                self.video_output_settings = video_output_settings;
+                self.is_video_size_change_pending = true;
+            }
+            io_state.video_output_settings = self.video_output_settings;
         }
         {
             let mut toggle = |item: MenuBarItem| {
@@ -271,6 +342,7 @@ impl Sdl2ImGuiOpenGlFrontend {
         let toggled_pause = toggle(MenuBarItem::Pause, self.gui.pause);
         self.gui.controllers_setup =
             toggle(MenuBarItem::ControllersSetup, self.gui.controllers_setup);
+        self.gui.state_slots_window = toggle(MenuBarItem::StateSlots, self.gui.state_slots_window);
         self.gui.pause = toggled_pause;
         io_state.pause = self.gui.pause;
 
@@ -284,6 +356,8 @@ impl Sdl2ImGuiOpenGlFrontend {
         update_gui_item(MenuBarItem::LoadNesFile);
         update_gui_item(MenuBarItem::SaveState);
         update_gui_item(MenuBarItem::LoadState);
+        update_gui_item(MenuBarItem::LoadScript);
+        update_gui_item(MenuBarItem::LoadPaletteFile);
     }
 
     fn check_for_keyboard_shortcuts(
@@ -300,6 +374,42 @@ impl Sdl2ImGuiOpenGlFrontend {
         }
     }
 
+    fn update_state_slot_textures(&mut self, control: &frontend::FrontendControl) {
+        for (slot, state_slot) in control.state_slots.iter().enumerate() {
+            let is_uploaded = match (&self.state_slots[slot], state_slot) {
+                (Some(uploaded), Some(state_slot)) => Rc::ptr_eq(uploaded, state_slot),
+                (uploaded, state_slot) => uploaded.is_none() && state_slot.is_none(),
+            };
+            if is_uploaded {
+                continue;
+            }
+            self.state_slots[slot] = state_slot.clone();
+            if let Some(thumbnail) = state_slot.as_ref().and_then(|slot| slot.thumbnail.as_ref()) {
+                unsafe {
+                    gl::BindTexture(gl::TEXTURE_2D, self.state_slot_textures[slot]);
+                    gl::PixelStorei(
+                        gl::UNPACK_ROW_LENGTH,
+                        nes_rs::SAVE_STATE_THUMBNAIL_WIDTH as _,
+                    );
+                    gl::PixelStorei(gl::UNPACK_SKIP_PIXELS, 0);
+                    gl::PixelStorei(gl::UNPACK_SKIP_ROWS, 0);
+                    gl::TexImage2D(
+                        gl::TEXTURE_2D,
+                        0,
+                        gl::RGB8 as _,
+                        nes_rs::SAVE_STATE_THUMBNAIL_WIDTH as _,
+                        nes_rs::SAVE_STATE_THUMBNAIL_HEIGHT as _,
+                        0,
+                        gl::RGB,
+                        gl::UNSIGNED_BYTE,
+                        thumbnail.as_ptr() as *const _,
+                    );
+                    gl::BindTexture(gl::TEXTURE_2D, self.emulation_texture);
+                }
+            }
+        }
+    }
+
     fn is_menu_bar_item_selected(&self, item: MenuBarItem) -> bool {
         self.keyboard_shortcuts.is_menu_bar_item_selected(item)
             || self.gui.is_menu_bar_item_selected(item)
@@ -313,7 +423,10 @@ impl Sdl2ImGuiOpenGlFrontend {
 
     fn set_window_size_and_get_video_size(&mut self) -> Size {
         if self.gui.video_size_control != gui::VideoSizeControl::FullScreen {
-            let [video_width, video_height]: [u32; 2] = self.gui.video_size_control.into();
+            let [video_width, video_height] = self
+                .gui
+                .video_size_control
+                .get_size(&self.video_output_settings);
             self.window
                 .borrow_mut()
                 .set_fullscreen(sdl2::video::FullscreenType::Off)
@@ -356,6 +469,7 @@ impl frontend::Frontend for Sdl2ImGuiOpenGlFrontend {
             self.gui.video_size = video_size;
             self.is_video_size_change_pending = false;
         }
+        self.update_state_slot_textures(&control);
         self.gui.prepare_for_new_frame(control.clone());
         self.keyboard_shortcuts = Default::default();
         self.cancel = false;
@@ -426,16 +540,30 @@ impl frontend::Frontend for Sdl2ImGuiOpenGlFrontend {
         }
         unsafe {
             if let Some(emulation_frame) = emulation_frame {
+                let (width, pixels) = match emulation_frame.ntsc_video {
+                    Some(ref ntsc_video) => (
+                        nes_rs::NTSC_VIDEO_FRAME_WIDTH,
+                        ntsc_video.get_pixels().as_slice(),
+                    ),
+                    None => (
+                        nes_rs::VIDEO_FRAME_WIDTH,
+                        emulation_frame.video.get_pixels().as_slice(),
+                    ),
+                };
+                let crop_rect = self.video_output_settings.get_crop_rect(width);
+                gl::PixelStorei(gl::UNPACK_ROW_LENGTH, width as _);
+                gl::PixelStorei(gl::UNPACK_SKIP_PIXELS, crop_rect.x as _);
+                gl::PixelStorei(gl::UNPACK_SKIP_ROWS, crop_rect.y as _);
                 gl::TexImage2D(
                     gl::TEXTURE_2D,
                     0,
                     gl::RGB8 as _,
-                    nes_rs::VIDEO_FRAME_WIDTH as _,
-                    nes_rs::VIDEO_FRAME_HEIGHT as _,
+                    crop_rect.width as _,
+                    crop_rect.height as _,
                     0,
                     gl::RGB,
                     gl::UNSIGNED_BYTE,
-                    emulation_frame.video.get_pixels().as_ptr() as *const _,
+                    pixels.as_ptr() as *const _,
                 );
             }
         };
@@ -493,4 +621,25 @@ impl frontend::ControllerCallback for Sdl2ImGuiOpenGlFrontend {
             None
         }
     }
+    fn is_vs_coin_inserted(&self, slot: crate::VsCoinSlot) -> bool {
+        let sdl2_scancode = match slot {
+            crate::VsCoinSlot::Coin1 => sdl2::keyboard::Scancode::Num5,
+            crate::VsCoinSlot::Coin2 => sdl2::keyboard::Scancode::Num6,
+        };
+        *self.keyboard_state.get(&sdl2_scancode).unwrap_or(&false)
+    }
+
+    fn is_vs_service_button_pressed(&self) -> bool {
+        *self
+            .keyboard_state
+            .get(&sdl2::keyboard::Scancode::Num9)
+            .unwrap_or(&false)
+    }
+
+    fn is_playchoice_coin_inserted(&self) -> bool {
+        *self
+            .keyboard_state
+            .get(&sdl2::keyboard::Scancode::Num5)
+            .unwrap_or(&false)
+    }
 }
@@ -0,0 +1,50 @@
+use std::{env, process::ExitCode};
+
+use nes_rs::Nes;
+use nes_rs::scripting::Script;
+
+const USAGE: &str = "Usage: nes-rs-headless <rom> <script> [frame limit]";
+
+fn run(args: &[String]) -> Result<(), String> {
+    let [rom_path, script_path, rest @ ..] = args else {
+        return Err(USAGE.to_string());
+    };
+    let frame_limit = rest
+        .first()
+        .map(|limit| {
+            limit
+                .parse::<u64>()
+                .map_err(|e| format!("Invalid frame limit {}: {}", limit, e))
+        })
+        .transpose()?;
+    let rom =
+        std::fs::read(rom_path).map_err(|e| format!("Unable to read ROM {}: {}", rom_path, e))?;
+    let source = std::fs::read_to_string(script_path)
+        .map_err(|e| format!("Unable to read script {}: {}", script_path, e))?;
+
+    let mut nes = Nes::new();
+    nes.load_rom(&rom)
+        .map_err(|e| format!("Error loading ROM {}: {}", rom_path, e))?;
+    let mut script = Script::new(&source, &mut nes).map_err(|e| e.to_string())?;
+
+    let mut frame = 0;
+    while !script.is_quit_requested() && frame_limit.is_none_or(|limit| frame < limit) {
+        let callback = script.get_controller_callback(None);
+        nes.run_single_frame(&callback)
+            .map_err(|e| format!("Emulation error: {}", e))?;
+        script.notify_frame(&mut nes).map_err(|e| e.to_string())?;
+        frame += 1;
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match run(&args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
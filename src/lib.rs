@@ -1,2 +1,4 @@
 pub mod nes;
+#[cfg(feature = "scripting")]
+pub mod scripting;
 pub use nes::*;
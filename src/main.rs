@@ -2,9 +2,11 @@ use std::{
     env,
     fs::File,
     io::{Read, Write},
+    path::{Path, PathBuf},
 };
 
 mod frontend;
+mod state_slots;
 
 use frontend::*;
 
@@ -14,10 +16,16 @@ use frontend::sdl2_imgui_opengl::HALF_FPS;
 use frontend::{
     Frontend, FrontendControl, FrontendState, sdl2_imgui_opengl::Sdl2ImGuiOpenGlFrontend,
 };
+use nes_rs::scripting::Script;
 use nes_rs::*;
+use state_slots::StateSlots;
 
 extern crate enum_tryfrom;
 
+const FDS_BIOS_FILE_NAME: &str = "disksys.rom";
+const IMAGE_PATCH_EXTENSION: &str = "sav.ips";
+const IMAGE_PATCH_SAVE_INTERVAL: std::time::Duration = std::time::Duration::from_secs(5);
+
 #[cfg(target_os = "emscripten")]
 unsafe extern "C" {
     fn emscripten_run_script(s: *const std::os::raw::c_char);
@@ -32,6 +40,11 @@ pub struct Emulation {
     error_timer: std::time::Instant,
     frame_start: std::time::Instant,
     is_audio_available: bool,
+    patchable_image_path: Option<String>,
+    saved_patchable_image: Option<Vec<u8>>,
+    image_patch_timer: std::time::Instant,
+    script: Option<Script>,
+    state_slots: StateSlots,
 }
 #[allow(clippy::new_without_default)]
 impl Emulation {
@@ -41,14 +54,19 @@ impl Emulation {
         nes.config().set_audio_target_fps(59.98);
 
         let mut initial_title: Option<String> = None;
+        let mut patchable_image_path: Option<String> = None;
         let args: Vec<String> = env::args().collect();
         if args.len() > 1 {
             let path = &args[1];
             load(&mut nes, path).map_err(|e| format!("Error loading ROM {}: {}", path, e))?;
             initial_title = Some(path.clone());
+            if is_patchable_image(&nes) {
+                patchable_image_path = Some(path.clone());
+            }
         } else {
             load_demo(&mut nes);
         }
+        let state_slots = StateSlots::new(&nes);
         let frontend = frontend::sdl2_imgui_opengl::Sdl2ImGuiOpenGlFrontend::new();
 
         let frontend_state: FrontendState = Default::default();
@@ -62,6 +80,11 @@ impl Emulation {
             title: initial_title,
             controller_type: [crate::ControllerType::NullController; 2],
             error: None,
+            disk_side_count: None,
+            inserted_disk_side: None,
+            nsf_info: None,
+            playchoice_time_expired: false,
+            state_slots: Default::default(),
         };
         let is_audio_available = frontend.is_audio_available();
         Ok(Self {
@@ -74,10 +97,41 @@ impl Emulation {
             error_timer: std::time::Instant::now(),
             frame_start,
             is_audio_available,
+            saved_patchable_image: get_patchable_image(&nes),
+            patchable_image_path,
+            image_patch_timer: std::time::Instant::now(),
+            script: None,
+            state_slots,
         })
     }
 }
 
+impl Emulation {
+    fn save_image_patch_if_changed(&mut self) {
+        let Some(ref path) = self.patchable_image_path else {
+            return;
+        };
+        let image = get_patchable_image(&self.nes);
+        if image == self.saved_patchable_image {
+            return;
+        }
+        match save_image_patch(&self.nes, path) {
+            Ok(()) => {
+                self.saved_patchable_image = image;
+                #[cfg(target_os = "emscripten")]
+                unsafe {
+                    let script = std::ffi::CString::new("syncToStorage();").unwrap();
+                    emscripten_run_script(script.as_ptr());
+                };
+            }
+            Err(e) => {
+                self.frontend_control.error = Some(format!("Error saving patch {}: {}", path, e));
+                self.error_timer = std::time::Instant::now();
+            }
+        }
+    }
+}
+
 impl emscripten_main_loop::MainLoop for Emulation {
     fn main_loop(&mut self) -> emscripten_main_loop::MainLoopEvent {
         self.frontend_control.controller_type = [
@@ -88,12 +142,33 @@ impl emscripten_main_loop::MainLoop for Emulation {
                 .config()
                 .get_controller_type(crate::ControllerId::Controller2),
         ];
+        self.frontend_control.disk_side_count = self.nes.get_disk_side_count();
+        self.frontend_control.inserted_disk_side = self.nes.get_inserted_disk_side();
+        self.frontend_control.nsf_info = self.nes.get_nsf_info().cloned();
+        self.frontend_control.playchoice_time_expired = self.nes.is_playchoice_time_expired();
+        self.frontend_control.state_slots = self.state_slots.get_slots().clone();
         let mut emulation_frame: Option<&EmulationFrame> = None;
         if !self.frontend_state.pause {
-            let emulation_result = self.nes.run_single_frame(&self.frontend);
+            let emulation_result = match self.script {
+                Some(ref script) => {
+                    let callback = script.get_controller_callback(Some(&self.frontend));
+                    self.nes.run_single_frame(&callback).map(|_| ())
+                }
+                None => self.nes.run_single_frame(&self.frontend).map(|_| ()),
+            };
             match emulation_result {
-                Ok(frame) => {
-                    emulation_frame = Some(frame);
+                Ok(()) => {
+                    if let Some(ref mut script) = self.script {
+                        let script_result = script.notify_frame(&mut self.nes);
+                        if let Err(e) = script_result {
+                            self.frontend_control.error = Some(e.to_string());
+                            self.error_timer = std::time::Instant::now();
+                        }
+                        if script_result.is_err() || script.is_quit_requested() {
+                            self.script = None;
+                        }
+                    }
+                    emulation_frame = Some(self.nes.get_emulation_frame());
                 }
                 Err(e) => {
                     self.frontend_control.error = Some(format!("Emulation error: {}", e));
@@ -120,7 +195,19 @@ impl emscripten_main_loop::MainLoop for Emulation {
             &mut self.nes,
             &self.frontend_state,
             &mut self.frontend_control,
+            &mut self.patchable_image_path,
+            &mut self.script,
+            &mut self.state_slots,
         );
+        if self.frontend_state.load_nes_file.is_some() {
+            self.saved_patchable_image = get_patchable_image(&self.nes);
+        }
+
+        // Disk and flash writes are saved while playing, as the page may be closed at any time.
+        if self.image_patch_timer.elapsed() >= IMAGE_PATCH_SAVE_INTERVAL {
+            self.image_patch_timer = std::time::Instant::now();
+            self.save_image_patch_if_changed();
+        }
 
         if !self.frontend_state.pause {
             let elapsed_time_since_frame_start = self.frame_start.elapsed();
@@ -168,6 +255,11 @@ pub fn run(mut emulation: Emulation) {
     while !emulation.frontend_state.quit {
         emulation.main_loop();
     }
+    if let Some(ref patchable_image_path) = emulation.patchable_image_path
+        && let Err(e) = save_image_patch(&emulation.nes, patchable_image_path)
+    {
+        eprintln!("Error saving patch {}: {}", patchable_image_path, e);
+    }
 }
 
 fn handle_io_state(
@@ -175,27 +267,126 @@ fn handle_io_state(
     nes: &mut Nes,
     fontend_state: &FrontendState,
     frontend_control: &mut FrontendControl,
+    patchable_image_path: &mut Option<String>,
+    script: &mut Option<Script>,
+    state_slots: &mut StateSlots,
 ) {
     if fontend_state.power_cycle {
         nes.power_cycle();
     }
 
+    if let Some(ref disk_side_switch) = fontend_state.switch_disk_side
+        && let Some(disk_side_count) = nes.get_disk_side_count()
+    {
+        let disk_side = match disk_side_switch {
+            DiskSideSwitch::Next => Some(
+                nes.get_inserted_disk_side()
+                    .map_or(0, |side| (side + 1) % disk_side_count),
+            ),
+            DiskSideSwitch::Eject => None,
+            DiskSideSwitch::Insert(side) => Some(*side),
+        };
+        let mut switch_result = nes.insert_disk_side(disk_side).map_err(|e| e.to_string());
+        if switch_result.is_ok()
+            && let Some(ref path) = *patchable_image_path
+        {
+            switch_result = save_image_patch(nes, path);
+        }
+        if let Err(e) = switch_result {
+            frontend_control.error = Some(format!("Error switching disk side: {}", e));
+            *error_timer = std::time::Instant::now();
+        }
+    }
+
+    if let Some(ref nsf_track_switch) = fontend_state.switch_nsf_track
+        && let Some(nsf_info) = nes.get_nsf_info()
+    {
+        let track_count = nsf_info.track_count;
+        let track = match nsf_track_switch {
+            NsfTrackSwitch::Next => (nsf_info.current_track + 1) % track_count,
+            NsfTrackSwitch::Previous => (nsf_info.current_track + track_count - 1) % track_count,
+            NsfTrackSwitch::Select(track) => *track,
+        };
+        if let Err(e) = nes.select_nsf_track(track) {
+            frontend_control.error = Some(format!("Error switching NSF track: {}", e));
+            *error_timer = std::time::Instant::now();
+        }
+    }
+
+    nes.config()
+        .set_run_ahead_frames(fontend_state.run_ahead_frames);
+    nes.config()
+        .set_run_ahead_second_instance_enabled(fontend_state.run_ahead_second_instance);
+
+    if fontend_state.ntsc_filter != nes.config().get_ntsc_filter().is_some() {
+        nes.config()
+            .set_ntsc_filter(fontend_state.ntsc_filter.then(NtscFilterSettings::default));
+    }
+
+    if let Some(ref palette_switch) = fontend_state.switch_palette {
+        let switch_result = match palette_switch {
+            PaletteSwitch::Builtin(palette) => {
+                nes.config().set_builtin_palette(*palette);
+                Ok(())
+            }
+            PaletteSwitch::Ntsc(settings) => {
+                nes.config().set_ntsc_palette(*settings);
+                Ok(())
+            }
+            PaletteSwitch::File(path) => get_bytes_from_file(path)
+                .and_then(|pal| nes.config().set_palette(&pal).map_err(|e| e.to_string())),
+        };
+        if let Err(e) = switch_result {
+            frontend_control.error = Some(format!("Error loading palette: {}", e));
+            *error_timer = std::time::Instant::now();
+        }
+    }
+
     if let Some(ref nes_file_path) = fontend_state.load_nes_file {
+        if let Some(ref path) = *patchable_image_path
+            && let Err(e) = save_image_patch(nes, path)
+        {
+            frontend_control.error = Some(format!("Error saving patch {}: {}", path, e));
+            *error_timer = std::time::Instant::now();
+        }
         let load_result = load(nes, nes_file_path.as_str());
         if load_result.is_ok() {
             frontend_control.title = Some(nes_file_path.clone());
+            *patchable_image_path = if is_patchable_image(nes) {
+                Some(nes_file_path.clone())
+            } else {
+                None
+            };
+            *state_slots = StateSlots::new(nes);
         } else {
             frontend_control.error = Some(load_result.err().unwrap());
             *error_timer = std::time::Instant::now();
         }
     }
+    if let Some(ref script_path) = fontend_state.load_script {
+        let load_result = std::fs::read_to_string(script_path)
+            .map_err(|e| e.to_string())
+            .and_then(|source| Script::new(&source, nes).map_err(|e| e.to_string()));
+        match load_result {
+            Ok(loaded_script) => *script = Some(loaded_script),
+            Err(e) => {
+                frontend_control.error =
+                    Some(format!("Error loading script {}: {}", script_path, e));
+                *error_timer = std::time::Instant::now();
+            }
+        }
+    }
+    if let Some(script) = script {
+        script.set_video_output_settings(fontend_state.video_output_settings);
+    }
+
     if frontend_control.error.is_some() && error_timer.elapsed() > std::time::Duration::from_secs(5)
     {
         frontend_control.error = None;
     }
 
     if let Some(ref save_state_path) = fontend_state.save_state {
-        let serialized = nes.save_state();
+        let serialized = nes.save_state_with_thumbnail();
         if serialized.is_err() {
             frontend_control.error =
                 Some(format!("Error saving state: {}", serialized.err().unwrap()));
@@ -240,6 +431,29 @@ fn handle_io_state(
         frontend_control.title = Some(load_state_path.clone());
     }
 
+    if let Some(state_slot_action) = fontend_state.state_slot_action {
+        let (slot_result, operation, slot) = match state_slot_action {
+            StateSlotAction::Save(slot) => (state_slots.save(nes, slot), "saving", slot),
+            StateSlotAction::Load(slot) => (state_slots.load(nes, slot), "loading", slot),
+        };
+        if let Err(e) = slot_result {
+            frontend_control.error = Some(format!(
+                "Error {} state slot {}: {}",
+                operation,
+                slot + 1,
+                e
+            ));
+            *error_timer = std::time::Instant::now();
+        }
+        #[cfg(target_os = "emscripten")]
+        if let StateSlotAction::Save(_) = state_slot_action {
+            unsafe {
+                let script = std::ffi::CString::new("syncToStorage();").unwrap();
+                emscripten_run_script(script.as_ptr());
+            };
+        }
+    }
+
     if let Some(ref speed) = fontend_state.speed {
         match speed {
             Speed::Half => frontend_control.target_fps = HALF_FPS,
@@ -266,9 +480,90 @@ fn handle_io_state(
     nes.config().set_audio_volume(fontend_state.audio_volume);
 }
 
+fn is_disk_image(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("fds"))
+}
+
+fn is_nsf_file(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|extension| {
+        extension.eq_ignore_ascii_case("nsf") || extension.eq_ignore_ascii_case("nsfe")
+    })
+}
+
+// Only disk images and flash boards write back to the image, so only they get an IPS patch.
+fn get_patchable_image(nes: &Nes) -> Option<Vec<u8>> {
+    nes.get_disk_image().or_else(|| nes.get_flash_image())
+}
+
+fn is_patchable_image(nes: &Nes) -> bool {
+    get_patchable_image(nes).is_some()
+}
+
+fn get_image_patch_path(image_path: &str) -> PathBuf {
+    Path::new(image_path).with_extension(IMAGE_PATCH_EXTENSION)
+}
+
+fn get_fds_bios(disk_image_path: &str) -> Result<Vec<u8>, String> {
+    let mut bios_path = Path::new(disk_image_path).with_file_name(FDS_BIOS_FILE_NAME);
+    if !bios_path.exists() {
+        bios_path = PathBuf::from(FDS_BIOS_FILE_NAME);
+    }
+    let bios_path = bios_path.to_string_lossy();
+    get_bytes_from_file(&bios_path)
+        .map_err(|e| format!("Unable to read FDS BIOS {}: {}", bios_path, e))
+}
+
+// Patches are made against the image as the emulator encodes it, which for disk images is not
+// always byte for byte the file that was loaded.
+fn get_original_image(image_path: &str) -> Result<Option<Vec<u8>>, String> {
+    let mut nes = Nes::new();
+    load_image(&mut nes, image_path, &get_bytes_from_file(image_path)?)?;
+    Ok(get_patchable_image(&nes))
+}
+
+fn save_image_patch(nes: &Nes, image_path: &str) -> Result<(), String> {
+    let Some(image) = get_patchable_image(nes) else {
+        return Ok(());
+    };
+    let Some(original_image) = get_original_image(image_path)? else {
+        return Ok(());
+    };
+    let patch_path = get_image_patch_path(image_path);
+    if image == original_image {
+        if patch_path.exists() {
+            std::fs::remove_file(&patch_path).map_err(|e| e.to_string())?;
+        }
+        return Ok(());
+    }
+    let patch = create_ips_patch(&original_image, &image);
+    std::fs::write(&patch_path, patch).map_err(|e| e.to_string())
+}
+
+fn load_image(nes: &mut Nes, path: &str, image: &[u8]) -> Result<(), String> {
+    if is_disk_image(path) {
+        let bios = get_fds_bios(path)?;
+        nes.load_fds(image, &bios).map_err(|e| e.to_string())
+    } else if is_nsf_file(path) {
+        nes.load_nsf(image).map_err(|e| e.to_string())
+    } else {
+        nes.load_rom(image).map_err(|e| e.to_string())
+    }
+}
+
 fn load(nes: &mut Nes, path: &str) -> Result<(), String> {
-    let rom = get_bytes_from_file(path)?;
-    nes.load_rom(&rom).map_err(|e| e.to_string())?;
+    load_image(nes, path, &get_bytes_from_file(path)?)?;
+    let patch_path = get_image_patch_path(path);
+    if patch_path.exists()
+        && let Some(original_image) = get_patchable_image(nes)
+        && let Some(rom_crc32) = nes.get_rom_crc32()
+    {
+        let patch = get_bytes_from_file(&patch_path.to_string_lossy())?;
+        let image = apply_ips_patch(&original_image, &patch).map_err(|e| e.to_string())?;
+        load_image(nes, path, &image)?;
+        nes.set_rom_crc32(rom_crc32);
+    }
     Ok(())
 }
 
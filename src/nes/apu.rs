@@ -61,7 +61,7 @@ pub(crate) const FRAME_COUNTER_QUARTER_FRAME_3_CPU_CYCLES: u16 = 22371;
 pub(crate) const FRAME_COUNTER_HALF_FRAME_0_MOD_0_CPU_CYCLES: u16 = 29829;
 const FRAME_COUNTER_HALF_FRAME_0_MOD_1_CPU_CYCLES: u16 = 37281;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct FrameCounter {
     data: u8,
 }
@@ -87,7 +87,7 @@ pub(crate) enum StatusRegisterFlag {
     DMCInterrupt = 0b10000000,
 }
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub(crate) struct StatusRegister {
     pub(crate) data: u8,
 }
@@ -109,7 +109,7 @@ impl StatusRegister {
     }
 }
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub(crate) struct Envelope {
     pub(crate) start_flag: bool,
     pub(crate) divider: u8,
@@ -135,7 +135,7 @@ impl Envelope {
     }
 }
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct SweepUnit {
     enabled: bool,
     reload_flag: bool,
@@ -192,7 +192,7 @@ impl SweepUnit {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct PulseWave {
     data: [u8; 4],
     length_counter: u8,
@@ -347,7 +347,7 @@ impl LengthCounterChannel for PulseWave {
     }
 }
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct TriangleWave {
     data: [u8; 4],
     length_counter: u8,
@@ -432,7 +432,7 @@ impl LengthCounterChannel for TriangleWave {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct Noise {
     data: [u8; 4],
     length_counter: u8,
@@ -537,7 +537,7 @@ impl LengthCounterChannel for Noise {
     }
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 struct Dmc {
     data: [u8; 4],
     timer_tick: u16,
@@ -663,7 +663,8 @@ impl Dmc {
     }
 }
 
-struct SampleProcessor {
+#[derive(Clone)]
+pub(crate) struct SampleProcessor {
     phase: f64,
     acc: f64,
     acc_count: f64,
@@ -708,7 +709,7 @@ impl Default for SampleProcessor {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Apu {
     frame_counter: FrameCounter,
     status: StatusRegister,
@@ -788,6 +789,14 @@ impl Apu {
         self.audio_buffer.reset();
     }
 
+    pub fn take_audio_buffer(&mut self) -> SampleProcessor {
+        std::mem::take(&mut self.audio_buffer)
+    }
+
+    pub fn set_audio_buffer(&mut self, audio_buffer: SampleProcessor) {
+        self.audio_buffer = audio_buffer;
+    }
+
     fn get_length_counter_channel(
         &mut self,
         flag: StatusRegisterFlag,
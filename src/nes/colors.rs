@@ -1,10 +1,27 @@
 pub type RgbColor = (u8, u8, u8);
 
+use super::Error;
 use serde::{Deserialize, Serialize};
+
+const PALETTE_SIZE: usize = 64;
+const EMPHASIS_PALETTE_SIZE: usize = 512;
+
 pub trait ColorMapper {
     fn map_nes_color(&self, color: u8) -> RgbColor;
+
+    fn map_nes_color_with_emphasis(&self, _color: u8, _emphasis: u8) -> Option<RgbColor> {
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn ColorMapper>;
 }
-#[derive(Serialize, Deserialize)]
+
+impl Clone for Box<dyn ColorMapper> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+#[derive(Clone, Serialize, Deserialize)]
 pub struct DefaultColorMapper {
     #[serde(with = "serde_arrays")]
     color_map: [RgbColor; 64],
@@ -95,4 +112,279 @@ impl ColorMapper for DefaultColorMapper {
     fn map_nes_color(&self, color: u8) -> RgbColor {
         self.color_map[color as usize]
     }
+
+    fn clone_box(&self) -> Box<dyn ColorMapper> {
+        Box::new(self.clone())
+    }
+}
+
+const RGB_PPU_PALETTE: [u16; 64] = [
+    0o333, 0o014, 0o006, 0o326, 0o403, 0o503, 0o510, 0o420, 0o320, 0o120, 0o031, 0o040, 0o022,
+    0o000, 0o000, 0o000, 0o555, 0o036, 0o027, 0o407, 0o507, 0o704, 0o700, 0o630, 0o430, 0o140,
+    0o040, 0o053, 0o044, 0o111, 0o000, 0o000, 0o777, 0o357, 0o447, 0o637, 0o707, 0o737, 0o740,
+    0o750, 0o660, 0o360, 0o070, 0o276, 0o077, 0o222, 0o000, 0o000, 0o777, 0o567, 0o657, 0o757,
+    0o747, 0o755, 0o764, 0o772, 0o773, 0o572, 0o473, 0o276, 0o467, 0o444, 0o000, 0o000,
+];
+
+const RP2C04_PALETTE_LUTS: [[u8; 64]; 4] = [
+    [
+        0x35, 0x23, 0x16, 0x22, 0x1C, 0x09, 0x1D, 0x15, 0x20, 0x00, 0x27, 0x05, 0x04, 0x28, 0x08,
+        0x20, 0x21, 0x3E, 0x1F, 0x29, 0x3C, 0x32, 0x36, 0x12, 0x3F, 0x2B, 0x2E, 0x1E, 0x3D, 0x2D,
+        0x24, 0x01, 0x0E, 0x31, 0x33, 0x2A, 0x2C, 0x0C, 0x1B, 0x14, 0x2E, 0x07, 0x34, 0x06, 0x13,
+        0x02, 0x26, 0x2E, 0x2E, 0x19, 0x10, 0x0A, 0x39, 0x03, 0x37, 0x17, 0x0F, 0x11, 0x0B, 0x0D,
+        0x38, 0x25, 0x18, 0x3A,
+    ],
+    [
+        0x2E, 0x27, 0x18, 0x39, 0x3A, 0x25, 0x1C, 0x31, 0x16, 0x13, 0x38, 0x34, 0x20, 0x23, 0x3C,
+        0x0B, 0x0F, 0x21, 0x06, 0x3D, 0x1B, 0x29, 0x1E, 0x22, 0x1D, 0x24, 0x0E, 0x2B, 0x32, 0x08,
+        0x2E, 0x03, 0x04, 0x36, 0x26, 0x33, 0x11, 0x1F, 0x10, 0x02, 0x14, 0x3F, 0x00, 0x09, 0x12,
+        0x2E, 0x28, 0x20, 0x3E, 0x0D, 0x2A, 0x17, 0x0C, 0x01, 0x15, 0x19, 0x2E, 0x2C, 0x07, 0x37,
+        0x35, 0x05, 0x0A, 0x2D,
+    ],
+    [
+        0x14, 0x25, 0x3A, 0x10, 0x0B, 0x20, 0x31, 0x09, 0x01, 0x2E, 0x36, 0x08, 0x15, 0x3D, 0x3E,
+        0x3C, 0x22, 0x1C, 0x05, 0x12, 0x19, 0x18, 0x17, 0x1B, 0x00, 0x03, 0x2E, 0x02, 0x16, 0x06,
+        0x34, 0x35, 0x23, 0x0F, 0x0E, 0x37, 0x0D, 0x27, 0x26, 0x20, 0x29, 0x04, 0x21, 0x24, 0x11,
+        0x2D, 0x2E, 0x1F, 0x2C, 0x1E, 0x39, 0x33, 0x07, 0x2A, 0x28, 0x1D, 0x0A, 0x2E, 0x32, 0x38,
+        0x13, 0x2B, 0x3F, 0x0C,
+    ],
+    [
+        0x18, 0x03, 0x1C, 0x28, 0x2E, 0x35, 0x01, 0x17, 0x10, 0x1F, 0x2A, 0x0E, 0x36, 0x37, 0x0B,
+        0x39, 0x25, 0x1E, 0x12, 0x34, 0x2E, 0x1D, 0x06, 0x26, 0x3E, 0x1B, 0x22, 0x19, 0x04, 0x2E,
+        0x3A, 0x21, 0x05, 0x0A, 0x07, 0x02, 0x13, 0x14, 0x00, 0x15, 0x0C, 0x3D, 0x11, 0x0F, 0x0D,
+        0x38, 0x2D, 0x24, 0x33, 0x20, 0x08, 0x16, 0x3F, 0x2B, 0x20, 0x3C, 0x2E, 0x27, 0x23, 0x31,
+        0x29, 0x32, 0x2C, 0x09,
+    ],
+];
+
+#[derive(Clone)]
+pub struct RgbColorMapper {
+    lut: Option<&'static [u8; 64]>,
+}
+
+impl RgbColorMapper {
+    pub const RP2C03: Self = Self { lut: None };
+
+    pub const fn rp2c04(palette: usize) -> Self {
+        Self {
+            lut: Some(&RP2C04_PALETTE_LUTS[palette]),
+        }
+    }
+}
+
+impl ColorMapper for RgbColorMapper {
+    fn map_nes_color(&self, color: u8) -> RgbColor {
+        let color = self.lut.map_or(color, |lut| lut[color as usize]);
+        let rgb = RGB_PPU_PALETTE[color as usize];
+        let scale = |level: u16| ((level & 0b111) * 255 / 7) as u8;
+        (scale(rgb >> 6), scale(rgb >> 3), scale(rgb))
+    }
+
+    fn clone_box(&self) -> Box<dyn ColorMapper> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct PalColorMapper {
+    colors: Vec<RgbColor>,
+}
+
+impl PalColorMapper {
+    pub fn new(pal: &[u8]) -> Result<Self, Error> {
+        if pal.len() != PALETTE_SIZE * 3 && pal.len() != EMPHASIS_PALETTE_SIZE * 3 {
+            return Err(Error::InvalidPaletteSize(pal.len()));
+        }
+        let colors = pal
+            .chunks_exact(3)
+            .map(|rgb| (rgb[0], rgb[1], rgb[2]))
+            .collect();
+        Ok(Self { colors })
+    }
+}
+
+impl ColorMapper for PalColorMapper {
+    fn map_nes_color(&self, color: u8) -> RgbColor {
+        self.colors[color as usize]
+    }
+
+    fn map_nes_color_with_emphasis(&self, color: u8, emphasis: u8) -> Option<RgbColor> {
+        (self.colors.len() == EMPHASIS_PALETTE_SIZE)
+            .then(|| self.colors[emphasis as usize * PALETTE_SIZE + color as usize])
+    }
+
+    fn clone_box(&self) -> Box<dyn ColorMapper> {
+        Box::new(self.clone())
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum BuiltinPalette {
+    #[default]
+    Default,
+    Ppu2C02Measured,
+    Fceux,
+    NestopiaYuv,
+    SonyCxa,
+}
+
+impl BuiltinPalette {
+    pub const ALL: [BuiltinPalette; 5] = [
+        BuiltinPalette::Default,
+        BuiltinPalette::Ppu2C02Measured,
+        BuiltinPalette::Fceux,
+        BuiltinPalette::NestopiaYuv,
+        BuiltinPalette::SonyCxa,
+    ];
+
+    pub fn get_name(&self) -> &'static str {
+        match self {
+            BuiltinPalette::Default => "Default",
+            BuiltinPalette::Ppu2C02Measured => "2C02 Measured",
+            BuiltinPalette::Fceux => "FCEUX",
+            BuiltinPalette::NestopiaYuv => "Nestopia YUV",
+            BuiltinPalette::SonyCxa => "Sony CXA",
+        }
+    }
+
+    pub(crate) fn create_color_mapper(&self) -> Box<dyn ColorMapper> {
+        let pal: &[u8] = match self {
+            BuiltinPalette::Default => return Box::new(DefaultColorMapper::new()),
+            BuiltinPalette::Ppu2C02Measured => {
+                include_bytes!("../../res/palettes/2c02_measured.pal")
+            }
+            BuiltinPalette::Fceux => include_bytes!("../../res/palettes/fceux.pal"),
+            BuiltinPalette::NestopiaYuv => include_bytes!("../../res/palettes/nestopia_yuv.pal"),
+            BuiltinPalette::SonyCxa => include_bytes!("../../res/palettes/sony_cxa.pal"),
+        };
+        Box::new(PalColorMapper::new(pal).unwrap())
+    }
+}
+
+const NTSC_SIGNAL_LOW: [f32; 4] = [0.228, 0.312, 0.552, 0.880];
+const NTSC_SIGNAL_HIGH: [f32; 4] = [0.616, 0.840, 1.100, 1.100];
+const NTSC_SIGNAL_BLACK: f32 = 0.312;
+const NTSC_SIGNAL_WHITE: f32 = 1.100;
+const NTSC_EMPHASIS_ATTENUATION: f32 = 0.746;
+pub(crate) const NTSC_SAMPLES_PER_CYCLE: usize = 12;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NtscPaletteSettings {
+    pub hue: f32,
+    pub saturation: f32,
+    pub contrast: f32,
+    pub brightness: f32,
+    pub gamma: f32,
+}
+
+impl Default for NtscPaletteSettings {
+    fn default() -> Self {
+        Self {
+            hue: 0.0,
+            saturation: 1.0,
+            contrast: 1.0,
+            brightness: 0.0,
+            gamma: 1.0,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub(crate) struct NtscColorMapper {
+    colors: Vec<RgbColor>,
+}
+
+impl NtscColorMapper {
+    pub fn new(settings: NtscPaletteSettings) -> Self {
+        let colors = (0..EMPHASIS_PALETTE_SIZE)
+            .map(|index| {
+                Self::generate_color(
+                    (index % PALETTE_SIZE) as u8,
+                    (index / PALETTE_SIZE) as u8,
+                    &settings,
+                )
+            })
+            .collect();
+        Self { colors }
+    }
+
+    fn is_in_color_phase(hue: usize, phase: usize) -> bool {
+        (hue + phase) % NTSC_SAMPLES_PER_CYCLE < NTSC_SAMPLES_PER_CYCLE / 2
+    }
+
+    pub fn get_signal(color: u8, emphasis: u8, phase: usize) -> f32 {
+        let hue = (color & 0x0F) as usize;
+        let level = if hue > 0x0D {
+            1
+        } else {
+            ((color >> 4) & 0x03) as usize
+        };
+        let low = if hue == 0x00 {
+            NTSC_SIGNAL_HIGH[level]
+        } else {
+            NTSC_SIGNAL_LOW[level]
+        };
+        let high = if hue > 0x0C {
+            low
+        } else {
+            NTSC_SIGNAL_HIGH[level]
+        };
+        let mut signal = if Self::is_in_color_phase(hue, phase) {
+            high
+        } else {
+            low
+        };
+        let is_attenuated = (emphasis & 0b001 != 0 && Self::is_in_color_phase(0x0C, phase))
+            || (emphasis & 0b010 != 0 && Self::is_in_color_phase(0x04, phase))
+            || (emphasis & 0b100 != 0 && Self::is_in_color_phase(0x08, phase));
+        if hue < 0x0E && is_attenuated {
+            signal *= NTSC_EMPHASIS_ATTENUATION;
+        }
+        (signal - NTSC_SIGNAL_BLACK) / (NTSC_SIGNAL_WHITE - NTSC_SIGNAL_BLACK)
+    }
+
+    pub fn get_subcarrier_angle(phase: usize, settings: &NtscPaletteSettings) -> f32 {
+        -std::f32::consts::TAU * phase as f32 / NTSC_SAMPLES_PER_CYCLE as f32
+            - settings.hue.to_radians()
+    }
+
+    pub fn decode_yuv(y: f32, u: f32, v: f32, settings: &NtscPaletteSettings) -> RgbColor {
+        let y = y * settings.contrast + settings.brightness;
+        let u = u * settings.saturation * settings.contrast;
+        let v = v * settings.saturation * settings.contrast;
+
+        let to_rgb_component =
+            |value: f32| (value.clamp(0.0, 1.0).powf(settings.gamma) * 255.0).round() as u8;
+        (
+            to_rgb_component(y + 1.140 * v),
+            to_rgb_component(y - 0.395 * u - 0.581 * v),
+            to_rgb_component(y + 2.032 * u),
+        )
+    }
+
+    fn generate_color(color: u8, emphasis: u8, settings: &NtscPaletteSettings) -> RgbColor {
+        let (mut y, mut u, mut v) = (0.0, 0.0, 0.0);
+        for phase in 0..NTSC_SAMPLES_PER_CYCLE {
+            let signal = Self::get_signal(color, emphasis, phase) / NTSC_SAMPLES_PER_CYCLE as f32;
+            let angle = Self::get_subcarrier_angle(phase, settings);
+            y += signal;
+            u += 2.0 * signal * angle.cos();
+            v += 2.0 * signal * angle.sin();
+        }
+        Self::decode_yuv(y, u, v, settings)
+    }
+}
+
+impl ColorMapper for NtscColorMapper {
+    fn map_nes_color(&self, color: u8) -> RgbColor {
+        self.colors[color as usize]
+    }
+
+    fn map_nes_color_with_emphasis(&self, color: u8, emphasis: u8) -> Option<RgbColor> {
+        Some(self.colors[emphasis as usize * PALETTE_SIZE + color as usize])
+    }
+
+    fn clone_box(&self) -> Box<dyn ColorMapper> {
+        Box::new(self.clone())
+    }
 }
@@ -10,6 +10,8 @@ pub const CPU_CYCLES_PER_FRAME: usize = 29780;
 pub enum NametableSource {
     Vram0,
     Vram1,
+    Vram2,
+    Vram3,
     ExRam,
     Fill,
 }
@@ -69,6 +71,15 @@ impl Mirroring {
             NametableSource::Vram1,
         ],
     };
+
+    pub const FOUR_SCREEN: Self = Self {
+        tables: [
+            NametableSource::Vram0,
+            NametableSource::Vram1,
+            NametableSource::Vram2,
+            NametableSource::Vram3,
+        ],
+    };
 }
 
 pub fn convert_2u8_to_u16(b0: u8, b1: u8) -> u16 {
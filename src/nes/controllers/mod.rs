@@ -3,6 +3,7 @@ use super::{ControllerCallback, ram_controllers::*};
 use super::ControllerId;
 use super::ControllerType;
 use super::StdNesControllerButton;
+use super::VsCoinSlot;
 use serde::Deserialize;
 use serde::Serialize;
 
@@ -33,7 +34,7 @@ impl ControllerType {
 }
 
 #[enum_dispatch::enum_dispatch]
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 enum ControllerEnum {
     NullController(self::null_controller::NullController),
     StdNesController(self::std_nes_controller::StdNesController),
@@ -66,10 +67,11 @@ impl ControllerId {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Controllers {
     controller_1: ControllerEnum,
     controller_2: ControllerEnum,
+    is_vs_system: bool,
 }
 
 impl Default for Controllers {
@@ -81,6 +83,7 @@ impl Default for Controllers {
             controller_2: ControllerEnum::StdNesController(StdNesController::new(
                 ControllerId::Controller2,
             )),
+            is_vs_system: false,
         }
     }
 }
@@ -100,6 +103,10 @@ impl Controllers {
             *controller = Self::new_controller(id, controller_type);
         }
     }
+    pub fn set_vs_system(&mut self, is_vs_system: bool) {
+        self.is_vs_system = is_vs_system;
+    }
+
     pub fn power_cycle(&mut self) {
         self.controller_1.power_cycle();
         self.controller_2.power_cycle();
@@ -133,9 +140,27 @@ impl Controllers {
 
 impl ReadInputRegisters for Controllers {
     fn read(&self, port: InputRegister, callback: Option<&dyn ControllerCallback>) -> u8 {
-        match port {
+        let value = match port {
             InputRegister::Controller1 => self.controller_1.read(callback),
             InputRegister::Controller2 => self.controller_2.read(callback),
+        };
+        if !self.is_vs_system {
+            return value;
+        }
+        let Some(callback) = callback else {
+            return value & 1;
+        };
+        let dip_switches = callback.get_vs_dip_switches();
+        match port {
+            InputRegister::Controller1 => {
+                let is_pressed = |pressed: bool, bit: u8| if pressed { 1 << bit } else { 0 };
+                (value & 1)
+                    | is_pressed(callback.is_vs_service_button_pressed(), 2)
+                    | (dip_switches & 0b11) << 3
+                    | is_pressed(callback.is_vs_coin_inserted(VsCoinSlot::Coin1), 5)
+                    | is_pressed(callback.is_vs_coin_inserted(VsCoinSlot::Coin2), 6)
+            }
+            InputRegister::Controller2 => (value & 1) | (dip_switches & 0b1111_1100),
         }
     }
 }
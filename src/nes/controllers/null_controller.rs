@@ -3,7 +3,7 @@ use serde::Serialize;
 
 use super::ControllerCallback;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct NullController {}
 
 impl NullController {
@@ -7,7 +7,7 @@ use super::ControllerCallback;
 use super::ControllerId;
 use super::StdNesControllerButton;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct StdNesController {
     id: ControllerId,
     button: RefCell<u8>,
@@ -7,7 +7,7 @@ use super::ControllerId;
 use crate::nes::EmulationFrame;
 use crate::nes::ZapperTarget;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Zapper {
     id: ControllerId,
     trigger_pressed: RefCell<bool>,
@@ -2,6 +2,7 @@ mod opcodes;
 
 use self::{AddressingMode::*, opcodes::IRQ_OPCODE};
 use super::CpuBus;
+use super::Interrupt;
 use super::RamBus;
 use super::apu::ApuState;
 use super::ppu::PpuState;
@@ -52,7 +53,7 @@ impl AddressingMode {
     }
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 enum Address {
     Implicit,
     Accumulator,
@@ -100,6 +101,7 @@ macro_rules! ram_bus {
             apu: $cpu_bus.apu,
             mapper: $cpu_bus.mapper,
             controllers: $cpu_bus.controllers,
+            vs_protection: $cpu_bus.vs_protection,
             callback: $cpu_bus.callback,
         }
     }};
@@ -107,24 +109,34 @@ macro_rules! ram_bus {
 impl CpuBus<'_> {
     fn get_byte(&mut self, address: u16) -> u8 {
         let mut ram_bus = ram_bus!(self);
-        self.ram.get_byte(address, &mut ram_bus)
+        let byte = self.ram.get_byte(address, &mut ram_bus);
+        if let Some(hooks) = self.hooks.as_deref_mut() {
+            hooks.notify_cpu_read(address, byte);
+        }
+        byte
     }
 
+    // Both bytes go through get_byte, so operand and vector fetches notify the CpuRead hooks.
     fn get_word(&mut self, address: u16) -> u16 {
-        let mut ram_bus = ram_bus!(self);
-        super::common::convert_2u8_to_u16(
-            self.ram.get_byte(address, &mut ram_bus),
-            self.ram.get_byte(address + 1, &mut ram_bus),
-        )
+        super::common::convert_2u8_to_u16(self.get_byte(address), self.get_byte(address + 1))
     }
 
     fn store_byte(&mut self, address: u16, byte: u8) {
         let mut ram_bus = ram_bus!(self);
         self.ram.store_byte(address, byte, &mut ram_bus);
+        if let Some(hooks) = self.hooks.as_deref_mut() {
+            hooks.notify_cpu_write(address, byte);
+        }
+    }
+
+    fn notify_interrupt(&mut self, interrupt: Interrupt, pc: u16) {
+        if let Some(hooks) = self.hooks.as_deref_mut() {
+            hooks.notify_interrupt(interrupt, pc);
+        }
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Cpu {
     pc: u16,
     sp: u8,
@@ -262,10 +274,12 @@ impl Cpu {
         if bus.ppu.check_for_nmi_pending() {
             bus.ppu.clear_nmi_pending();
             self.interrupt = Some(NMI_OPCODE as u8);
+            bus.notify_interrupt(Interrupt::Nmi, self.pc);
         } else if !self.get_flag(ProcessorFlag::InterruptDisable)
             && (bus.mapper.is_irq_pending() || bus.apu.is_irq_pending())
         {
-            self.interrupt = Some(IRQ_OPCODE as u8)
+            self.interrupt = Some(IRQ_OPCODE as u8);
+            bus.notify_interrupt(Interrupt::Irq, self.pc);
         }
     }
 
@@ -277,7 +291,11 @@ impl Cpu {
         let op = if let Some(op) = self.interrupt.take() {
             op
         } else {
-            bus.get_byte(self.pc)
+            let op = bus.get_byte(self.pc);
+            if let Some(hooks) = bus.hooks.as_deref_mut() {
+                hooks.notify_cpu_execute(self.pc, op);
+            }
+            op
         };
 
         let (_, code_segment_end) = self.code_segment;
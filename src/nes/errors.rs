@@ -14,7 +14,7 @@ pub enum Error {
     )]
     NesPlayChoiceRomTooShort(usize),
     #[error("Unsupported Mapper {0}.")]
-    NesUnsupportedMapper(u8),
+    NesUnsupportedMapper(u16),
     #[error("Unknown NES file format detected.")]
     UnknownNesFormat,
     #[error("Loaded state version mismatch. Expected version '{0}', but found version '{1}'.")]
@@ -27,6 +27,48 @@ pub enum Error {
     LoadStateDecompressionError(yazi::Error),
     #[error("Save state compression  error {0:?}")]
     LoadStateCompressionError(yazi::Error),
+    #[error(
+        "Loaded state belongs to a different ROM. Expected ROM CRC32 {0:08X}, but found {1:08X}."
+    )]
+    LoadStateRomMismatch(u32, u32),
     #[error("Invalid opcode {0} at address {1:#06X}")]
     NesCpuInvalidOpcode(u8, u16),
+    #[error("Unknown FDS file format detected.")]
+    UnknownFdsFormat,
+    #[error("FDS BIOS has wrong size. Expected 8192 bytes, but got {0} bytes.")]
+    FdsBiosWrongSize(usize),
+    #[error("FDS disk side {0} too short. Expected at least 65500 bytes, but got {1} bytes.")]
+    FdsDiskSideTooShort(u8, usize),
+    #[error("FDS disk side {0} is not available.")]
+    FdsDiskSideUnavailable(usize),
+    #[error("No FDS disk image loaded.")]
+    FdsNotLoaded,
+    #[error("Unknown NSF file format detected.")]
+    UnknownNsfFormat,
+    #[error("NSF header is too short. Expected at least 128 bytes, but got {0} bytes.")]
+    NsfHeaderTooShort(usize),
+    #[error("NSF load address {0:#06X} is invalid.")]
+    NsfInvalidLoadAddress(u16),
+    #[error("NSF data is too large. Expected at most 524288 bytes, but got {0} bytes.")]
+    NsfDataTooLarge(usize),
+    #[error("NSFE chunk '{0}' is missing.")]
+    NsfeMissingChunk(String),
+    #[error("NSFE chunk '{0}' is corrupted.")]
+    NsfeChunkCorrupted(String),
+    #[error("NSF track {0} is not available.")]
+    NsfTrackUnavailable(usize),
+    #[error("No NSF file loaded.")]
+    NsfNotLoaded,
+    #[error("Barcode '{0}' is invalid. Expected 8 or 13 digits.")]
+    InvalidBarcode(String),
+    #[error("Loaded ROM has no barcode reader.")]
+    BarcodeReaderUnavailable,
+    #[error("Loaded ROM is not a PlayChoice-10 ROM.")]
+    PlayChoiceNotLoaded,
+    #[error("Palette has wrong size. Expected 192 or 1536 bytes, but got {0} bytes.")]
+    InvalidPaletteSize(usize),
+    #[error("IPS patch is corrupted at offset {0:#06X}.")]
+    IpsPatchCorrupted(usize),
+    #[error("Script error: {0}")]
+    ScriptError(String),
 }
@@ -0,0 +1,55 @@
+use super::errors::Error;
+use super::mappers::*;
+use Error::*;
+
+pub(crate) const FDS_DISK_SIDE_SIZE: usize = 65500;
+pub(crate) const FDS_BIOS_SIZE: usize = 0x2000;
+const FWNES_HEADER_SIZE: usize = 16;
+const DISK_INFO_BLOCK_MAGIC: &[u8] = b"\x01*NINTENDO-HVC*";
+
+pub struct FdsFile {
+    disk_sides: Vec<Vec<u8>>,
+    has_header: bool,
+    bios: Vec<u8>,
+}
+
+impl FdsFile {
+    pub fn create_mapper(self) -> MapperEnum {
+        MapperEnum::MapperFds(MapperFds::new(self.bios, self.disk_sides, self.has_header))
+    }
+
+    pub fn new(image: &[u8], bios: &[u8]) -> Result<FdsFile, Error> {
+        if bios.len() != FDS_BIOS_SIZE {
+            return Err(FdsBiosWrongSize(bios.len()));
+        }
+
+        let (has_header, side_count, mut read_index) =
+            if image.len() >= FWNES_HEADER_SIZE && image[0..4] == *b"FDS\x1A" {
+                (true, image[4] as usize, FWNES_HEADER_SIZE)
+            } else if image.starts_with(DISK_INFO_BLOCK_MAGIC) {
+                (false, image.len().div_ceil(FDS_DISK_SIDE_SIZE), 0)
+            } else {
+                return Err(UnknownFdsFormat);
+            };
+
+        if side_count == 0 {
+            return Err(UnknownFdsFormat);
+        }
+
+        let mut disk_sides = Vec::with_capacity(side_count);
+        for side in 0..side_count {
+            let side_slice = &image[read_index..];
+            if side_slice.len() < FDS_DISK_SIDE_SIZE {
+                return Err(FdsDiskSideTooShort(side as u8, side_slice.len()));
+            }
+            disk_sides.push(side_slice[..FDS_DISK_SIDE_SIZE].to_vec());
+            read_index += FDS_DISK_SIDE_SIZE;
+        }
+
+        Ok(FdsFile {
+            disk_sides,
+            has_header,
+            bios: bios.to_vec(),
+        })
+    }
+}
@@ -0,0 +1,98 @@
+use super::{VIDEO_FRAME_HEIGHT, VIDEO_FRAME_WIDTH};
+
+const CELL_SIZE: usize = 8;
+const GLYPH_WIDTH: usize = 5;
+const GLYPH_HEIGHT: usize = 7;
+
+const FONT: [[u8; GLYPH_HEIGHT]; 64] = [
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x04, 0x04, 0x04, 0x04, 0x04, 0x00, 0x04],
+    [0x0A, 0x0A, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x0A, 0x0A, 0x1F, 0x0A, 0x1F, 0x0A, 0x0A],
+    [0x04, 0x0F, 0x14, 0x0E, 0x05, 0x1E, 0x04],
+    [0x18, 0x19, 0x02, 0x04, 0x08, 0x13, 0x03],
+    [0x0C, 0x12, 0x14, 0x08, 0x15, 0x12, 0x0D],
+    [0x04, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00],
+    [0x02, 0x04, 0x08, 0x08, 0x08, 0x04, 0x02],
+    [0x08, 0x04, 0x02, 0x02, 0x02, 0x04, 0x08],
+    [0x00, 0x04, 0x15, 0x0E, 0x15, 0x04, 0x00],
+    [0x00, 0x04, 0x04, 0x1F, 0x04, 0x04, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x0C, 0x04, 0x08],
+    [0x00, 0x00, 0x00, 0x1F, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x0C, 0x0C],
+    [0x00, 0x01, 0x02, 0x04, 0x08, 0x10, 0x00],
+    [0x0E, 0x11, 0x13, 0x15, 0x19, 0x11, 0x0E],
+    [0x04, 0x0C, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x08, 0x1F],
+    [0x1F, 0x02, 0x04, 0x02, 0x01, 0x11, 0x0E],
+    [0x02, 0x06, 0x0A, 0x12, 0x1F, 0x02, 0x02],
+    [0x1F, 0x10, 0x1E, 0x01, 0x01, 0x11, 0x0E],
+    [0x06, 0x08, 0x10, 0x1E, 0x11, 0x11, 0x0E],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x08, 0x08],
+    [0x0E, 0x11, 0x11, 0x0E, 0x11, 0x11, 0x0E],
+    [0x0E, 0x11, 0x11, 0x0F, 0x01, 0x02, 0x0C],
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x0C, 0x00],
+    [0x00, 0x0C, 0x0C, 0x00, 0x0C, 0x04, 0x08],
+    [0x02, 0x04, 0x08, 0x10, 0x08, 0x04, 0x02],
+    [0x00, 0x00, 0x1F, 0x00, 0x1F, 0x00, 0x00],
+    [0x08, 0x04, 0x02, 0x01, 0x02, 0x04, 0x08],
+    [0x0E, 0x11, 0x01, 0x02, 0x04, 0x00, 0x04],
+    [0x0E, 0x11, 0x01, 0x0D, 0x15, 0x15, 0x0E],
+    [0x0E, 0x11, 0x11, 0x11, 0x1F, 0x11, 0x11],
+    [0x1E, 0x11, 0x11, 0x1E, 0x11, 0x11, 0x1E],
+    [0x0E, 0x11, 0x10, 0x10, 0x10, 0x11, 0x0E],
+    [0x1C, 0x12, 0x11, 0x11, 0x11, 0x12, 0x1C],
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x1F],
+    [0x1F, 0x10, 0x10, 0x1E, 0x10, 0x10, 0x10],
+    [0x0E, 0x11, 0x10, 0x17, 0x11, 0x11, 0x0F],
+    [0x11, 0x11, 0x11, 0x1F, 0x11, 0x11, 0x11],
+    [0x0E, 0x04, 0x04, 0x04, 0x04, 0x04, 0x0E],
+    [0x07, 0x02, 0x02, 0x02, 0x02, 0x12, 0x0C],
+    [0x11, 0x12, 0x14, 0x18, 0x14, 0x12, 0x11],
+    [0x10, 0x10, 0x10, 0x10, 0x10, 0x10, 0x1F],
+    [0x11, 0x1B, 0x15, 0x15, 0x11, 0x11, 0x11],
+    [0x11, 0x11, 0x19, 0x15, 0x13, 0x11, 0x11],
+    [0x0E, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+    [0x1E, 0x11, 0x11, 0x1E, 0x10, 0x10, 0x10],
+    [0x0E, 0x11, 0x11, 0x11, 0x15, 0x12, 0x0D],
+    [0x1E, 0x11, 0x11, 0x1E, 0x14, 0x12, 0x11],
+    [0x0F, 0x10, 0x10, 0x0E, 0x01, 0x01, 0x1E],
+    [0x1F, 0x04, 0x04, 0x04, 0x04, 0x04, 0x04],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x11, 0x0E],
+    [0x11, 0x11, 0x11, 0x11, 0x11, 0x0A, 0x04],
+    [0x11, 0x11, 0x11, 0x15, 0x15, 0x15, 0x0A],
+    [0x11, 0x11, 0x0A, 0x04, 0x0A, 0x11, 0x11],
+    [0x11, 0x11, 0x11, 0x0A, 0x04, 0x04, 0x04],
+    [0x1F, 0x01, 0x02, 0x04, 0x08, 0x10, 0x1F],
+    [0x0E, 0x08, 0x08, 0x08, 0x08, 0x08, 0x0E],
+    [0x00, 0x10, 0x08, 0x04, 0x02, 0x01, 0x00],
+    [0x0E, 0x02, 0x02, 0x02, 0x02, 0x02, 0x0E],
+    [0x04, 0x0A, 0x11, 0x00, 0x00, 0x00, 0x00],
+    [0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x1F],
+];
+
+pub(crate) fn draw_text(x: usize, y: usize, text: &str, mut set_pixel: impl FnMut(usize, usize)) {
+    for (column, character) in text.bytes().enumerate() {
+        draw_glyph(
+            x + column * CELL_SIZE,
+            y,
+            character.to_ascii_uppercase(),
+            &mut set_pixel,
+        );
+    }
+}
+
+fn draw_glyph(x: usize, y: usize, character: u8, set_pixel: &mut impl FnMut(usize, usize)) {
+    let glyph = FONT[(character.saturating_sub(0x20) as usize) % FONT.len()];
+    for (glyph_y, glyph_row) in glyph.iter().enumerate() {
+        for glyph_x in 0..GLYPH_WIDTH {
+            let (pixel_x, pixel_y) = (x + glyph_x + 1, y + glyph_y);
+            if glyph_row & (0x10 >> glyph_x) != 0
+                && pixel_x < VIDEO_FRAME_WIDTH
+                && pixel_y < VIDEO_FRAME_HEIGHT
+            {
+                set_pixel(pixel_x, pixel_y);
+            }
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use std::ops::RangeInclusive;
+
+const PPU_REGISTERS_START: u16 = 0x2000;
+const PPU_REGISTERS_END: u16 = 0x3FFF;
+const PPU_REGISTERS_MIRROR_SIZE: u16 = 0x0008;
+const OAM_DMA_REGISTER: u16 = 0x4014;
+const CARTRIDGE_SPACE_START: u16 = 0x4020;
+const PRG_RAM_START: u16 = 0x6000;
+const PRG_RAM_END: u16 = 0x7FFF;
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum MemoryHookType {
+    /// Every byte the CPU reads, opcodes, operands and the NMI, reset and IRQ vectors included.
+    CpuRead,
+    CpuWrite,
+    CpuExecute,
+    PpuRegisterRead,
+    PpuRegisterWrite,
+    /// Writes to $4020-$5FFF and $8000-$FFFF, PRG-RAM writes are left out. Boards with registers
+    /// in $6000-$7FFF (NINA-001) need a `CpuWrite` hook.
+    MapperWrite,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum Interrupt {
+    Nmi,
+    Irq,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, Hash, Debug)]
+pub struct HookId(usize);
+
+pub type MemoryHook = Box<dyn FnMut(u16, u8) + Send>;
+pub type InterruptHook = Box<dyn FnMut(Interrupt, u16) + Send>;
+
+struct MemoryHookEntry {
+    id: HookId,
+    hook_type: MemoryHookType,
+    addresses: RangeInclusive<u16>,
+    hook: MemoryHook,
+}
+
+struct InterruptHookEntry {
+    id: HookId,
+    hook: InterruptHook,
+}
+
+#[derive(Default)]
+pub(crate) struct Hooks {
+    memory_hooks: Vec<MemoryHookEntry>,
+    interrupt_hooks: Vec<InterruptHookEntry>,
+    next_id: usize,
+}
+
+impl Hooks {
+    fn get_next_id(&mut self) -> HookId {
+        let id = HookId(self.next_id);
+        self.next_id += 1;
+        id
+    }
+
+    pub fn add_memory_hook(
+        &mut self,
+        hook_type: MemoryHookType,
+        addresses: RangeInclusive<u16>,
+        hook: MemoryHook,
+    ) -> HookId {
+        let id = self.get_next_id();
+        self.memory_hooks.push(MemoryHookEntry {
+            id,
+            hook_type,
+            addresses,
+            hook,
+        });
+        id
+    }
+
+    pub fn add_interrupt_hook(&mut self, hook: InterruptHook) -> HookId {
+        let id = self.get_next_id();
+        self.interrupt_hooks.push(InterruptHookEntry { id, hook });
+        id
+    }
+
+    pub fn remove_hook(&mut self, id: HookId) -> bool {
+        let hook_count = self.memory_hooks.len() + self.interrupt_hooks.len();
+        self.memory_hooks.retain(|entry| entry.id != id);
+        self.interrupt_hooks.retain(|entry| entry.id != id);
+        hook_count != self.memory_hooks.len() + self.interrupt_hooks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.memory_hooks.is_empty() && self.interrupt_hooks.is_empty()
+    }
+
+    fn notify_memory(&mut self, hook_type: MemoryHookType, address: u16, value: u8) {
+        self.memory_hooks
+            .iter_mut()
+            .filter(|entry| entry.hook_type == hook_type && entry.addresses.contains(&address))
+            .for_each(|entry| (entry.hook)(address, value));
+    }
+
+    pub fn notify_cpu_read(&mut self, address: u16, value: u8) {
+        self.notify_memory(MemoryHookType::CpuRead, address, value);
+        if let Some(register) = get_ppu_register(address) {
+            self.notify_memory(MemoryHookType::PpuRegisterRead, register, value);
+        }
+    }
+
+    pub fn notify_cpu_write(&mut self, address: u16, value: u8) {
+        self.notify_memory(MemoryHookType::CpuWrite, address, value);
+        if let Some(register) = get_ppu_register(address)
+            .or_else(|| (address == OAM_DMA_REGISTER).then_some(OAM_DMA_REGISTER))
+        {
+            self.notify_memory(MemoryHookType::PpuRegisterWrite, register, value);
+        } else if address >= CARTRIDGE_SPACE_START
+            && !(PRG_RAM_START..=PRG_RAM_END).contains(&address)
+        {
+            self.notify_memory(MemoryHookType::MapperWrite, address, value);
+        }
+    }
+
+    pub fn notify_cpu_execute(&mut self, address: u16, opcode: u8) {
+        self.notify_memory(MemoryHookType::CpuExecute, address, opcode);
+    }
+
+    pub fn notify_interrupt(&mut self, interrupt: Interrupt, pc: u16) {
+        self.interrupt_hooks
+            .iter_mut()
+            .for_each(|entry| (entry.hook)(interrupt, pc));
+    }
+}
+
+fn get_ppu_register(address: u16) -> Option<u16> {
+    (PPU_REGISTERS_START..=PPU_REGISTERS_END)
+        .contains(&address)
+        .then(|| PPU_REGISTERS_START + address % PPU_REGISTERS_MIRROR_SIZE)
+}
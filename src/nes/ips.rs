@@ -0,0 +1,97 @@
+use super::errors::Error;
+
+const IPS_HEADER: &[u8] = b"PATCH";
+const IPS_FOOTER: &[u8] = b"EOF";
+const IPS_EOF_OFFSET: usize = 0x454F46;
+const IPS_MAX_OFFSET: usize = 0xFFFFFF;
+const IPS_MAX_RECORD_SIZE: usize = 0xFFFF;
+const IPS_MIN_GAP: usize = 6;
+
+fn read_u16(patch: &[u8], index: usize) -> Result<usize, Error> {
+    patch
+        .get(index..index + 2)
+        .map(|b| ((b[0] as usize) << 8) | b[1] as usize)
+        .ok_or(Error::IpsPatchCorrupted(index))
+}
+
+fn read_u24(patch: &[u8], index: usize) -> Result<usize, Error> {
+    patch
+        .get(index..index + 3)
+        .map(|b| ((b[0] as usize) << 16) | ((b[1] as usize) << 8) | b[2] as usize)
+        .ok_or(Error::IpsPatchCorrupted(index))
+}
+
+fn write_u24(patch: &mut Vec<u8>, value: usize) {
+    patch.extend_from_slice(&[(value >> 16) as u8, (value >> 8) as u8, value as u8]);
+}
+
+pub fn apply_ips_patch(original: &[u8], patch: &[u8]) -> Result<Vec<u8>, Error> {
+    if !patch.starts_with(IPS_HEADER) {
+        return Err(Error::IpsPatchCorrupted(0));
+    }
+    let mut patched = original.to_vec();
+    let mut index = IPS_HEADER.len();
+    loop {
+        if patch[index..].starts_with(IPS_FOOTER) {
+            index += IPS_FOOTER.len();
+            break;
+        }
+        let offset = read_u24(patch, index)?;
+        let size = read_u16(patch, index + 3)?;
+        index += 5;
+        let (data, record_size) = if size == 0 {
+            let run_length = read_u16(patch, index)?;
+            let value = *patch
+                .get(index + 2)
+                .ok_or(Error::IpsPatchCorrupted(index))?;
+            index += 3;
+            (vec![value; run_length], run_length)
+        } else {
+            let data = patch
+                .get(index..index + size)
+                .ok_or(Error::IpsPatchCorrupted(index))?;
+            index += size;
+            (data.to_vec(), size)
+        };
+        if patched.len() < offset + record_size {
+            patched.resize(offset + record_size, 0);
+        }
+        patched[offset..offset + record_size].copy_from_slice(&data);
+    }
+    if let Ok(truncated_size) = read_u24(patch, index) {
+        patched.truncate(truncated_size);
+    }
+    Ok(patched)
+}
+
+pub fn create_ips_patch(original: &[u8], modified: &[u8]) -> Vec<u8> {
+    let mut patch = IPS_HEADER.to_vec();
+    let differs = |i: usize| original.get(i) != modified.get(i);
+    let mut index = 0;
+    while index < modified.len().min(IPS_MAX_OFFSET) {
+        if !differs(index) {
+            index += 1;
+            continue;
+        }
+        let mut start = index;
+        if start == IPS_EOF_OFFSET {
+            start -= 1;
+        }
+        let mut end = index + 1;
+        while end < modified.len()
+            && end - start < IPS_MAX_RECORD_SIZE
+            && (end..(end + IPS_MIN_GAP).min(modified.len())).any(differs)
+        {
+            end += 1;
+        }
+        write_u24(&mut patch, start);
+        patch.extend_from_slice(&[((end - start) >> 8) as u8, (end - start) as u8]);
+        patch.extend_from_slice(&modified[start..end]);
+        index = end;
+    }
+    patch.extend_from_slice(IPS_FOOTER);
+    if modified.len() < original.len() {
+        write_u24(&mut patch, modified.len());
+    }
+    patch
+}
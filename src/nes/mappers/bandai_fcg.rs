@@ -0,0 +1,268 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use super::datach_barcode_reader::DatachBarcodeReader;
+use super::eeprom_24c0x::Eeprom24C0X;
+use super::eeprom_24c0x::EepromType;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use super::mapper_internal::TRAINER_PRG_RAM_OFFSET;
+use crate::nes::common::Mirroring;
+
+use serde::{Deserialize, Serialize};
+
+const PRG_OUTER_BANK_SHIFT: u8 = 4;
+const FIXED_PRG_BANK: usize = 0x0F;
+const PRG_RAM_ENABLED_FLAG: u8 = 0b0010_0000;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub(super) enum BandaiFcgVariant {
+    Fcg,
+    FcgOrLZ93D50,
+    LZ93D50,
+    LZ93D50PrgRam,
+    Datach,
+}
+
+enum EepromControlFlag {
+    Scl = 0b0010_0000,
+    Sda = 0b0100_0000,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct BandaiFcg {
+    mapper_internal: MapperInternal,
+    variant: BandaiFcgVariant,
+    chr_banks: [u8; 8],
+    prg_bank: u8,
+    prg_outer_bank: u8,
+    prg_ram_enabled: bool,
+    mirroring: u8,
+    irq_enabled: bool,
+    irq_triggered: bool,
+    irq_counter: u16,
+    irq_latch: u16,
+    eeprom: Option<Eeprom24C0X>,
+    extra_eeprom: Option<Eeprom24C0X>,
+    barcode_reader: Option<DatachBarcodeReader>,
+}
+
+impl BandaiFcg {
+    pub fn new(
+        prg_rom: Vec<u8>,
+        chr_rom: Vec<u8>,
+        variant: BandaiFcgVariant,
+        eeprom_type: Option<EepromType>,
+    ) -> Self {
+        let is_datach = variant == BandaiFcgVariant::Datach;
+        Self {
+            mapper_internal: MapperInternal::new(prg_rom, chr_rom),
+            variant,
+            chr_banks: [0; 8],
+            prg_bank: 0,
+            prg_outer_bank: 0,
+            prg_ram_enabled: false,
+            mirroring: 0,
+            irq_enabled: false,
+            irq_triggered: false,
+            irq_counter: 0,
+            irq_latch: 0,
+            eeprom: eeprom_type.map(Eeprom24C0X::new),
+            extra_eeprom: is_datach.then(|| Eeprom24C0X::new(EepromType::_24C01)),
+            barcode_reader: is_datach.then(DatachBarcodeReader::new),
+        }
+    }
+
+    pub fn scan_barcode(&mut self, barcode: &str) {
+        if let Some(barcode_reader) = self.barcode_reader.as_mut() {
+            barcode_reader.scan(barcode);
+        }
+    }
+
+    fn has_chr_ram(&self) -> bool {
+        matches!(
+            self.variant,
+            BandaiFcgVariant::LZ93D50PrgRam | BandaiFcgVariant::Datach
+        )
+    }
+
+    fn are_registers_mapped(&self, address: u16) -> bool {
+        use BandaiFcgVariant::*;
+        match self.variant {
+            Fcg => PRG_RAM_RANGE.contains(&address),
+            FcgOrLZ93D50 => address >= PRG_RAM_RANGE.start,
+            LZ93D50 | LZ93D50PrgRam | Datach => address >= PRG_RAM_RANGE.end,
+        }
+    }
+
+    fn get_prg_rom_bank(&self, address: u16) -> usize {
+        let outer_bank = (self.prg_outer_bank << PRG_OUTER_BANK_SHIFT) as usize;
+        let last_bank = if self.variant == BandaiFcgVariant::LZ93D50PrgRam {
+            FIXED_PRG_BANK
+        } else {
+            self.mapper_internal.get_prg_rom_bank_count(_16KB) - 1
+        };
+        if address < 0xC000 {
+            outer_bank | (self.prg_bank & 0x0F) as usize
+        } else {
+            outer_bank | last_bank
+        }
+    }
+
+    fn read_eeprom(&self) -> u8 {
+        let sda = self.eeprom.as_ref().is_none_or(|e| e.read())
+            && self.extra_eeprom.as_ref().is_none_or(|e| e.read());
+        let barcode = self.barcode_reader.as_ref().map_or(0, |b| b.read());
+        (sda as u8) << 4 | barcode
+    }
+
+    fn write_register(&mut self, address: u16, byte: u8) {
+        let is_lz93d50 = !PRG_RAM_RANGE.contains(&address);
+        match address & 0x000F {
+            register @ 0x0..=0x7 => {
+                self.chr_banks[register as usize] = byte;
+                if register < 4 {
+                    if self.variant == BandaiFcgVariant::LZ93D50PrgRam {
+                        self.prg_outer_bank = byte & 1;
+                    }
+                    if let Some(extra_eeprom) = self.extra_eeprom.as_mut() {
+                        extra_eeprom.write_scl(byte & 0b0000_1000 != 0);
+                    }
+                }
+            }
+            0x8 => self.prg_bank = byte,
+            0x9 => self.mirroring = byte & 0b11,
+            0xA => {
+                self.irq_enabled = byte & 1 != 0;
+                self.irq_triggered = false;
+                if is_lz93d50 {
+                    self.irq_counter = self.irq_latch;
+                }
+            }
+            0xB => {
+                let target = if is_lz93d50 {
+                    &mut self.irq_latch
+                } else {
+                    &mut self.irq_counter
+                };
+                *target = (*target & 0xFF00) | byte as u16;
+            }
+            0xC => {
+                let target = if is_lz93d50 {
+                    &mut self.irq_latch
+                } else {
+                    &mut self.irq_counter
+                };
+                *target = (*target & 0x00FF) | (byte as u16) << 8;
+            }
+            0xD => {
+                let scl = byte & EepromControlFlag::Scl as u8 != 0;
+                let sda = byte & EepromControlFlag::Sda as u8 != 0;
+                self.prg_ram_enabled = byte & PRG_RAM_ENABLED_FLAG != 0;
+                if let Some(eeprom) = self.eeprom.as_mut() {
+                    eeprom.write(scl, sda);
+                }
+                if let Some(extra_eeprom) = self.extra_eeprom.as_mut() {
+                    extra_eeprom.write_sda(sda);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Mapper for BandaiFcg {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        if self.has_chr_ram() {
+            self.mapper_internal.get_chr_byte(address, 0, _8KB)
+        } else {
+            let bank = self.chr_banks[address as usize / _1KB as usize] as usize;
+            self.mapper_internal.get_chr_byte(address, bank, _1KB)
+        }
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        if self.has_chr_ram() {
+            self.mapper_internal.store_chr_byte(address, 0, _8KB, byte);
+        }
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if PRG_RAM_RANGE.contains(&address) {
+            if self.variant == BandaiFcgVariant::LZ93D50PrgRam {
+                if self.prg_ram_enabled {
+                    self.mapper_internal.get_prg_ram_byte(address, 0, _8KB)
+                } else {
+                    0
+                }
+            } else {
+                self.read_eeprom()
+            }
+        } else if address >= PRG_RAM_RANGE.end {
+            let bank = self.get_prg_rom_bank(address);
+            self.mapper_internal.get_prg_rom_byte(address, bank, _16KB)
+        } else {
+            0
+        }
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if self.are_registers_mapped(address) {
+            self.write_register(address, byte);
+        } else if PRG_RAM_RANGE.contains(&address)
+            && self.variant == BandaiFcgVariant::LZ93D50PrgRam
+            && self.prg_ram_enabled
+        {
+            self.mapper_internal
+                .store_prg_ram_byte(address, 0, _8KB, byte);
+        }
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        match self.mirroring {
+            0 => Mirroring::VERTICAL,
+            1 => Mirroring::HORIZONTAL,
+            2 => Mirroring::SINGLE_SCREEN_0,
+            _ => Mirroring::SINGLE_SCREEN_1,
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.mapper_internal.power_cycle();
+        self.chr_banks = [0; 8];
+        self.prg_bank = 0;
+        self.prg_outer_bank = 0;
+        self.prg_ram_enabled = false;
+        self.mirroring = 0;
+        self.irq_enabled = false;
+        self.irq_triggered = false;
+        self.irq_counter = 0;
+        self.irq_latch = 0;
+        if let Some(eeprom) = self.eeprom.as_mut() {
+            eeprom.power_cycle();
+        }
+        if let Some(extra_eeprom) = self.extra_eeprom.as_mut() {
+            extra_eeprom.power_cycle();
+        }
+    }
+
+    fn is_irq_pending(&self) -> bool {
+        self.irq_triggered
+    }
+
+    fn notify_cpu_cycle(&mut self) {
+        if self.irq_enabled {
+            if self.irq_counter == 0 {
+                self.irq_triggered = true;
+            }
+            self.irq_counter = self.irq_counter.wrapping_sub(1);
+        }
+        if let Some(barcode_reader) = self.barcode_reader.as_mut() {
+            barcode_reader.notify_cpu_cycle();
+        }
+    }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mapper_internal
+            .load_trainer(trainer, TRAINER_PRG_RAM_OFFSET);
+    }
+}
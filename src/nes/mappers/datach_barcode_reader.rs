@@ -0,0 +1,119 @@
+use serde::{Deserialize, Serialize};
+
+const CPU_CYCLES_PER_BIT: u32 = 1000;
+const QUIET_ZONE_LENGTH: usize = 32;
+const SPACE: u8 = 0b0000_1000;
+const BAR: u8 = 0;
+
+const L_CODES: [u8; 10] = [
+    0b0001101, 0b0011001, 0b0010011, 0b0111101, 0b0100011, 0b0110001, 0b0101111, 0b0111011,
+    0b0110111, 0b0001011,
+];
+
+const EAN_13_PARITY: [u8; 10] = [
+    0b000000, 0b001011, 0b001101, 0b001110, 0b010011, 0b011001, 0b011100, 0b010101, 0b010110,
+    0b011010,
+];
+
+#[derive(Clone, Copy)]
+enum DigitEncoding {
+    L,
+    G,
+    R,
+}
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub(super) struct DatachBarcodeReader {
+    stream: Vec<u8>,
+    cpu_cycle: u32,
+}
+
+impl DatachBarcodeReader {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn is_valid_barcode(barcode: &str) -> bool {
+        (barcode.len() == 13 || barcode.len() == 8) && barcode.bytes().all(|b| b.is_ascii_digit())
+    }
+
+    pub fn scan(&mut self, barcode: &str) {
+        let digits: Vec<u8> = barcode.bytes().map(|b| b - b'0').collect();
+        let mut stream = vec![SPACE; QUIET_ZONE_LENGTH + 1];
+        Self::push_guard(&mut stream, 3);
+        let checksum = if digits.len() == 13 {
+            let parity = EAN_13_PARITY[digits[0] as usize];
+            for (i, digit) in digits[1..7].iter().enumerate() {
+                let encoding = if parity & (0b100000 >> i) != 0 {
+                    DigitEncoding::G
+                } else {
+                    DigitEncoding::L
+                };
+                Self::push_digit(&mut stream, *digit, encoding);
+            }
+            Self::push_middle_guard(&mut stream);
+            for digit in &digits[7..12] {
+                Self::push_digit(&mut stream, *digit, DigitEncoding::R);
+            }
+            Self::calculate_checksum(&digits[..12], 1, 3)
+        } else {
+            for digit in &digits[..4] {
+                Self::push_digit(&mut stream, *digit, DigitEncoding::L);
+            }
+            Self::push_middle_guard(&mut stream);
+            for digit in &digits[4..7] {
+                Self::push_digit(&mut stream, *digit, DigitEncoding::R);
+            }
+            Self::calculate_checksum(&digits[..7], 3, 1)
+        };
+        Self::push_digit(&mut stream, checksum, DigitEncoding::R);
+        Self::push_guard(&mut stream, 3);
+        stream.extend([SPACE; QUIET_ZONE_LENGTH]);
+        self.stream = stream;
+        self.cpu_cycle = 0;
+    }
+
+    pub fn read(&self) -> u8 {
+        let bit = (self.cpu_cycle / CPU_CYCLES_PER_BIT) as usize;
+        self.stream.get(bit).copied().unwrap_or(0)
+    }
+
+    pub fn notify_cpu_cycle(&mut self) {
+        if (self.cpu_cycle / CPU_CYCLES_PER_BIT) < self.stream.len() as u32 {
+            self.cpu_cycle += 1;
+        }
+    }
+
+    fn calculate_checksum(digits: &[u8], even_weight: u32, odd_weight: u32) -> u8 {
+        let sum: u32 = digits
+            .iter()
+            .enumerate()
+            .map(|(i, d)| *d as u32 * if i % 2 == 0 { even_weight } else { odd_weight })
+            .sum();
+        ((10 - sum % 10) % 10) as u8
+    }
+
+    fn push_guard(stream: &mut Vec<u8>, length: usize) {
+        stream.extend((0..length).map(|i| if i % 2 == 0 { BAR } else { SPACE }));
+    }
+
+    fn push_middle_guard(stream: &mut Vec<u8>) {
+        stream.push(SPACE);
+        Self::push_guard(stream, 3);
+        stream.push(SPACE);
+    }
+
+    fn push_digit(stream: &mut Vec<u8>, digit: u8, encoding: DigitEncoding) {
+        let code = L_CODES[digit as usize];
+        let code = match encoding {
+            DigitEncoding::L => code,
+            DigitEncoding::R => !code & 0b0111_1111,
+            DigitEncoding::G => (!code & 0b0111_1111).reverse_bits() >> 1,
+        };
+        stream.extend(
+            (0..7)
+                .rev()
+                .map(|bit| if code & (1 << bit) != 0 { BAR } else { SPACE }),
+        );
+    }
+}
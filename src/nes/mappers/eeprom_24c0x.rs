@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+
+const EEPROM_24C02_DEVICE_ADDRESS: u8 = 0b1010_0000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub(super) enum EepromType {
+    _24C01 = 0x80,
+    _24C02 = 0x100,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+enum EepromMode {
+    Idle,
+    DeviceAddress,
+    WordAddress,
+    Write,
+    Read,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct Eeprom24C0X {
+    eeprom_type: EepromType,
+    data: Vec<u8>,
+    mode: EepromMode,
+    next_mode: EepromMode,
+    scl: bool,
+    sda: bool,
+    output: bool,
+    bit_counter: u8,
+    shift_register: u8,
+    address: u8,
+    is_acknowledged: bool,
+}
+
+impl Eeprom24C0X {
+    pub fn new(eeprom_type: EepromType) -> Self {
+        Self {
+            eeprom_type,
+            data: vec![0; eeprom_type as usize],
+            mode: EepromMode::Idle,
+            next_mode: EepromMode::Idle,
+            scl: false,
+            sda: false,
+            output: true,
+            bit_counter: 0,
+            shift_register: 0,
+            address: 0,
+            is_acknowledged: false,
+        }
+    }
+
+    pub fn read(&self) -> bool {
+        self.output
+    }
+
+    pub fn write_scl(&mut self, scl: bool) {
+        self.write(scl, self.sda);
+    }
+
+    pub fn write_sda(&mut self, sda: bool) {
+        self.write(self.scl, sda);
+    }
+
+    pub fn write(&mut self, scl: bool, sda: bool) {
+        match (self.scl, scl) {
+            (true, true) if self.sda && !sda => self.start(),
+            (true, true) if !self.sda && sda => self.stop(),
+            (false, true) => self.clock_rise(sda),
+            (true, false) => self.clock_fall(),
+            _ => (),
+        }
+        self.scl = scl;
+        self.sda = sda;
+    }
+
+    pub fn power_cycle(&mut self) {
+        self.mode = EepromMode::Idle;
+        self.next_mode = EepromMode::Idle;
+        self.output = true;
+        self.bit_counter = 0;
+    }
+
+    fn is_lsb_first(&self) -> bool {
+        self.eeprom_type == EepromType::_24C01
+    }
+
+    fn address_mask(&self) -> u8 {
+        (self.eeprom_type as usize - 1) as u8
+    }
+
+    fn start(&mut self) {
+        self.mode = EepromMode::DeviceAddress;
+        self.bit_counter = 0;
+        self.shift_register = 0;
+        self.output = true;
+    }
+
+    fn stop(&mut self) {
+        self.mode = EepromMode::Idle;
+        self.output = true;
+    }
+
+    fn clock_rise(&mut self, sda: bool) {
+        match self.mode {
+            EepromMode::Idle => (),
+            EepromMode::Read => {
+                if self.bit_counter == 8 {
+                    self.is_acknowledged = !sda;
+                }
+                self.bit_counter += 1;
+            }
+            _ => {
+                if self.bit_counter < 8 {
+                    let bit = if self.is_lsb_first() {
+                        self.bit_counter
+                    } else {
+                        7 - self.bit_counter
+                    };
+                    self.shift_register |= (sda as u8) << bit;
+                }
+                self.bit_counter += 1;
+                if self.bit_counter == 8 {
+                    self.receive_byte(self.shift_register);
+                }
+            }
+        }
+    }
+
+    fn clock_fall(&mut self) {
+        match self.mode {
+            EepromMode::Idle => (),
+            EepromMode::Read => {
+                if self.bit_counter == 9 {
+                    self.bit_counter = 0;
+                    self.address = self.address.wrapping_add(1) & self.address_mask();
+                    if !self.is_acknowledged {
+                        self.stop();
+                        return;
+                    }
+                }
+                self.drive_read_bit();
+            }
+            _ => {
+                if self.bit_counter == 8 {
+                    self.output = self.next_mode == EepromMode::Idle;
+                } else if self.bit_counter == 9 {
+                    self.bit_counter = 0;
+                    self.shift_register = 0;
+                    self.output = true;
+                    self.mode = self.next_mode;
+                    if self.mode == EepromMode::Read {
+                        self.drive_read_bit();
+                    }
+                }
+            }
+        }
+    }
+
+    fn drive_read_bit(&mut self) {
+        if self.bit_counter < 8 {
+            let bit = if self.is_lsb_first() {
+                self.bit_counter
+            } else {
+                7 - self.bit_counter
+            };
+            self.output = self.data[self.address as usize] & (1 << bit) != 0;
+        } else {
+            self.output = true;
+        }
+    }
+
+    fn receive_byte(&mut self, byte: u8) {
+        let is_read = if self.is_lsb_first() {
+            byte & 0b1000_0000 != 0
+        } else {
+            byte & 0b0000_0001 != 0
+        };
+        self.next_mode = match (self.mode, self.eeprom_type) {
+            (EepromMode::DeviceAddress, EepromType::_24C01) => {
+                self.address = byte & self.address_mask();
+                if is_read {
+                    EepromMode::Read
+                } else {
+                    EepromMode::Write
+                }
+            }
+            (EepromMode::DeviceAddress, EepromType::_24C02) => {
+                if byte & 0b1111_0000 != EEPROM_24C02_DEVICE_ADDRESS {
+                    EepromMode::Idle
+                } else if is_read {
+                    EepromMode::Read
+                } else {
+                    EepromMode::WordAddress
+                }
+            }
+            (EepromMode::WordAddress, _) => {
+                self.address = byte & self.address_mask();
+                EepromMode::Write
+            }
+            (EepromMode::Write, _) => {
+                self.data[self.address as usize] = byte;
+                self.address = self.address.wrapping_add(1) & self.address_mask();
+                EepromMode::Write
+            }
+            _ => EepromMode::Idle,
+        };
+    }
+}
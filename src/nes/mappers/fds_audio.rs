@@ -0,0 +1,299 @@
+use serde::{Deserialize, Serialize};
+use serde_arrays;
+
+const WAVE_TABLE_START: u16 = 0x4040;
+const WAVE_TABLE_END: u16 = 0x407F;
+const VOLUME_ENVELOPE_REGISTER: u16 = 0x4080;
+const WAVE_FREQUENCY_LOW_REGISTER: u16 = 0x4082;
+const WAVE_FREQUENCY_HIGH_REGISTER: u16 = 0x4083;
+const MOD_ENVELOPE_REGISTER: u16 = 0x4084;
+const MOD_COUNTER_REGISTER: u16 = 0x4085;
+const MOD_FREQUENCY_LOW_REGISTER: u16 = 0x4086;
+const MOD_FREQUENCY_HIGH_REGISTER: u16 = 0x4087;
+const MOD_TABLE_REGISTER: u16 = 0x4088;
+const WAVE_WRITE_REGISTER: u16 = 0x4089;
+const ENVELOPE_SPEED_REGISTER: u16 = 0x408A;
+const VOLUME_GAIN_REGISTER: u16 = 0x4090;
+const MOD_GAIN_REGISTER: u16 = 0x4092;
+
+const MASTER_VOLUME_TABLE: [u32; 4] = [36, 24, 17, 14];
+const MOD_COUNTER_INCREMENTS: [i8; 8] = [0, 1, 2, 4, 0, -4, -2, -1];
+const MOD_COUNTER_RESET: u8 = 4;
+const MAX_GAIN: u8 = 32;
+const MAX_OUTPUT_LEVEL: f32 = 63.0;
+const MAX_OUTPUT_SAMPLE: f32 = 0.36;
+
+#[derive(Clone, Serialize, Deserialize, Default)]
+struct FdsChannel {
+    speed: u8,
+    gain: u8,
+    increase: bool,
+    disabled: bool,
+    frequency: u16,
+    timer: u32,
+}
+
+impl FdsChannel {
+    fn write_control(&mut self, value: u8, master_speed: u8) {
+        self.speed = value & 0x3F;
+        self.increase = value & 0x40 != 0;
+        self.disabled = value & 0x80 != 0;
+        self.reset_timer(master_speed);
+        if self.disabled {
+            self.gain = self.speed;
+        }
+    }
+
+    fn write_frequency_low(&mut self, value: u8) {
+        self.frequency = (self.frequency & 0x0F00) | value as u16;
+    }
+
+    fn write_frequency_high(&mut self, value: u8) {
+        self.frequency = (self.frequency & 0x00FF) | (((value & 0x0F) as u16) << 8);
+    }
+
+    fn reset_timer(&mut self, master_speed: u8) {
+        self.timer = 8 * (self.speed as u32 + 1) * master_speed as u32;
+    }
+
+    fn clock(&mut self, master_speed: u8) -> bool {
+        if self.disabled || master_speed == 0 {
+            return false;
+        }
+        if self.timer > 0 {
+            self.timer -= 1;
+        }
+        if self.timer == 0 {
+            self.reset_timer(master_speed);
+            if self.increase && self.gain < MAX_GAIN {
+                self.gain += 1;
+            } else if !self.increase && self.gain > 0 {
+                self.gain -= 1;
+            }
+            return true;
+        }
+        false
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct Modulator {
+    channel: FdsChannel,
+    counter: i8,
+    disabled: bool,
+    #[serde(with = "serde_arrays")]
+    table: [u8; 64],
+    table_position: u8,
+    accumulator: u16,
+    output: i32,
+}
+
+impl Modulator {
+    fn new() -> Self {
+        Self {
+            channel: FdsChannel::default(),
+            counter: 0,
+            disabled: true,
+            table: [0; 64],
+            table_position: 0,
+            accumulator: 0,
+            output: 0,
+        }
+    }
+
+    fn set_counter(&mut self, value: i32) {
+        self.counter = (((value + 64) & 0x7F) - 64) as i8;
+    }
+
+    fn write_table(&mut self, value: u8) {
+        if self.disabled {
+            let position = self.table_position as usize;
+            self.table[position] = value & 0x07;
+            self.table[(position + 1) & 0x3F] = value & 0x07;
+            self.table_position = (self.table_position + 2) & 0x3F;
+        }
+    }
+
+    fn clock(&mut self) -> bool {
+        if self.disabled || self.channel.frequency == 0 {
+            return false;
+        }
+        let (accumulator, overflow) = self.accumulator.overflowing_add(self.channel.frequency);
+        self.accumulator = accumulator;
+        if overflow {
+            let step = self.table[self.table_position as usize];
+            if step == MOD_COUNTER_RESET {
+                self.counter = 0;
+            } else {
+                self.set_counter(
+                    self.counter as i32 + MOD_COUNTER_INCREMENTS[step as usize] as i32,
+                );
+            }
+            self.table_position = (self.table_position + 1) & 0x3F;
+        }
+        overflow
+    }
+
+    fn update_output(&mut self, wave_frequency: u16) {
+        let mut temp = self.counter as i32 * self.channel.gain as i32;
+        let remainder = temp & 0x0F;
+        temp >>= 4;
+        if remainder > 0 && temp & 0x80 == 0 {
+            temp += if self.counter < 0 { -1 } else { 2 };
+        }
+        if temp >= 192 {
+            temp -= 256;
+        } else if temp < -64 {
+            temp += 256;
+        }
+        temp *= wave_frequency as i32;
+        let remainder = temp & 0x3F;
+        temp >>= 6;
+        if remainder >= 32 {
+            temp += 1;
+        }
+        self.output = temp;
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct FdsAudio {
+    #[serde(with = "serde_arrays")]
+    wave_table: [u8; 64],
+    wave_position: u8,
+    wave_accumulator: u32,
+    wave_write_enabled: bool,
+    wave_halted: bool,
+    envelopes_halted: bool,
+    volume: FdsChannel,
+    modulator: Modulator,
+    master_volume: u8,
+    master_envelope_speed: u8,
+    output_level: u8,
+}
+
+impl FdsAudio {
+    pub fn new() -> Self {
+        Self {
+            wave_table: [0; 64],
+            wave_position: 0,
+            wave_accumulator: 0,
+            wave_write_enabled: false,
+            wave_halted: true,
+            envelopes_halted: false,
+            volume: FdsChannel::default(),
+            modulator: Modulator::new(),
+            master_volume: 0,
+            master_envelope_speed: 0xE8,
+            output_level: 0,
+        }
+    }
+
+    pub fn power_cycle(&mut self) {
+        *self = Self::new();
+    }
+
+    pub fn read(&self, address: u16) -> Option<u8> {
+        match address {
+            WAVE_TABLE_START..=WAVE_TABLE_END => {
+                let position = if self.wave_write_enabled {
+                    address - WAVE_TABLE_START
+                } else {
+                    self.wave_position as u16
+                };
+                Some(self.wave_table[position as usize] | 0x40)
+            }
+            VOLUME_GAIN_REGISTER => Some(self.volume.gain | 0x40),
+            MOD_GAIN_REGISTER => Some(self.modulator.channel.gain | 0x40),
+            _ => None,
+        }
+    }
+
+    pub fn write(&mut self, address: u16, value: u8) {
+        match address {
+            WAVE_TABLE_START..=WAVE_TABLE_END => {
+                if self.wave_write_enabled {
+                    self.wave_table[(address - WAVE_TABLE_START) as usize] = value & 0x3F;
+                }
+            }
+            VOLUME_ENVELOPE_REGISTER => {
+                self.volume.write_control(value, self.master_envelope_speed);
+            }
+            WAVE_FREQUENCY_LOW_REGISTER => self.volume.write_frequency_low(value),
+            WAVE_FREQUENCY_HIGH_REGISTER => {
+                self.volume.write_frequency_high(value);
+                self.wave_halted = value & 0x80 != 0;
+                self.envelopes_halted = value & 0x40 != 0;
+                if self.wave_halted {
+                    self.wave_position = 0;
+                    self.wave_accumulator = 0;
+                }
+                if self.envelopes_halted {
+                    self.volume.reset_timer(self.master_envelope_speed);
+                    self.modulator
+                        .channel
+                        .reset_timer(self.master_envelope_speed);
+                }
+            }
+            MOD_ENVELOPE_REGISTER => {
+                self.modulator
+                    .channel
+                    .write_control(value, self.master_envelope_speed);
+            }
+            MOD_COUNTER_REGISTER => self.modulator.set_counter((value & 0x7F) as i32),
+            MOD_FREQUENCY_LOW_REGISTER => self.modulator.channel.write_frequency_low(value),
+            MOD_FREQUENCY_HIGH_REGISTER => {
+                self.modulator.channel.write_frequency_high(value);
+                self.modulator.disabled = value & 0x80 != 0;
+                if self.modulator.disabled {
+                    self.modulator.accumulator = 0;
+                }
+            }
+            MOD_TABLE_REGISTER => self.modulator.write_table(value),
+            WAVE_WRITE_REGISTER => {
+                self.master_volume = value & 0x03;
+                self.wave_write_enabled = value & 0x80 != 0;
+            }
+            ENVELOPE_SPEED_REGISTER => self.master_envelope_speed = value,
+            _ => (),
+        }
+    }
+
+    fn update_output_level(&mut self) {
+        let gain = self.volume.gain.min(MAX_GAIN) as u32;
+        let level = gain * MASTER_VOLUME_TABLE[self.master_volume as usize];
+        self.output_level =
+            ((self.wave_table[self.wave_position as usize] as u32 * level) / 1152) as u8;
+    }
+
+    pub fn clock(&mut self) -> f32 {
+        let wave_frequency = self.volume.frequency;
+        if !self.wave_halted && !self.envelopes_halted {
+            self.volume.clock(self.master_envelope_speed);
+            if self.modulator.channel.clock(self.master_envelope_speed) {
+                self.modulator.update_output(wave_frequency);
+            }
+        }
+
+        if self.modulator.clock() {
+            self.modulator.update_output(wave_frequency);
+        }
+
+        if !self.wave_write_enabled {
+            self.update_output_level();
+        }
+
+        if !self.wave_halted && !self.wave_write_enabled {
+            let pitch = wave_frequency as i32 + self.modulator.output;
+            if pitch > 0 {
+                self.wave_accumulator += pitch as u32;
+                if self.wave_accumulator > 0xFFFF {
+                    self.wave_accumulator &= 0xFFFF;
+                    self.wave_position = (self.wave_position + 1) & 0x3F;
+                }
+            }
+        }
+
+        self.output_level as f32 * MAX_OUTPUT_SAMPLE / MAX_OUTPUT_LEVEL
+    }
+}
@@ -0,0 +1,132 @@
+use serde::{Deserialize, Serialize};
+
+const COMMAND_ADDRESS_MASK: usize = 0x7FFF;
+const UNLOCK_ADDRESS_1: usize = 0x5555;
+const UNLOCK_ADDRESS_2: usize = 0x2AAA;
+const UNLOCK_DATA_1: u8 = 0xAA;
+const UNLOCK_DATA_2: u8 = 0x55;
+const SECTOR_SIZE: usize = 0x1000;
+const MANUFACTURER_ID: u8 = 0xBF;
+const DEVICE_ID: u8 = 0xB7;
+
+enum FlashCommandCode {
+    ByteProgram = 0xA0,
+    Erase = 0x80,
+    ChipErase = 0x10,
+    SectorErase = 0x30,
+    SoftwareIdEntry = 0x90,
+    SoftwareIdExit = 0xF0,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+enum FlashState {
+    Idle,
+    Unlocked1,
+    Unlocked2,
+    ProgramPending,
+    EraseUnlocked,
+    EraseUnlocked1,
+    EraseUnlocked2,
+}
+
+pub(super) enum FlashOperation {
+    Program(usize, u8),
+    EraseSector(usize),
+    EraseChip,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct FlashSst39Sf040 {
+    state: FlashState,
+    is_software_id_mode: bool,
+}
+
+impl FlashSst39Sf040 {
+    pub fn new() -> Self {
+        Self {
+            state: FlashState::Idle,
+            is_software_id_mode: false,
+        }
+    }
+
+    pub fn power_cycle(&mut self) {
+        self.state = FlashState::Idle;
+        self.is_software_id_mode = false;
+    }
+
+    pub fn read(&self, address: usize) -> Option<u8> {
+        if !self.is_software_id_mode {
+            return None;
+        }
+        if address & 1 == 0 {
+            Some(MANUFACTURER_ID)
+        } else {
+            Some(DEVICE_ID)
+        }
+    }
+
+    pub fn write(&mut self, address: usize, byte: u8) -> Option<FlashOperation> {
+        use FlashCommandCode::*;
+        use FlashState::*;
+        let command_address = address & COMMAND_ADDRESS_MASK;
+        let (next_state, operation) = match self.state {
+            ProgramPending => (Idle, Some(FlashOperation::Program(address, byte))),
+            _ if byte == SoftwareIdExit as u8 => {
+                self.is_software_id_mode = false;
+                (Idle, None)
+            }
+            Idle | EraseUnlocked
+                if command_address == UNLOCK_ADDRESS_1 && byte == UNLOCK_DATA_1 =>
+            {
+                let next_state = if self.state == Idle {
+                    Unlocked1
+                } else {
+                    EraseUnlocked1
+                };
+                (next_state, None)
+            }
+            Unlocked1 if command_address == UNLOCK_ADDRESS_2 && byte == UNLOCK_DATA_2 => {
+                (Unlocked2, None)
+            }
+            EraseUnlocked1 if command_address == UNLOCK_ADDRESS_2 && byte == UNLOCK_DATA_2 => {
+                (EraseUnlocked2, None)
+            }
+            Unlocked2 if command_address == UNLOCK_ADDRESS_1 => match byte {
+                b if b == ByteProgram as u8 => (ProgramPending, None),
+                b if b == Erase as u8 => (EraseUnlocked, None),
+                b if b == SoftwareIdEntry as u8 => {
+                    self.is_software_id_mode = true;
+                    (Idle, None)
+                }
+                _ => (Idle, None),
+            },
+            EraseUnlocked2 if command_address == UNLOCK_ADDRESS_1 && byte == ChipErase as u8 => {
+                (Idle, Some(FlashOperation::EraseChip))
+            }
+            EraseUnlocked2 if byte == SectorErase as u8 => (
+                Idle,
+                Some(FlashOperation::EraseSector(address & !(SECTOR_SIZE - 1))),
+            ),
+            _ => (Idle, None),
+        };
+        self.state = next_state;
+        operation
+    }
+
+    pub fn apply(operation: FlashOperation, memory: &mut [u8]) {
+        match operation {
+            FlashOperation::Program(address, byte) => {
+                if let Some(target) = memory.get_mut(address) {
+                    *target &= byte;
+                }
+            }
+            FlashOperation::EraseSector(address) => {
+                let end = (address + SECTOR_SIZE).min(memory.len());
+                if address < end {
+                    memory[address..end].fill(0xFF);
+                }
+            }
+            FlashOperation::EraseChip => memory.fill(0xFF),
+        }
+    }
+}
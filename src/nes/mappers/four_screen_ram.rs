@@ -0,0 +1,42 @@
+use crate::nes::common::NametableSource;
+use crate::nes::memory::MemoryImpl;
+use serde::{Deserialize, Serialize};
+
+const NAMETABLE_SIZE: u16 = 0x0400;
+
+// Four-screen boards carry the RAM for the two nametables the console's own VRAM can not hold.
+#[derive(Clone, Serialize, Deserialize, Default)]
+pub(super) struct FourScreenRam {
+    memory: MemoryImpl<0x0800>,
+}
+
+impl FourScreenRam {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    fn get_address(source: NametableSource, offset: u16) -> Option<u16> {
+        let page = match source {
+            NametableSource::Vram2 => 0,
+            NametableSource::Vram3 => 1,
+            _ => return None,
+        };
+        Some(page * NAMETABLE_SIZE + offset % NAMETABLE_SIZE)
+    }
+
+    pub fn get_byte(&self, source: NametableSource, offset: u16) -> Option<u8> {
+        Self::get_address(source, offset).map(|address| self.memory.get_byte(address))
+    }
+
+    pub fn store_byte(&mut self, source: NametableSource, offset: u16, byte: u8) -> bool {
+        let Some(address) = Self::get_address(source, offset) else {
+            return false;
+        };
+        self.memory.store_byte(address, byte);
+        true
+    }
+
+    pub fn power_cycle(&mut self) {
+        self.memory.clear();
+    }
+}
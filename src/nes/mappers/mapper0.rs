@@ -1,12 +1,14 @@
+use super::four_screen_ram::FourScreenRam;
 use super::{Mapper, mapper_internal::BankSize::*};
-use crate::nes::common::Mirroring;
+use crate::nes::common::{Mirroring, NametableSource};
 use crate::nes::mappers::mapper_internal::MapperInternal;
 
 use serde::{Deserialize, Serialize};
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper0 {
     mapper_internal: MapperInternal,
     mirroring: Mirroring,
+    four_screen_ram: Option<FourScreenRam>,
 }
 
 impl Mapper0 {
@@ -20,6 +22,7 @@ impl Mapper0 {
         Self {
             mapper_internal,
             mirroring,
+            four_screen_ram: (mirroring == Mirroring::FOUR_SCREEN).then(FourScreenRam::new),
         }
     }
 }
@@ -29,7 +32,7 @@ impl Mapper for Mapper0 {
         self.mapper_internal.get_chr_byte(address, 0, _8KB)
     }
 
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         if address < 0xC000 {
             self.mapper_internal.get_prg_rom_byte(address, 0, _16KB)
         } else {
@@ -49,5 +52,25 @@ impl Mapper for Mapper0 {
 
     fn power_cycle(&mut self) {
         self.mapper_internal.power_cycle();
+        if let Some(ram) = &mut self.four_screen_ram {
+            ram.power_cycle();
+        }
+    }
+
+    fn get_nametable_byte(&self, source: NametableSource, offset: u16) -> Option<u8> {
+        self.four_screen_ram
+            .as_ref()
+            .and_then(|ram| ram.get_byte(source, offset))
+    }
+
+    fn store_nametable_or_bg_palette_index(
+        &mut self,
+        source: NametableSource,
+        offset: u16,
+        byte: u8,
+    ) -> bool {
+        self.four_screen_ram
+            .as_mut()
+            .is_some_and(|ram| ram.store_byte(source, offset, byte))
     }
 }
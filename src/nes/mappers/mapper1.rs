@@ -3,6 +3,7 @@ use super::PRG_RAM_RANGE;
 use super::mapper_internal::BankSize;
 use super::mapper_internal::BankSize::*;
 use super::mapper_internal::MapperInternal;
+use super::mapper_internal::TRAINER_PRG_RAM_OFFSET;
 use crate::nes::common::Mirroring;
 trait ControlRegister {
     fn get_prg_bank_mode(&self) -> u8;
@@ -30,14 +31,14 @@ impl ControlRegister for u8 {
         (self & 0b10000) >> 4
     }
 }
-#[derive(Default, serde::Serialize, serde::Deserialize)]
+#[derive(Clone, Default, serde::Serialize, serde::Deserialize)]
 struct ShiftRegister {
     value: u8,
     write_count: u8,
 }
 
 use serde::{Deserialize, Serialize};
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper1 {
     mapper_internal: MapperInternal,
     shift_register: ShiftRegister,
@@ -82,7 +83,7 @@ impl Mapper for Mapper1 {
         self.mapper_internal.get_chr_byte(address, bank, bank_size)
     }
 
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         if PRG_RAM_RANGE.contains(&address) {
             return self.mapper_internal.get_prg_ram_byte(address, 0, _8KB);
         }
@@ -156,4 +157,9 @@ impl Mapper for Mapper1 {
         self.shift_register.value = 0;
         self.shift_register.write_count = 0;
     }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mapper_internal
+            .load_trainer(trainer, TRAINER_PRG_RAM_OFFSET);
+    }
 }
@@ -2,9 +2,10 @@ use super::Mapper;
 use crate::nes::common::Mirroring;
 use crate::nes::mappers::mapper_internal::BankSize::*;
 use crate::nes::mappers::mapper_internal::MapperInternal;
+use crate::nes::mappers::mapper_internal::TRAINER_PRG_RAM_OFFSET;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper10 {
     mapper_internal: MapperInternal,
     mirroring: Mirroring,
@@ -61,7 +62,7 @@ impl Mapper for Mapper10 {
         self.mirroring
     }
 
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         if (0x6000..=0x7FFF).contains(&address) {
             return self.mapper_internal.get_prg_ram_byte(address, 0, _8KB);
         }
@@ -111,4 +112,9 @@ impl Mapper for Mapper10 {
             };
         }
     }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mapper_internal
+            .load_trainer(trainer, TRAINER_PRG_RAM_OFFSET);
+    }
 }
@@ -0,0 +1,71 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use crate::nes::common::Mirroring;
+use crate::nes::mappers::mapper_internal::BankSize::*;
+use crate::nes::mappers::mapper_internal::MapperInternal;
+use serde::{Deserialize, Serialize};
+
+const CPROM_CHR_RAM_SIZE: usize = 0x4000;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper13 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    chr_bank: usize,
+}
+
+impl Mapper13 {
+    pub fn new(prg_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let mapper_internal =
+            MapperInternal::new_with_chr_ram_size(prg_rom, Vec::new(), CPROM_CHR_RAM_SIZE);
+        Self {
+            mapper_internal,
+            mirroring,
+            chr_bank: 0,
+        }
+    }
+
+    fn get_chr_bank(&self, address: u16) -> usize {
+        if address < 0x1000 { 0 } else { self.chr_bank }
+    }
+}
+
+impl Mapper for Mapper13 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal
+            .get_chr_byte(address, self.get_chr_bank(address), _4KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address < PRG_RAM_RANGE.end {
+            return 0;
+        }
+        self.mapper_internal.get_prg_rom_byte(address, 0, _32KB)
+    }
+
+    fn power_cycle(&mut self) {
+        self.chr_bank = 0;
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        let bank = self.get_chr_bank(address);
+        self.mapper_internal
+            .store_chr_byte(address, bank, _4KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if address < PRG_RAM_RANGE.end {
+            return;
+        }
+        self.chr_bank = (byte & 0b0000_0011) as usize;
+    }
+
+    fn has_bus_conflicts(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,59 @@
+use super::Mapper;
+use super::bandai_fcg::BandaiFcg;
+use super::bandai_fcg::BandaiFcgVariant;
+use super::eeprom_24c0x::EepromType;
+use crate::nes::common::Mirroring;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename(serialize = "concretemapper"))]
+pub struct Mapper16 {
+    fcg: BandaiFcg,
+}
+
+impl Mapper16 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, submapper: u8) -> Self {
+        let (variant, eeprom_type) = match submapper {
+            4 => (BandaiFcgVariant::Fcg, None),
+            5 => (BandaiFcgVariant::LZ93D50, Some(EepromType::_24C02)),
+            _ => (BandaiFcgVariant::FcgOrLZ93D50, Some(EepromType::_24C02)),
+        };
+        Self {
+            fcg: BandaiFcg::new(prg_rom, chr_rom, variant, eeprom_type),
+        }
+    }
+}
+
+impl Mapper for Mapper16 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.fcg.get_chr_byte(address)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.fcg.get_mirroring()
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        self.fcg.peek_prg_byte(address)
+    }
+
+    fn power_cycle(&mut self) {
+        self.fcg.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.fcg.store_chr_byte(address, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        self.fcg.store_prg_byte(address, byte)
+    }
+
+    fn is_irq_pending(&self) -> bool {
+        self.fcg.is_irq_pending()
+    }
+
+    fn notify_cpu_cycle(&mut self) {
+        self.fcg.notify_cpu_cycle()
+    }
+}
@@ -0,0 +1,66 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use crate::nes::common::Mirroring;
+use crate::nes::mappers::mapper_internal::BankSize::*;
+use crate::nes::mappers::mapper_internal::MapperInternal;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper180 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    switchable_bank_1: usize,
+}
+
+impl Mapper180 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            mirroring,
+            switchable_bank_1: 0,
+        }
+    }
+}
+
+impl Mapper for Mapper180 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal.get_chr_byte(address, 0, _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address < PRG_RAM_RANGE.end {
+            return 0;
+        }
+        let bank = if address < 0xC000 {
+            0
+        } else {
+            self.switchable_bank_1
+        };
+        self.mapper_internal.get_prg_rom_byte(address, bank, _16KB)
+    }
+
+    fn power_cycle(&mut self) {
+        self.switchable_bank_1 = 0;
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal.store_chr_byte(address, 0, _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if address < PRG_RAM_RANGE.end {
+            return;
+        }
+        self.switchable_bank_1 = (byte & 0b0000_0111) as usize;
+    }
+
+    fn has_bus_conflicts(&self) -> bool {
+        true
+    }
+}
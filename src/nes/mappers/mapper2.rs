@@ -4,18 +4,25 @@ use crate::nes::mappers::mapper_internal::BankSize::*;
 use crate::nes::mappers::mapper_internal::MapperInternal;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper2 {
     mapper_internal: MapperInternal,
+    bus_conflicts: bool,
     mirroring: Mirroring,
     switchable_bank_0: usize,
 }
 
 impl Mapper2 {
-    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+    pub fn new(
+        prg_rom: Vec<u8>,
+        chr_rom: Vec<u8>,
+        mirroring: Mirroring,
+        bus_conflicts: bool,
+    ) -> Self {
         let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
         Self {
             mapper_internal,
+            bus_conflicts,
             mirroring,
             switchable_bank_0: 0,
         }
@@ -30,7 +37,7 @@ impl Mapper for Mapper2 {
     fn get_mirroring(&self) -> Mirroring {
         self.mirroring
     }
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         let bank = if address < 0xC000 {
             self.switchable_bank_0
         } else {
@@ -51,4 +58,8 @@ impl Mapper for Mapper2 {
     fn store_prg_byte(&mut self, _: u16, byte: u8) {
         self.switchable_bank_0 = byte as usize;
     }
+
+    fn has_bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
 }
@@ -0,0 +1,65 @@
+use super::Mapper;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use crate::nes::common::Mirroring;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper200 {
+    mapper_internal: MapperInternal,
+    register: u16,
+}
+
+impl Mapper200 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            register: 0,
+        }
+    }
+
+    fn get_bank(&self) -> usize {
+        (self.register & 0b111) as usize
+    }
+}
+
+impl Mapper for Mapper200 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal
+            .get_chr_byte(address, self.get_bank(), _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        if self.register & 0b1000 != 0 {
+            Mirroring::HORIZONTAL
+        } else {
+            Mirroring::VERTICAL
+        }
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address >= 0x8000 {
+            self.mapper_internal
+                .get_prg_rom_byte(address, self.get_bank(), _16KB)
+        } else {
+            0
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.register = 0;
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal
+            .store_chr_byte(address, self.get_bank(), _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, _: u8) {
+        if address >= 0x8000 {
+            self.register = address;
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use super::Mapper;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use crate::nes::common::Mirroring;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper201 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    bank: usize,
+}
+
+impl Mapper201 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            mirroring,
+            bank: 0,
+        }
+    }
+}
+
+impl Mapper for Mapper201 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal.get_chr_byte(address, self.bank, _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address >= 0x8000 {
+            self.mapper_internal
+                .get_prg_rom_byte(address, self.bank, _32KB)
+        } else {
+            0
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.bank = 0;
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal
+            .store_chr_byte(address, self.bank, _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, _: u8) {
+        if address >= 0x8000 {
+            self.bank = (address & 0xFF) as usize;
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use super::four_screen_ram::FourScreenRam;
+use crate::nes::common::{Mirroring, NametableSource};
+use crate::nes::mappers::mapper_internal::BankSize;
+use crate::nes::mappers::mapper_internal::BankSize::*;
+use crate::nes::mappers::mapper_internal::MapperInternal;
+use serde::{Deserialize, Serialize};
+
+const INITIAL_BANK_REGISTERS: [u8; 8] = [0, 2, 4, 5, 6, 7, 0, 1];
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper206 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    four_screen_ram: Option<FourScreenRam>,
+    prg_rom_banks_count: usize,
+    bank_select: u8,
+    bank_registers: [u8; 8],
+}
+
+impl Mapper206 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        let prg_rom_banks_count = mapper_internal.get_prg_rom_bank_count(_8KB);
+        Self {
+            mapper_internal,
+            mirroring,
+            four_screen_ram: (mirroring == Mirroring::FOUR_SCREEN).then(FourScreenRam::new),
+            prg_rom_banks_count,
+            bank_select: 0,
+            bank_registers: INITIAL_BANK_REGISTERS,
+        }
+    }
+
+    fn get_chr_bank(&self, address: u16) -> (usize, BankSize) {
+        match address {
+            0x0000..=0x07FF => ((self.bank_registers[0] >> 1) as usize, _2KB),
+            0x0800..=0x0FFF => ((self.bank_registers[1] >> 1) as usize, _2KB),
+            _ => {
+                let register = 2 + (address as usize - 0x1000) / _1KB as usize;
+                (self.bank_registers[register] as usize, _1KB)
+            }
+        }
+    }
+
+    fn get_prg_bank(&self, address: u16) -> usize {
+        match address {
+            0x8000..=0x9FFF => self.bank_registers[6] as usize,
+            0xA000..=0xBFFF => self.bank_registers[7] as usize,
+            0xC000..=0xDFFF => self.prg_rom_banks_count - 2,
+            _ => self.prg_rom_banks_count - 1,
+        }
+    }
+}
+
+impl Mapper for Mapper206 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        let (bank, size) = self.get_chr_bank(address);
+        self.mapper_internal.get_chr_byte(address, bank, size)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address < PRG_RAM_RANGE.end {
+            return 0;
+        }
+        let bank = self.get_prg_bank(address);
+        self.mapper_internal.get_prg_rom_byte(address, bank, _8KB)
+    }
+
+    fn power_cycle(&mut self) {
+        self.bank_select = 0;
+        self.bank_registers = INITIAL_BANK_REGISTERS;
+        self.mapper_internal.power_cycle();
+        if let Some(ram) = &mut self.four_screen_ram {
+            ram.power_cycle();
+        }
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal.store_chr_byte(address, 0, _8KB, byte)
+    }
+
+    fn get_nametable_byte(&self, source: NametableSource, offset: u16) -> Option<u8> {
+        self.four_screen_ram
+            .as_ref()
+            .and_then(|ram| ram.get_byte(source, offset))
+    }
+
+    fn store_nametable_or_bg_palette_index(
+        &mut self,
+        source: NametableSource,
+        offset: u16,
+        byte: u8,
+    ) -> bool {
+        self.four_screen_ram
+            .as_mut()
+            .is_some_and(|ram| ram.store_byte(source, offset, byte))
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if !(0x8000..=0x9FFF).contains(&address) {
+            return;
+        }
+        if address.is_multiple_of(2) {
+            self.bank_select = byte & 0b0000_0111;
+        } else {
+            let mask = if self.bank_select < 6 {
+                0b0011_1111
+            } else {
+                0b0000_1111
+            };
+            self.bank_registers[self.bank_select as usize] = byte & mask;
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use super::Mapper;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use crate::nes::common::Mirroring;
+use serde::{Deserialize, Serialize};
+
+const PRG_CHIP_BANK_COUNT: usize = 32;
+const RAM_REGISTERS_END: u16 = 0x6000;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper228 {
+    mapper_internal: MapperInternal,
+    register: u16,
+    chr_bank: usize,
+    ram: [u8; 4],
+}
+
+impl Mapper228 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            register: 0,
+            chr_bank: 0,
+            ram: [0; 4],
+        }
+    }
+
+    fn get_prg_bank(&self, address: u16) -> usize {
+        let chip = match (self.register >> 11) & 0b11 {
+            3 => 2,
+            chip => chip as usize,
+        };
+        let bank = chip * PRG_CHIP_BANK_COUNT + ((self.register >> 6) & 0b1_1111) as usize;
+        if self.register & 0b10_0000 != 0 {
+            bank
+        } else if address < 0xC000 {
+            bank & !1
+        } else {
+            bank | 1
+        }
+    }
+}
+
+impl Mapper for Mapper228 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal
+            .get_chr_byte(address, self.chr_bank, _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        if self.register & 0b10_0000_0000_0000 != 0 {
+            Mirroring::HORIZONTAL
+        } else {
+            Mirroring::VERTICAL
+        }
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address < RAM_REGISTERS_END {
+            self.ram[(address & 0b11) as usize]
+        } else if address >= 0x8000 {
+            let bank = self.get_prg_bank(address);
+            self.mapper_internal.get_prg_rom_byte(address, bank, _16KB)
+        } else {
+            0
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.register = 0;
+        self.chr_bank = 0;
+        self.ram = [0; 4];
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal
+            .store_chr_byte(address, self.chr_bank, _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if address < RAM_REGISTERS_END {
+            self.ram[(address & 0b11) as usize] = byte & 0x0F;
+        } else if address >= 0x8000 {
+            self.register = address;
+            self.chr_bank = (((address & 0x0F) << 2) | (byte as u16 & 0b11)) as usize;
+        }
+    }
+}
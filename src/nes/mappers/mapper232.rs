@@ -0,0 +1,72 @@
+use super::Mapper;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use crate::nes::common::Mirroring;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper232 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    is_aladdin_deck_enhancer: bool,
+    outer_bank: usize,
+    inner_bank: usize,
+}
+
+impl Mapper232 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring, submapper: u8) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            mirroring,
+            is_aladdin_deck_enhancer: submapper == 1,
+            outer_bank: 0,
+            inner_bank: 0,
+        }
+    }
+}
+
+impl Mapper for Mapper232 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal.get_chr_byte(address, 0, _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address >= 0x8000 {
+            let inner_bank = if address < 0xC000 { self.inner_bank } else { 3 };
+            let bank = (self.outer_bank << 2) | inner_bank;
+            self.mapper_internal.get_prg_rom_byte(address, bank, _16KB)
+        } else {
+            0
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.outer_bank = 0;
+        self.inner_bank = 0;
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal.store_chr_byte(address, 0, _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        match address {
+            0x8000..=0xBFFF => {
+                let outer_bank = (byte >> 3) & 0b11;
+                self.outer_bank = if self.is_aladdin_deck_enhancer {
+                    ((outer_bank & 1) << 1 | outer_bank >> 1) as usize
+                } else {
+                    outer_bank as usize
+                };
+            }
+            0xC000..=0xFFFF => self.inner_bank = (byte & 0b11) as usize,
+            _ => (),
+        }
+    }
+}
@@ -4,18 +4,25 @@ use crate::nes::mappers::mapper_internal::BankSize::*;
 use crate::nes::mappers::mapper_internal::MapperInternal;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper3 {
     mapper_internal: MapperInternal,
+    bus_conflicts: bool,
     mirroring: Mirroring,
     chr_bank: usize,
 }
 
 impl Mapper3 {
-    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+    pub fn new(
+        prg_rom: Vec<u8>,
+        chr_rom: Vec<u8>,
+        mirroring: Mirroring,
+        bus_conflicts: bool,
+    ) -> Self {
         let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
         Self {
             mapper_internal,
+            bus_conflicts,
             mirroring,
             chr_bank: 0,
         }
@@ -31,7 +38,7 @@ impl Mapper for Mapper3 {
     fn get_mirroring(&self) -> Mirroring {
         self.mirroring
     }
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         if (0x6000..=0x7FFF).contains(&address) {
             return self
                 .mapper_internal
@@ -55,4 +62,12 @@ impl Mapper for Mapper3 {
         }
         self.chr_bank = (byte & 0x3) as usize;
     }
+
+    fn has_bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mapper_internal.load_trainer(trainer, 0);
+    }
 }
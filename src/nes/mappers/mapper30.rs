@@ -0,0 +1,161 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use super::flash_sst39sf040::FlashSst39Sf040;
+use crate::nes::common::Mirroring;
+use crate::nes::common::NametableSource;
+use crate::nes::mappers::mapper_internal::BankSize::*;
+use crate::nes::mappers::mapper_internal::MapperInternal;
+use serde::{Deserialize, Serialize};
+
+const UNROM_512_CHR_RAM_SIZE: usize = 0x8000;
+const FOUR_SCREEN_CHR_RAM_BANK: usize = 3;
+const BANK_REGISTER_START: u16 = 0xC000;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub(crate) enum Mapper30Mirroring {
+    Fixed(Mirroring),
+    SingleScreen,
+    FourScreen,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper30 {
+    mapper_internal: MapperInternal,
+    mirroring: Mapper30Mirroring,
+    register: u8,
+    flash: Option<FlashSst39Sf040>,
+    image_header: Vec<u8>,
+    image_footer: Vec<u8>,
+}
+
+impl Mapper30 {
+    pub fn new(
+        prg_rom: Vec<u8>,
+        mirroring: Mapper30Mirroring,
+        is_flashable: bool,
+        image_header: Vec<u8>,
+        image_footer: Vec<u8>,
+    ) -> Self {
+        let mapper_internal =
+            MapperInternal::new_with_chr_ram_size(prg_rom, Vec::new(), UNROM_512_CHR_RAM_SIZE);
+        Self {
+            mapper_internal,
+            mirroring,
+            register: 0,
+            flash: is_flashable.then(FlashSst39Sf040::new),
+            image_header,
+            image_footer,
+        }
+    }
+
+    fn get_prg_bank(&self, address: u16) -> usize {
+        if address < 0xC000 {
+            (self.register & 0b0001_1111) as usize
+        } else {
+            self.mapper_internal.get_prg_rom_bank_count(_16KB) - 1
+        }
+    }
+
+    fn get_chr_bank(&self) -> usize {
+        ((self.register & 0b0110_0000) >> 5) as usize
+    }
+}
+
+impl Mapper for Mapper30 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal
+            .get_chr_byte(address, self.get_chr_bank(), _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        match self.mirroring {
+            Mapper30Mirroring::Fixed(mirroring) => mirroring,
+            Mapper30Mirroring::SingleScreen if self.register & 0b1000_0000 != 0 => {
+                Mirroring::SINGLE_SCREEN_1
+            }
+            _ => Mirroring::SINGLE_SCREEN_0,
+        }
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address < PRG_RAM_RANGE.end {
+            return 0;
+        }
+        let bank = self.get_prg_bank(address);
+        if let Some(flash) = self.flash.as_ref() {
+            let index = self.mapper_internal.get_prg_rom_index(address, bank, _16KB);
+            if let Some(byte) = flash.read(index) {
+                return byte;
+            }
+        }
+        self.mapper_internal.get_prg_rom_byte(address, bank, _16KB)
+    }
+
+    fn power_cycle(&mut self) {
+        self.register = 0;
+        if let Some(flash) = self.flash.as_mut() {
+            flash.power_cycle();
+        }
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal
+            .store_chr_byte(address, self.get_chr_bank(), _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if address < PRG_RAM_RANGE.end {
+            return;
+        }
+        let Some(flash) = self.flash.as_mut() else {
+            self.register = byte;
+            return;
+        };
+        if address >= BANK_REGISTER_START {
+            self.register = byte;
+            return;
+        }
+        let bank = (self.register & 0b0001_1111) as usize;
+        let index = self.mapper_internal.get_prg_rom_index(address, bank, _16KB);
+        if let Some(operation) = flash.write(index, byte) {
+            FlashSst39Sf040::apply(operation, self.mapper_internal.get_prg_rom_mut());
+        }
+    }
+
+    fn get_nametable_byte(&self, _source: NametableSource, offset: u16) -> Option<u8> {
+        if self.mirroring != Mapper30Mirroring::FourScreen {
+            return None;
+        }
+        Some(
+            self.mapper_internal
+                .get_chr_ram_byte(offset, FOUR_SCREEN_CHR_RAM_BANK, _8KB),
+        )
+    }
+
+    fn store_nametable_or_bg_palette_index(
+        &mut self,
+        _source: NametableSource,
+        offset: u16,
+        byte: u8,
+    ) -> bool {
+        if self.mirroring != Mapper30Mirroring::FourScreen {
+            return false;
+        }
+        self.mapper_internal
+            .store_chr_byte(offset, FOUR_SCREEN_CHR_RAM_BANK, _8KB, byte);
+        true
+    }
+
+    fn has_bus_conflicts(&self) -> bool {
+        self.flash.is_none()
+    }
+
+    fn get_flash_image(&self) -> Option<Vec<u8>> {
+        self.flash.as_ref()?;
+        let mut image = self.image_header.clone();
+        image.extend_from_slice(self.mapper_internal.get_prg_rom());
+        image.extend_from_slice(&self.image_footer);
+        Some(image)
+    }
+}
@@ -0,0 +1,115 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use crate::nes::common::CHR_ROM_UNIT_SIZE;
+use crate::nes::common::Mirroring;
+use crate::nes::mappers::mapper_internal::BankSize::*;
+use crate::nes::mappers::mapper_internal::MapperInternal;
+use crate::nes::mappers::mapper_internal::TRAINER_PRG_RAM_OFFSET;
+use serde::{Deserialize, Serialize};
+
+const NINA_001_PRG_BANK_REGISTER: u16 = 0x7FFD;
+const NINA_001_CHR_BANK_0_REGISTER: u16 = 0x7FFE;
+const NINA_001_CHR_BANK_1_REGISTER: u16 = 0x7FFF;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+enum Mapper34Variant {
+    Nina001,
+    Bnrom,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper34 {
+    mapper_internal: MapperInternal,
+    variant: Mapper34Variant,
+    mirroring: Mirroring,
+    prg_bank: usize,
+    chr_banks: [usize; 2],
+}
+
+impl Mapper34 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring, submapper: u8) -> Self {
+        let variant = match submapper {
+            1 => Mapper34Variant::Nina001,
+            2 => Mapper34Variant::Bnrom,
+            _ if chr_rom.len() > CHR_ROM_UNIT_SIZE => Mapper34Variant::Nina001,
+            _ => Mapper34Variant::Bnrom,
+        };
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            variant,
+            mirroring,
+            prg_bank: 0,
+            chr_banks: [0, 1],
+        }
+    }
+}
+
+impl Mapper for Mapper34 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        let bank = self.chr_banks[address as usize / _4KB as usize];
+        self.mapper_internal.get_chr_byte(address, bank, _4KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if PRG_RAM_RANGE.contains(&address) {
+            if self.variant == Mapper34Variant::Nina001 {
+                return self.mapper_internal.get_prg_ram_byte(address, 0, _8KB);
+            }
+            return 0;
+        } else if address < PRG_RAM_RANGE.start {
+            return 0;
+        }
+        self.mapper_internal
+            .get_prg_rom_byte(address, self.prg_bank, _32KB)
+    }
+
+    fn power_cycle(&mut self) {
+        self.prg_bank = 0;
+        self.chr_banks = [0, 1];
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        let bank = self.chr_banks[address as usize / _4KB as usize];
+        self.mapper_internal
+            .store_chr_byte(address, bank, _4KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        match self.variant {
+            Mapper34Variant::Nina001 => {
+                if !PRG_RAM_RANGE.contains(&address) {
+                    return;
+                }
+                self.mapper_internal
+                    .store_prg_ram_byte(address, 0, _8KB, byte);
+                match address {
+                    NINA_001_PRG_BANK_REGISTER => self.prg_bank = (byte & 1) as usize,
+                    NINA_001_CHR_BANK_0_REGISTER => self.chr_banks[0] = (byte & 0x0F) as usize,
+                    NINA_001_CHR_BANK_1_REGISTER => self.chr_banks[1] = (byte & 0x0F) as usize,
+                    _ => {}
+                }
+            }
+            Mapper34Variant::Bnrom => {
+                if address < PRG_RAM_RANGE.end {
+                    return;
+                }
+                self.prg_bank = byte as usize;
+            }
+        }
+    }
+
+    fn has_bus_conflicts(&self) -> bool {
+        self.variant == Mapper34Variant::Bnrom
+    }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mapper_internal
+            .load_trainer(trainer, TRAINER_PRG_RAM_OFFSET);
+    }
+}
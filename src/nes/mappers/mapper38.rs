@@ -0,0 +1,65 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use crate::nes::common::Mirroring;
+use crate::nes::mappers::mapper_internal::BankSize::*;
+use crate::nes::mappers::mapper_internal::MapperInternal;
+use serde::{Deserialize, Serialize};
+
+const BANK_REGISTER_START: u16 = 0x7000;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper38 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    prg_bank: usize,
+    chr_bank: usize,
+}
+
+impl Mapper38 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            mirroring,
+            prg_bank: 0,
+            chr_bank: 0,
+        }
+    }
+}
+
+impl Mapper for Mapper38 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal
+            .get_chr_byte(address, self.chr_bank, _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address < PRG_RAM_RANGE.end {
+            return 0;
+        }
+        self.mapper_internal
+            .get_prg_rom_byte(address, self.prg_bank, _32KB)
+    }
+
+    fn power_cycle(&mut self) {
+        self.prg_bank = 0;
+        self.chr_bank = 0;
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal
+            .store_chr_byte(address, self.chr_bank, _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if (BANK_REGISTER_START..PRG_RAM_RANGE.end).contains(&address) {
+            self.prg_bank = (byte & 0b0000_0011) as usize;
+            self.chr_bank = ((byte & 0b0000_1100) >> 2) as usize;
+        }
+    }
+}
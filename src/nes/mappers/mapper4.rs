@@ -1,19 +1,31 @@
 use super::Mapper;
+use super::four_screen_ram::FourScreenRam;
 use super::mmc3_6::MMC3_6;
 use super::mmc3_6::MMC3_6Variant;
-use crate::nes::common::Mirroring;
+use super::mmc3_6::MMC3IrqRevision;
+use crate::nes::common::{Mirroring, NametableSource};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 #[serde(rename(serialize = "concretemapper"))]
 pub struct Mapper4 {
     mmc3: MMC3_6,
+    four_screen_ram: Option<FourScreenRam>,
 }
 
 impl Mapper4 {
-    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>) -> Self {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, submapper: u8, four_screen: bool) -> Self {
+        let variant = match submapper {
+            1 => MMC3_6Variant::MMC6,
+            _ => MMC3_6Variant::MMC3HkROM,
+        };
+        let irq_revision = match submapper {
+            4 => MMC3IrqRevision::A,
+            _ => MMC3IrqRevision::B,
+        };
         Self {
-            mmc3: MMC3_6::new(prg_rom, chr_rom, MMC3_6Variant::MMC3HkROM),
+            mmc3: MMC3_6::new(prg_rom, chr_rom, variant, irq_revision),
+            four_screen_ram: four_screen.then(FourScreenRam::new),
         }
     }
 }
@@ -24,14 +36,21 @@ impl Mapper for Mapper4 {
     }
 
     fn get_mirroring(&self) -> Mirroring {
-        self.mmc3.get_mirroring()
+        if self.four_screen_ram.is_some() {
+            Mirroring::FOUR_SCREEN
+        } else {
+            self.mmc3.get_mirroring()
+        }
     }
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
-        self.mmc3.get_prg_byte(address)
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        self.mmc3.peek_prg_byte(address)
     }
 
     fn power_cycle(&mut self) {
         self.mmc3.power_cycle();
+        if let Some(ram) = &mut self.four_screen_ram {
+            ram.power_cycle();
+        }
     }
 
     fn store_chr_byte(&mut self, address: u16, byte: u8) {
@@ -49,4 +68,25 @@ impl Mapper for Mapper4 {
     fn is_irq_pending(&self) -> bool {
         self.mmc3.is_irq_pending()
     }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mmc3.load_trainer(trainer)
+    }
+
+    fn get_nametable_byte(&self, source: NametableSource, offset: u16) -> Option<u8> {
+        self.four_screen_ram
+            .as_ref()
+            .and_then(|ram| ram.get_byte(source, offset))
+    }
+
+    fn store_nametable_or_bg_palette_index(
+        &mut self,
+        source: NametableSource,
+        offset: u16,
+        byte: u8,
+    ) -> bool {
+        self.four_screen_ram
+            .as_mut()
+            .is_some_and(|ram| ram.store_byte(source, offset, byte))
+    }
 }
@@ -1,16 +1,8 @@
 use super::Mapper;
 use super::mapper_internal::BankSize;
 use super::mapper_internal::MapperInternal;
-use crate::nes::apu::DUTY_CYCLE_SEQUENCES;
-use crate::nes::apu::Envelope;
-use crate::nes::apu::FRAME_COUNTER_HALF_FRAME_0_MOD_0_CPU_CYCLES;
-use crate::nes::apu::FRAME_COUNTER_HALF_FRAME_1_CPU_CYCLES;
-use crate::nes::apu::FRAME_COUNTER_QUARTER_FRAME_1_CPU_CYCLES;
-use crate::nes::apu::FRAME_COUNTER_QUARTER_FRAME_3_CPU_CYCLES;
-use crate::nes::apu::LengthCounterChannel;
-use crate::nes::apu::StatusRegister;
-use crate::nes::apu::StatusRegisterFlag::{Pulse1Enabled, Pulse2Enabled};
-
+use super::mapper_internal::TRAINER_PRG_RAM_OFFSET;
+use super::mmc5_audio::Mmc5Audio;
 use crate::nes::common::Mirroring;
 use crate::nes::common::NametableSource;
 use crate::nes::mappers::PRG_RAM_RANGE;
@@ -23,11 +15,6 @@ use BankSize::*;
 use serde::{Deserialize, Serialize};
 use serde_arrays;
 
-const PULSE_REGISTER_1: u16 = 0x5000;
-const PULSE_REGISTER_8: u16 = 0x5007;
-const AUDIO_STATUS_REGISTER: u16 = 0x5015;
-const PCM_MODE_REGISTER: u16 = 0x5010;
-const PCM_RAW_REGISTER: u16 = 0x5011;
 const PRG_MODE_SELECTION_REGISTER: u16 = 0x5100;
 const CHR_MODE_SELECTION_REGISTER: u16 = 0x5101;
 const PRG_RAM_PROTECT_REGISTER_1: u16 = 0x5102;
@@ -50,123 +37,7 @@ const MULTIPLIER_A_REGISTER: u16 = 0x5205;
 const MULTIPLIER_B_REGISTER: u16 = 0x5206;
 const EXPANSION_RAM_START: u16 = 0x5C00;
 const EXPANSION_RAM_END: u16 = 0x5FFF;
-#[derive(Serialize, Deserialize)]
-struct PulseWave {
-    data: [u8; 4],
-    length_counter: u8,
-    sequencer_position: u8,
-    timer_tick: u16,
-    envelope: Envelope,
-    current_period: u16,
-}
-
-impl PulseWave {
-    fn new() -> Self {
-        PulseWave {
-            data: [0; 4],
-            length_counter: 0,
-            timer_tick: 0,
-            sequencer_position: 0,
-            envelope: Envelope::default(),
-            current_period: 0,
-        }
-    }
-
-    fn power_cycle(&mut self) {
-        self.data = [0; 4];
-        self.length_counter = 0;
-        self.timer_tick = 0;
-        self.sequencer_position = 0;
-        self.current_period = 0;
-        self.envelope = Envelope::default();
-    }
-    fn update_period(&mut self) {
-        self.current_period = self.get_raw_timer_period();
-    }
-
-    fn reset_phase(&mut self) {
-        self.sequencer_position = 0;
-        self.envelope.start_flag = true;
-    }
-
-    fn get_duty_cycle(&self) -> u8 {
-        (self.data[0] & 0b11000000) >> 6
-    }
-
-    fn is_length_counter_halt_envelope_loop_flag_set(&self) -> bool {
-        (self.data[0] & 0b00100000) != 0
-    }
-
-    fn is_constant_volume_set(&self) -> bool {
-        (self.data[0] & 0b00010000) != 0
-    }
-
-    fn get_constant_volume_or_envelope_divider_reload_value(&self) -> u8 {
-        self.data[0] & 0x0F
-    }
-
-    fn get_raw_timer_period(&self) -> u16 {
-        let timer_hi = ((self.data[3] & 0x7) as u16) << 8;
-        self.data[2] as u16 + timer_hi
-    }
-
-    fn clock_timer(&mut self) {
-        if self.timer_tick == 0 {
-            if self.sequencer_position > 0 {
-                self.sequencer_position -= 1;
-            } else {
-                self.sequencer_position = 7;
-            }
-            self.timer_tick = self.current_period;
-        } else {
-            self.timer_tick -= 1;
-        }
-    }
-
-    fn get_sample(&self) -> u8 {
-        DUTY_CYCLE_SEQUENCES[self.get_duty_cycle() as usize][self.sequencer_position as usize]
-            * self.get_volume()
-    }
-
-    fn get_volume(&self) -> u8 {
-        if self.length_counter == 0 {
-            0
-        } else if self.is_constant_volume_set() {
-            self.get_constant_volume_or_envelope_divider_reload_value()
-        } else {
-            self.envelope.decay_level_counter
-        }
-    }
-
-    fn clock_envelope(&mut self) {
-        self.envelope.clock(
-            self.get_constant_volume_or_envelope_divider_reload_value(),
-            self.is_length_counter_halt_envelope_loop_flag_set(),
-        )
-    }
-
-    fn clock_length_counter(&mut self) {
-        if self.length_counter > 0 && !self.is_length_counter_halt_envelope_loop_flag_set() {
-            self.length_counter -= 1;
-        }
-    }
-}
-
-impl LengthCounterChannel for PulseWave {
-    fn get_length_counter_load(&self) -> u8 {
-        (self.data[3] & 0b11111000) >> 3
-    }
-
-    fn set_length_counter(&mut self, counter: u8) {
-        self.length_counter = counter
-    }
-
-    fn get_length_counter(&self) -> u8 {
-        self.length_counter
-    }
-}
-
-#[derive(PartialEq, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 enum FetchMode {
     Cpu,
     Background,
@@ -181,7 +52,7 @@ enum CpuExRamAccess {
     ReadWrite = 3,
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper5 {
     mapper_internal: MapperInternal,
     prg_selection_mode: u8,
@@ -213,13 +84,8 @@ pub struct Mapper5 {
     vertical_split_tile_index: u8,
     multiplier_a: u8,
     multiplier_b: u8,
-    pulse_1: PulseWave,
-    pulse_2: PulseWave,
-    cpu_cycle: u16,
-    audio_status_register: StatusRegister,
-    pcm_mode_register: u8,
-    raw_pcm: u8,
-    pcm_irq_pending: bool,
+    #[serde(flatten)]
+    audio: Mmc5Audio,
 }
 
 impl Mapper5 {
@@ -255,13 +121,7 @@ impl Mapper5 {
             vertical_split_tile_index: 0,
             multiplier_a: 0xFF,
             multiplier_b: 0xFF,
-            cpu_cycle: 8,
-            pulse_1: PulseWave::new(),
-            pulse_2: PulseWave::new(),
-            audio_status_register: StatusRegister { data: 0 },
-            pcm_mode_register: 0,
-            raw_pcm: 0,
-            pcm_irq_pending: false,
+            audio: Mmc5Audio::new(),
         }
     }
 
@@ -367,16 +227,6 @@ impl Mapper5 {
         }
         false
     }
-    fn is_half_frame_reached(&self) -> bool {
-        self.cpu_cycle == FRAME_COUNTER_HALF_FRAME_1_CPU_CYCLES
-            || self.cpu_cycle == FRAME_COUNTER_HALF_FRAME_0_MOD_0_CPU_CYCLES
-    }
-
-    fn is_quarter_frame_reached(&self) -> bool {
-        self.is_half_frame_reached()
-            || self.cpu_cycle == FRAME_COUNTER_QUARTER_FRAME_1_CPU_CYCLES
-            || self.cpu_cycle == FRAME_COUNTER_QUARTER_FRAME_3_CPU_CYCLES
-    }
 }
 
 impl Mapper for Mapper5 {
@@ -421,6 +271,26 @@ impl Mapper for Mapper5 {
     }
 
     fn get_prg_byte(&mut self, address: u16) -> u8 {
+        let byte = self.peek_prg_byte(address);
+        match address {
+            IRQ_SCANLINE_STATUS_REGISTER => self.scanline_irq_pending = false,
+            0x5000..=0x5015 => {
+                self.audio.read(address);
+            }
+            0xFFFA | 0xFFFB => {
+                self.in_frame = false;
+                self.scanline_irq_pending = false;
+                self.scanline_counter = 0;
+            }
+            _ => (),
+        }
+        if PRG_RANGE.contains(&address) {
+            self.audio.notify_prg_read(address, byte);
+        }
+        byte
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         match address {
             IRQ_SCANLINE_STATUS_REGISTER => {
                 let mut byte: u8 = 0;
@@ -430,7 +300,6 @@ impl Mapper for Mapper5 {
                 if self.in_frame {
                     byte |= 0b0100_0000
                 }
-                self.scanline_irq_pending = false;
                 byte
             }
             EXPANSION_RAM_START..=EXPANSION_RAM_END => {
@@ -450,71 +319,26 @@ impl Mapper for Mapper5 {
                 let result = self.multiplier_a as u16 * self.multiplier_b as u16;
                 (result >> 8) as u8
             }
-            AUDIO_STATUS_REGISTER => {
-                let mut out = StatusRegister { data: 0 };
-                out.set_flag_status(Pulse1Enabled, self.pulse_1.length_counter > 0);
-                out.set_flag_status(Pulse2Enabled, self.pulse_2.length_counter > 0);
-                out.data
-            }
-            PCM_MODE_REGISTER => {
-                let mut out = self.pcm_mode_register & 1;
-                let irq_enabled = self.pcm_mode_register & 0b1000_0000 != 0;
-                if irq_enabled && self.pcm_irq_pending {
-                    out |= 0b1000_0000;
-                }
-                self.pcm_irq_pending = false;
-                out
-            }
+            0x5000..=0x5015 => self.audio.peek(address).unwrap_or(0),
             0x5016..=0x5BFF => 0,
             0x4020..=0x4FFF => 0,
             address if PRG_RANGE.contains(&address) => {
-                if address == 0xFFFA || address == 0xFFFB {
-                    self.in_frame = false;
-                    self.scanline_irq_pending = false;
-                    self.scanline_counter = 0;
-                }
                 let (index, bank_size) = self.get_prg_bank_register_index_and_size(address);
                 let (bank, is_rom) = self.decode_prg_bank_register(index as u8, bank_size);
-                let byte = if is_rom {
+                if is_rom {
                     self.mapper_internal
                         .get_prg_rom_byte(address, bank, bank_size)
                 } else {
                     self.mapper_internal
                         .get_prg_ram_byte(address, bank, bank_size)
-                };
-                if (0x8000..=0xBFFF).contains(&address) && self.pcm_mode_register & 1 == 1 {
-                    if byte == 0 {
-                        self.pcm_irq_pending = true;
-                    } else {
-                        self.pcm_irq_pending = false;
-                        self.raw_pcm = byte;
-                    }
                 }
-                byte
-            }
-            _ => {
-                println!("Get prg byte : Unknown address ${:04X}", address);
-                0
             }
+            _ => 0,
         }
     }
 
     fn store_prg_byte(&mut self, address: u16, byte: u8) {
         match address {
-            PCM_MODE_REGISTER => {
-                self.pcm_mode_register = byte;
-            }
-            PCM_RAW_REGISTER => {
-                let write_mode = self.pcm_mode_register & 1 == 0;
-                if write_mode {
-                    if byte == 0 {
-                        self.pcm_irq_pending = true;
-                    } else {
-                        self.pcm_irq_pending = false;
-                        self.raw_pcm = byte;
-                    }
-                }
-            }
             PRG_MODE_SELECTION_REGISTER => {
                 self.prg_selection_mode = byte & 0b11;
             }
@@ -580,40 +404,8 @@ impl Mapper for Mapper5 {
                     self.expansion_ram[index] = byte;
                 }
             }
-            AUDIO_STATUS_REGISTER => {
-                self.audio_status_register.data = byte;
-                if !self.audio_status_register.is_flag_enabled(Pulse1Enabled) {
-                    self.pulse_1.reset_length_counter();
-                }
-                if !self.audio_status_register.is_flag_enabled(Pulse2Enabled) {
-                    self.pulse_2.reset_length_counter();
-                }
-            }
-
-            PULSE_REGISTER_1..=PULSE_REGISTER_8 => {
-                let index = (address - PULSE_REGISTER_1) as usize;
-                let (pulse, status_flag) = if index < 4 {
-                    (&mut self.pulse_1, Pulse1Enabled)
-                } else {
-                    (&mut self.pulse_2, Pulse2Enabled)
-                };
-                match index % 4 {
-                    0 => pulse.data[0] = byte,
-                    1 => pulse.data[1] = byte,
-                    2 => {
-                        pulse.data[2] = byte;
-                        pulse.update_period();
-                    }
-                    3 => {
-                        pulse.data[3] = byte;
-                        pulse.update_period();
-                        pulse.reset_phase();
-                        if self.audio_status_register.is_flag_enabled(status_flag) {
-                            pulse.reload_length_counter();
-                        }
-                    }
-                    _ => unreachable!(),
-                }
+            0x5000..=0x5015 => {
+                self.audio.write(address, byte);
             }
             0x5016..=0x5BFF => {}
             address if PRG_RANGE.contains(&address) => {
@@ -669,13 +461,7 @@ impl Mapper for Mapper5 {
         self.vertical_split_tile_index = 0;
         self.multiplier_a = 0xFF;
         self.multiplier_b = 0xFF;
-        self.cpu_cycle = 8;
-        self.audio_status_register.data = 0;
-        self.pcm_mode_register = 0;
-        self.raw_pcm = 0;
-        self.pcm_irq_pending = false;
-        self.pulse_1.power_cycle();
-        self.pulse_2.power_cycle();
+        self.audio.power_cycle();
         self.mapper_internal.power_cycle();
     }
 
@@ -693,11 +479,13 @@ impl Mapper for Mapper5 {
     }
 
     fn is_irq_pending(&self) -> bool {
-        (self.scanline_irq_enabled && self.scanline_irq_pending)
-            || (self.pcm_mode_register & 0b1000_0000 != 0 && self.pcm_irq_pending)
+        (self.scanline_irq_enabled && self.scanline_irq_pending) || self.audio.is_irq_pending()
     }
 
     fn get_nametable_byte(&self, source: NametableSource, offset: u16) -> Option<u8> {
+        if offset & 0x3FF >= 0x3C0 {
+            return None;
+        }
         if self.is_in_split_region() {
             let effective_y = (self.split_mode_scroll as u16 + self.scanline_counter as u16) % 240;
             let coarse_y = effective_y / 8;
@@ -738,7 +526,7 @@ impl Mapper for Mapper5 {
                 true
             }
             NametableSource::Fill => true,
-            NametableSource::Vram0 | NametableSource::Vram1 => false,
+            _ => false,
         }
     }
 
@@ -818,22 +606,11 @@ impl Mapper for Mapper5 {
     }
 
     fn clock_audio(&mut self) -> Option<f32> {
-        self.pulse_1.clock_timer();
-        self.pulse_2.clock_timer();
-        if self.is_quarter_frame_reached() {
-            self.pulse_1.clock_length_counter();
-            self.pulse_2.clock_length_counter();
-            self.pulse_1.clock_envelope();
-            self.pulse_2.clock_envelope();
-        }
-        let n = self.pulse_1.get_sample() + self.pulse_2.get_sample();
-        let pulse_out = if n != 0 {
-            95.52 / ((8128.0 / (n as f32)) + 100.0)
-        } else {
-            0.0
-        };
-        let pcm_out = self.raw_pcm as f32 / 256.0;
-        self.cpu_cycle = (self.cpu_cycle + 1) % (FRAME_COUNTER_HALF_FRAME_0_MOD_0_CPU_CYCLES + 1);
-        Some(pulse_out + pcm_out)
+        Some(self.audio.clock())
+    }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mapper_internal
+            .load_trainer(trainer, TRAINER_PRG_RAM_OFFSET);
     }
 }
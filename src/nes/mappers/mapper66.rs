@@ -4,19 +4,26 @@ use crate::nes::mappers::mapper_internal::BankSize::*;
 use crate::nes::mappers::mapper_internal::MapperInternal;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper66 {
     mapper_internal: MapperInternal,
+    bus_conflicts: bool,
     mirroring: Mirroring,
     prg_bank: usize,
     chr_bank: usize,
 }
 
 impl Mapper66 {
-    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+    pub fn new(
+        prg_rom: Vec<u8>,
+        chr_rom: Vec<u8>,
+        mirroring: Mirroring,
+        bus_conflicts: bool,
+    ) -> Self {
         let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
         Self {
             mapper_internal,
+            bus_conflicts,
             mirroring,
             prg_bank: 0,
             chr_bank: 0,
@@ -33,7 +40,7 @@ impl Mapper for Mapper66 {
         self.mirroring
     }
 
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         self.mapper_internal
             .get_prg_rom_byte(address, self.prg_bank, _32KB)
     }
@@ -52,4 +59,8 @@ impl Mapper for Mapper66 {
         self.chr_bank = (byte & 3) as usize;
         self.prg_bank = ((byte & 0b00110000) >> 4) as usize;
     }
+
+    fn has_bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
 }
@@ -4,17 +4,19 @@ use super::mapper_internal::MapperInternal;
 use crate::nes::common::Mirroring;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper7 {
     mapper_internal: MapperInternal,
+    bus_conflicts: bool,
     register: usize,
 }
 
 impl Mapper7 {
-    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>) -> Self {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, bus_conflicts: bool) -> Self {
         let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
         Self {
             mapper_internal,
+            bus_conflicts,
             register: 0,
         }
     }
@@ -33,7 +35,7 @@ impl Mapper for Mapper7 {
         }
     }
 
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         if address >= 0x8000 {
             let bank = self.register & 7;
             self.mapper_internal.get_prg_rom_byte(address, bank, _32KB)
@@ -56,4 +58,8 @@ impl Mapper for Mapper7 {
             self.register = byte as usize;
         }
     }
+
+    fn has_bus_conflicts(&self) -> bool {
+        self.bus_conflicts
+    }
 }
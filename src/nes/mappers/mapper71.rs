@@ -4,21 +4,28 @@ use super::mapper_internal::MapperInternal;
 use crate::nes::common::Mirroring;
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Mapper71 {
     mapper_internal: MapperInternal,
     mirroring: Mirroring,
+    is_fire_hawk: bool,
     switchable_prg_rom_bank: usize,
     last_prg_rom_bank: usize,
 }
 
 impl Mapper71 {
-    pub fn new(prg_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+    pub fn new(prg_rom: Vec<u8>, mirroring: Mirroring, submapper: u8) -> Self {
+        let is_fire_hawk = submapper == 1;
         let mapper_internal = MapperInternal::new(prg_rom, vec![]);
         let last_prg_rom_bank = mapper_internal.get_prg_rom_bank_count(_16KB) - 1;
         Self {
             mapper_internal,
-            mirroring,
+            mirroring: if is_fire_hawk {
+                Mirroring::SINGLE_SCREEN_0
+            } else {
+                mirroring
+            },
+            is_fire_hawk,
             switchable_prg_rom_bank: 0,
             last_prg_rom_bank,
         }
@@ -33,7 +40,7 @@ impl Mapper for Mapper71 {
     fn get_mirroring(&self) -> Mirroring {
         self.mirroring
     }
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
         assert!(address >= 0x8000);
         let bank = if address < 0xC000 {
             self.switchable_prg_rom_bank
@@ -54,7 +61,8 @@ impl Mapper for Mapper71 {
 
     fn store_prg_byte(&mut self, address: u16, byte: u8) {
         match address {
-            0x9000..=0x9FFF => {
+            0x8000..=0x8FFF if !self.is_fire_hawk => (),
+            0x8000..=0x9FFF => {
                 self.mirroring = if byte & 0x10 != 0 {
                     Mirroring::SINGLE_SCREEN_1
                 } else {
@@ -0,0 +1,66 @@
+use super::Mapper;
+use super::PRG_RAM_RANGE;
+use crate::nes::common::Mirroring;
+use crate::nes::mappers::mapper_internal::BankSize::*;
+use crate::nes::mappers::mapper_internal::MapperInternal;
+use serde::{Deserialize, Serialize};
+
+const BANK_REGISTER_MASK: u16 = 0xE100;
+const BANK_REGISTER_ADDRESS: u16 = 0x4100;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper79 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    prg_bank: usize,
+    chr_bank: usize,
+}
+
+impl Mapper79 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            mirroring,
+            prg_bank: 0,
+            chr_bank: 0,
+        }
+    }
+}
+
+impl Mapper for Mapper79 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal
+            .get_chr_byte(address, self.chr_bank, _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if address < PRG_RAM_RANGE.end {
+            return 0;
+        }
+        self.mapper_internal
+            .get_prg_rom_byte(address, self.prg_bank, _32KB)
+    }
+
+    fn power_cycle(&mut self) {
+        self.prg_bank = 0;
+        self.chr_bank = 0;
+        self.mapper_internal.power_cycle();
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal
+            .store_chr_byte(address, self.chr_bank, _8KB, byte)
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if address & BANK_REGISTER_MASK == BANK_REGISTER_ADDRESS {
+            self.prg_bank = ((byte & 0b0000_1000) >> 3) as usize;
+            self.chr_bank = (byte & 0b0000_0111) as usize;
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use super::Mapper;
+use super::four_screen_ram::FourScreenRam;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use crate::nes::common::{Mirroring, NametableSource};
+use serde::{Deserialize, Serialize};
+
+const VS_BANK_SELECT: u8 = 0b0000_0100;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Mapper99 {
+    mapper_internal: MapperInternal,
+    mirroring: Mirroring,
+    four_screen_ram: Option<FourScreenRam>,
+    bank: usize,
+}
+
+impl Mapper99 {
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, mirroring: Mirroring) -> Self {
+        let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
+        Self {
+            mapper_internal,
+            mirroring,
+            four_screen_ram: (mirroring == Mirroring::FOUR_SCREEN).then(FourScreenRam::new),
+            bank: 0,
+        }
+    }
+}
+
+impl Mapper for Mapper99 {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal.get_chr_byte(address, self.bank, _8KB)
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        self.mirroring
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        match address {
+            0x6000..=0x7FFF => self
+                .mapper_internal
+                .get_prg_ram_byte(address & 0x7FF, 0, _2KB),
+            0x8000..=0x9FFF if self.mapper_internal.get_prg_rom_bank_count(_8KB) > 4 => {
+                let bank = self.bank * 4;
+                self.mapper_internal.get_prg_rom_byte(address, bank, _8KB)
+            }
+            0x8000..=0xFFFF => self.mapper_internal.get_prg_rom_byte(address, 0, _32KB),
+            _ => 0,
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.bank = 0;
+        self.mapper_internal.power_cycle();
+        if let Some(ram) = &mut self.four_screen_ram {
+            ram.power_cycle();
+        }
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal
+            .store_chr_byte(address, self.bank, _8KB, byte)
+    }
+
+    fn get_nametable_byte(&self, source: NametableSource, offset: u16) -> Option<u8> {
+        self.four_screen_ram
+            .as_ref()
+            .and_then(|ram| ram.get_byte(source, offset))
+    }
+
+    fn store_nametable_or_bg_palette_index(
+        &mut self,
+        source: NametableSource,
+        offset: u16,
+        byte: u8,
+    ) -> bool {
+        self.four_screen_ram
+            .as_mut()
+            .is_some_and(|ram| ram.store_byte(source, offset, byte))
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        if (0x6000..=0x7FFF).contains(&address) {
+            self.mapper_internal
+                .store_prg_ram_byte(address & 0x7FF, 0, _2KB, byte);
+        }
+    }
+
+    fn notify_controller_port_write(&mut self, value: u8) {
+        self.bank = if value & VS_BANK_SELECT != 0 { 1 } else { 0 };
+    }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        self.mapper_internal.load_trainer(trainer, 0);
+    }
+}
@@ -0,0 +1,509 @@
+use super::Mapper;
+use super::fds_audio::FdsAudio;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use crate::nes::common::CPU_CYCLES_PER_FRAME;
+use crate::nes::common::Mirroring;
+use crate::nes::fds_file::FDS_DISK_SIDE_SIZE;
+use serde::{Deserialize, Serialize};
+
+const IRQ_RELOAD_LOW_REGISTER: u16 = 0x4020;
+const IRQ_RELOAD_HIGH_REGISTER: u16 = 0x4021;
+const IRQ_CONTROL_REGISTER: u16 = 0x4022;
+const MASTER_IO_ENABLE_REGISTER: u16 = 0x4023;
+const WRITE_DATA_REGISTER: u16 = 0x4024;
+const FDS_CONTROL_REGISTER: u16 = 0x4025;
+const EXTERNAL_CONNECTOR_REGISTER: u16 = 0x4026;
+const DISK_STATUS_REGISTER: u16 = 0x4030;
+const READ_DATA_REGISTER: u16 = 0x4031;
+const DRIVE_STATUS_REGISTER: u16 = 0x4032;
+const EXTERNAL_CONNECTOR_READ_REGISTER: u16 = 0x4033;
+const AUDIO_REGISTERS_START: u16 = 0x4040;
+const AUDIO_REGISTERS_END: u16 = 0x409F;
+const PRG_RAM_START: u16 = 0x6000;
+const BIOS_START: u16 = 0xE000;
+
+const GAP_END_MARK: u8 = 0x80;
+const LEADING_GAP_SIZE: usize = 28300 / 8;
+const BLOCK_GAP_SIZE: usize = 976 / 8;
+const RAW_DISK_SIDE_SIZE: usize = FDS_DISK_SIDE_SIZE + LEADING_GAP_SIZE + 0x1000;
+const DISK_INFO_BLOCK: u8 = 1;
+const FILE_AMOUNT_BLOCK: u8 = 2;
+const FILE_HEADER_BLOCK: u8 = 3;
+const FILE_DATA_BLOCK: u8 = 4;
+const DISK_INFO_BLOCK_SIZE: usize = 56;
+const FILE_AMOUNT_BLOCK_SIZE: usize = 2;
+const FILE_HEADER_BLOCK_SIZE: usize = 16;
+const FILE_HEADER_SIZE_OFFSET: usize = 13;
+
+const HEAD_REWIND_CPU_CYCLES: u32 = 50000;
+const BYTE_TRANSFER_CPU_CYCLES: u32 = 150;
+const DISK_SWAP_CPU_CYCLES: u32 = 30 * CPU_CYCLES_PER_FRAME as u32;
+
+enum FdsControlFlag {
+    MotorOn = 0b00000001,
+    TransferReset = 0b00000010,
+    ReadMode = 0b00000100,
+    MirroringHorizontal = 0b00001000,
+    CrcControl = 0b00010000,
+    DiskReady = 0b01000000,
+    DiskIrqEnabled = 0b10000000,
+}
+
+fn update_crc(crc: u16, byte: u8) -> u16 {
+    let mut crc = crc;
+    for bit in 0..8 {
+        let carry = crc & 1 != 0;
+        crc >>= 1;
+        if carry {
+            crc ^= 0x8408;
+        }
+        if byte & (1 << bit) != 0 {
+            crc ^= 0x8000;
+        }
+    }
+    crc
+}
+
+fn get_block_size(block: &[u8], file_size: usize) -> Option<usize> {
+    let block_size = match block.first() {
+        Some(&DISK_INFO_BLOCK) => DISK_INFO_BLOCK_SIZE,
+        Some(&FILE_AMOUNT_BLOCK) => FILE_AMOUNT_BLOCK_SIZE,
+        Some(&FILE_HEADER_BLOCK) => FILE_HEADER_BLOCK_SIZE,
+        Some(&FILE_DATA_BLOCK) => 1 + file_size,
+        _ => return None,
+    };
+    if block.len() < block_size {
+        None
+    } else {
+        Some(block_size)
+    }
+}
+
+fn get_file_size(file_header_block: &[u8]) -> usize {
+    file_header_block[FILE_HEADER_SIZE_OFFSET] as usize
+        + ((file_header_block[FILE_HEADER_SIZE_OFFSET + 1] as usize) << 8)
+}
+
+fn convert_to_raw_disk_side(disk_side: &[u8]) -> Vec<u8> {
+    let mut raw_side = vec![0; LEADING_GAP_SIZE];
+    let mut index = 0;
+    let mut file_size = 0;
+    while let Some(block_size) = get_block_size(&disk_side[index..], file_size) {
+        let block = &disk_side[index..index + block_size];
+        if block[0] == FILE_HEADER_BLOCK {
+            file_size = get_file_size(block);
+        }
+        let mut crc = update_crc(0, GAP_END_MARK);
+        crc = block.iter().fold(crc, |crc, byte| update_crc(crc, *byte));
+        crc = update_crc(update_crc(crc, 0), 0);
+
+        raw_side.push(GAP_END_MARK);
+        raw_side.extend_from_slice(block);
+        raw_side.extend_from_slice(&crc.to_le_bytes());
+        raw_side.extend(std::iter::repeat_n(0, BLOCK_GAP_SIZE));
+        index += block_size;
+    }
+    raw_side.resize(raw_side.len().max(RAW_DISK_SIDE_SIZE), 0);
+    raw_side
+}
+
+fn convert_from_raw_disk_side(raw_side: &[u8]) -> Vec<u8> {
+    let mut disk_side = Vec::with_capacity(FDS_DISK_SIDE_SIZE);
+    let mut index = 0;
+    let mut file_size = 0;
+    loop {
+        while index < raw_side.len() && raw_side[index] != GAP_END_MARK {
+            index += 1;
+        }
+        index += 1;
+        if index >= raw_side.len() {
+            break;
+        }
+        let Some(block_size) = get_block_size(&raw_side[index..], file_size) else {
+            break;
+        };
+        let block = &raw_side[index..index + block_size];
+        if block[0] == FILE_HEADER_BLOCK {
+            file_size = get_file_size(block);
+        }
+        disk_side.extend_from_slice(block);
+        index += block_size + 2 + BLOCK_GAP_SIZE / 2;
+    }
+    disk_side.resize(FDS_DISK_SIDE_SIZE, 0);
+    disk_side
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+struct DiskDrive {
+    disk_sides: Vec<Vec<u8>>,
+    inserted_disk_side: Option<usize>,
+    pending_disk_side: Option<usize>,
+    disk_swap_delay: u32,
+    position: usize,
+    delay: u32,
+    end_of_head: bool,
+    scanning: bool,
+    gap_ended: bool,
+    previous_crc_control: bool,
+    crc: u16,
+    read_data: u8,
+    write_data: u8,
+    transfer_complete: bool,
+    irq_pending: bool,
+}
+
+impl DiskDrive {
+    fn new(disk_sides: Vec<Vec<u8>>) -> Self {
+        Self {
+            disk_sides,
+            inserted_disk_side: Some(0),
+            pending_disk_side: Some(0),
+            disk_swap_delay: 0,
+            position: 0,
+            delay: 0,
+            end_of_head: true,
+            scanning: false,
+            gap_ended: false,
+            previous_crc_control: false,
+            crc: 0,
+            read_data: 0,
+            write_data: 0,
+            transfer_complete: false,
+            irq_pending: false,
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.position = 0;
+        self.delay = 0;
+        self.end_of_head = true;
+        self.scanning = false;
+        self.gap_ended = false;
+        self.previous_crc_control = false;
+        self.crc = 0;
+        self.read_data = 0;
+        self.write_data = 0;
+        self.transfer_complete = false;
+        self.irq_pending = false;
+    }
+
+    fn insert_disk_side(&mut self, disk_side: Option<usize>) {
+        self.inserted_disk_side = None;
+        self.pending_disk_side = disk_side;
+        self.disk_swap_delay = if disk_side.is_some() {
+            DISK_SWAP_CPU_CYCLES
+        } else {
+            0
+        };
+    }
+
+    fn clock(&mut self, control: u8) -> bool {
+        if self.disk_swap_delay > 0 {
+            self.disk_swap_delay -= 1;
+        } else {
+            self.inserted_disk_side = self.pending_disk_side;
+        }
+
+        let Some(side) = self.inserted_disk_side else {
+            self.end_of_head = true;
+            self.scanning = false;
+            return false;
+        };
+        if control & FdsControlFlag::MotorOn as u8 == 0 {
+            self.end_of_head = true;
+            self.scanning = false;
+            return false;
+        }
+        if control & FdsControlFlag::TransferReset as u8 != 0 && !self.scanning {
+            return false;
+        }
+        if self.end_of_head {
+            self.delay = HEAD_REWIND_CPU_CYCLES;
+            self.end_of_head = false;
+            self.position = 0;
+            self.gap_ended = false;
+            return false;
+        }
+        if self.delay > 0 {
+            self.delay -= 1;
+            return false;
+        }
+
+        self.scanning = true;
+        let disk_ready = control & FdsControlFlag::DiskReady as u8 != 0;
+        let crc_control = control & FdsControlFlag::CrcControl as u8 != 0;
+        let mut irq = control & FdsControlFlag::DiskIrqEnabled as u8 != 0;
+        let disk_side = &mut self.disk_sides[side];
+
+        if control & FdsControlFlag::ReadMode as u8 != 0 {
+            let byte = disk_side[self.position];
+            if !disk_ready {
+                self.gap_ended = false;
+            } else if byte != 0 && !self.gap_ended {
+                self.gap_ended = true;
+                irq = false;
+            }
+            if self.gap_ended {
+                self.transfer_complete = true;
+                self.read_data = byte;
+                self.irq_pending |= irq;
+            }
+        } else {
+            let mut byte = 0;
+            if !crc_control {
+                self.transfer_complete = true;
+                byte = self.write_data;
+                self.irq_pending |= irq;
+            }
+            if !disk_ready {
+                byte = 0;
+                self.crc = 0;
+            }
+            if !crc_control {
+                self.crc = update_crc(self.crc, byte);
+            } else {
+                if !self.previous_crc_control {
+                    self.crc = update_crc(update_crc(self.crc, 0), 0);
+                }
+                byte = self.crc as u8;
+                self.crc >>= 8;
+            }
+            disk_side[self.position] = byte;
+            self.gap_ended = false;
+        }
+        self.previous_crc_control = crc_control;
+
+        self.position += 1;
+        if self.position >= disk_side.len() {
+            self.end_of_head = true;
+            true
+        } else {
+            self.delay = BYTE_TRANSFER_CPU_CYCLES;
+            false
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MapperFds {
+    mapper_internal: MapperInternal,
+    disk_drive: DiskDrive,
+    audio: FdsAudio,
+    has_header: bool,
+    disk_registers_enabled: bool,
+    audio_registers_enabled: bool,
+    control: u8,
+    external_connector: u8,
+    timer_irq_reload: u16,
+    timer_irq_counter: u16,
+    timer_irq_repeat: bool,
+    timer_irq_enabled: bool,
+    timer_irq_pending: bool,
+}
+
+impl MapperFds {
+    pub fn new(bios: Vec<u8>, disk_sides: Vec<Vec<u8>>, has_header: bool) -> Self {
+        let disk_sides = disk_sides
+            .iter()
+            .map(|disk_side| convert_to_raw_disk_side(disk_side))
+            .collect();
+        Self {
+            mapper_internal: MapperInternal::new(bios, vec![]),
+            disk_drive: DiskDrive::new(disk_sides),
+            audio: FdsAudio::new(),
+            has_header,
+            disk_registers_enabled: true,
+            audio_registers_enabled: true,
+            control: 0,
+            external_connector: 0,
+            timer_irq_reload: 0,
+            timer_irq_counter: 0,
+            timer_irq_repeat: false,
+            timer_irq_enabled: false,
+            timer_irq_pending: false,
+        }
+    }
+
+    pub fn get_disk_side_count(&self) -> usize {
+        self.disk_drive.disk_sides.len()
+    }
+
+    pub fn get_inserted_disk_side(&self) -> Option<usize> {
+        self.disk_drive.pending_disk_side
+    }
+
+    pub fn insert_disk_side(&mut self, disk_side: Option<usize>) {
+        self.disk_drive.insert_disk_side(disk_side);
+    }
+
+    pub fn get_disk_image(&self) -> Vec<u8> {
+        let side_count = self.get_disk_side_count();
+        let mut image = Vec::new();
+        if self.has_header {
+            image.extend_from_slice(b"FDS\x1A");
+            image.push(side_count as u8);
+            image.resize(16, 0);
+        }
+        for raw_side in &self.disk_drive.disk_sides {
+            image.extend(convert_from_raw_disk_side(raw_side));
+        }
+        image
+    }
+
+    fn clock_timer_irq(&mut self) {
+        if self.timer_irq_enabled {
+            if self.timer_irq_counter == 0 {
+                self.timer_irq_pending = true;
+                self.timer_irq_counter = self.timer_irq_reload;
+                if !self.timer_irq_repeat {
+                    self.timer_irq_enabled = false;
+                }
+            } else {
+                self.timer_irq_counter -= 1;
+            }
+        }
+    }
+}
+
+impl Mapper for MapperFds {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal.get_chr_byte(address, 0, _8KB)
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal.store_chr_byte(address, 0, _8KB, byte)
+    }
+
+    fn get_prg_byte(&mut self, address: u16) -> u8 {
+        let byte = self.peek_prg_byte(address);
+        match address {
+            DISK_STATUS_REGISTER if self.disk_registers_enabled => {
+                self.timer_irq_pending = false;
+                self.disk_drive.transfer_complete = false;
+                self.disk_drive.irq_pending = false;
+            }
+            READ_DATA_REGISTER if self.disk_registers_enabled => {
+                self.disk_drive.transfer_complete = false;
+                self.disk_drive.irq_pending = false;
+            }
+            _ => (),
+        }
+        byte
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        match address {
+            DISK_STATUS_REGISTER if self.disk_registers_enabled => {
+                self.timer_irq_pending as u8 | (self.disk_drive.transfer_complete as u8) << 1 | 0x40
+            }
+            READ_DATA_REGISTER if self.disk_registers_enabled => self.disk_drive.read_data,
+            DRIVE_STATUS_REGISTER if self.disk_registers_enabled => {
+                let is_disk_missing = self.disk_drive.inserted_disk_side.is_none();
+                is_disk_missing as u8
+                    | ((is_disk_missing || !self.disk_drive.scanning) as u8) << 1
+                    | (is_disk_missing as u8) << 2
+                    | 0x40
+            }
+            EXTERNAL_CONNECTOR_READ_REGISTER if self.disk_registers_enabled => {
+                0x80 | (self.external_connector & 0x7F)
+            }
+            AUDIO_REGISTERS_START..=AUDIO_REGISTERS_END if self.audio_registers_enabled => {
+                self.audio.read(address).unwrap_or(0)
+            }
+            PRG_RAM_START..BIOS_START => {
+                self.mapper_internal
+                    .get_prg_ram_byte(address - PRG_RAM_START, 0, _32KB)
+            }
+            BIOS_START..=0xFFFF => self.mapper_internal.get_prg_rom_byte(address, 0, _8KB),
+            _ => 0,
+        }
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        match address {
+            IRQ_RELOAD_LOW_REGISTER => {
+                self.timer_irq_reload = (self.timer_irq_reload & 0xFF00) | byte as u16;
+            }
+            IRQ_RELOAD_HIGH_REGISTER => {
+                self.timer_irq_reload = (self.timer_irq_reload & 0x00FF) | ((byte as u16) << 8);
+            }
+            IRQ_CONTROL_REGISTER => {
+                self.timer_irq_repeat = byte & 0x01 != 0;
+                self.timer_irq_enabled = byte & 0x02 != 0 && self.disk_registers_enabled;
+                if self.timer_irq_enabled {
+                    self.timer_irq_counter = self.timer_irq_reload;
+                } else {
+                    self.timer_irq_pending = false;
+                }
+            }
+            MASTER_IO_ENABLE_REGISTER => {
+                self.disk_registers_enabled = byte & 0x01 != 0;
+                self.audio_registers_enabled = byte & 0x02 != 0;
+                if !self.disk_registers_enabled {
+                    self.timer_irq_enabled = false;
+                    self.timer_irq_pending = false;
+                    self.disk_drive.irq_pending = false;
+                }
+            }
+            WRITE_DATA_REGISTER if self.disk_registers_enabled => {
+                self.disk_drive.write_data = byte;
+                self.disk_drive.transfer_complete = false;
+                self.disk_drive.irq_pending = false;
+            }
+            FDS_CONTROL_REGISTER if self.disk_registers_enabled => {
+                self.control = byte;
+                self.disk_drive.irq_pending = false;
+            }
+            EXTERNAL_CONNECTOR_REGISTER if self.disk_registers_enabled => {
+                self.external_connector = byte;
+            }
+            AUDIO_REGISTERS_START..=AUDIO_REGISTERS_END if self.audio_registers_enabled => {
+                self.audio.write(address, byte);
+            }
+            PRG_RAM_START..BIOS_START => {
+                self.mapper_internal
+                    .store_prg_ram_byte(address - PRG_RAM_START, 0, _32KB, byte);
+            }
+            _ => (),
+        }
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        if self.control & FdsControlFlag::MirroringHorizontal as u8 != 0 {
+            Mirroring::HORIZONTAL
+        } else {
+            Mirroring::VERTICAL
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.mapper_internal.power_cycle();
+        self.disk_drive.power_cycle();
+        self.audio.power_cycle();
+        self.disk_registers_enabled = true;
+        self.audio_registers_enabled = true;
+        self.control = 0;
+        self.external_connector = 0;
+        self.timer_irq_reload = 0;
+        self.timer_irq_counter = 0;
+        self.timer_irq_repeat = false;
+        self.timer_irq_enabled = false;
+        self.timer_irq_pending = false;
+    }
+
+    fn is_irq_pending(&self) -> bool {
+        self.timer_irq_pending || self.disk_drive.irq_pending
+    }
+
+    fn notify_cpu_cycle(&mut self) {
+        self.clock_timer_irq();
+        if self.disk_drive.clock(self.control) {
+            self.control &= !(FdsControlFlag::MotorOn as u8);
+        }
+    }
+
+    fn clock_audio(&mut self) -> Option<f32> {
+        Some(self.audio.clock())
+    }
+}
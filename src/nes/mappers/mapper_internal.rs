@@ -4,6 +4,7 @@ const PRG_RAM_DATA_SIZE: usize = 0x20000;
 const PRG_ROM_DATA_SIZE: usize = 0x80000;
 const CHR_ROM_DATA_SIZE: usize = 0x40000;
 const CHR_RAM_DATA_SIZE: usize = 0x2000;
+pub(super) const TRAINER_PRG_RAM_OFFSET: usize = 0x1000;
 
 #[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub(super) enum BankSize {
@@ -30,7 +31,7 @@ impl Default for BankSelect {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(super) struct MapperInternal {
     prg_ram: Vec<u8>,
     prg_rom: Vec<u8>,
@@ -38,14 +39,22 @@ pub(super) struct MapperInternal {
     chr_rom: Vec<u8>,
     chr_rom_size: usize,
     chr_ram: Vec<u8>,
+    trainer: Vec<u8>,
+    trainer_prg_ram_offset: usize,
 }
 
 impl MapperInternal {
-    pub fn new(_prg_rom: Vec<u8>, _chr_rom: Vec<u8>) -> Self {
-        assert!(_prg_rom.len() <= PRG_ROM_DATA_SIZE);
-        assert!(_chr_rom.len() <= CHR_ROM_DATA_SIZE);
-        let mut prg_rom = vec![0u8; PRG_ROM_DATA_SIZE];
-        let mut chr_rom = vec![0u8; CHR_ROM_DATA_SIZE];
+    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>) -> Self {
+        Self::new_with_chr_ram_size(prg_rom, chr_rom, CHR_RAM_DATA_SIZE)
+    }
+
+    pub fn new_with_chr_ram_size(
+        _prg_rom: Vec<u8>,
+        _chr_rom: Vec<u8>,
+        chr_ram_size: usize,
+    ) -> Self {
+        let mut prg_rom = vec![0u8; _prg_rom.len().max(PRG_ROM_DATA_SIZE)];
+        let mut chr_rom = vec![0u8; _chr_rom.len().max(CHR_ROM_DATA_SIZE)];
 
         prg_rom[.._prg_rom.len()].copy_from_slice(&_prg_rom);
         chr_rom[.._chr_rom.len()].copy_from_slice(&_chr_rom);
@@ -56,7 +65,9 @@ impl MapperInternal {
             prg_rom_size: _prg_rom.len(),
             chr_rom,
             chr_rom_size: _chr_rom.len(),
-            chr_ram: vec![0u8; CHR_RAM_DATA_SIZE],
+            chr_ram: vec![0u8; chr_ram_size],
+            trainer: Vec::new(),
+            trainer_prg_ram_offset: TRAINER_PRG_RAM_OFFSET,
         }
     }
 
@@ -65,15 +76,26 @@ impl MapperInternal {
     }
 
     pub fn get_prg_rom_byte(&self, address: u16, bank: usize, prg_bank_size: BankSize) -> u8 {
+        self.prg_rom[self.get_prg_rom_index(address, bank, prg_bank_size)]
+    }
+
+    pub fn get_prg_rom(&self) -> &[u8] {
+        &self.prg_rom[..self.prg_rom_size]
+    }
+
+    pub fn get_prg_rom_mut(&mut self) -> &mut [u8] {
+        &mut self.prg_rom[..self.prg_rom_size]
+    }
+
+    pub fn get_prg_rom_index(&self, address: u16, bank: usize, prg_bank_size: BankSize) -> usize {
         let bank_count = self.prg_rom_size / prg_bank_size as usize;
         let bank = if bank_count > 0 { bank % bank_count } else { 0 };
         let index = Self::get_address_index(address, bank, prg_bank_size);
-        let index = if self.prg_rom_size > 0 {
+        if self.prg_rom_size > 0 {
             index % self.prg_rom_size
         } else {
             0
-        };
-        self.prg_rom[index]
+        }
     }
 
     pub fn get_prg_ram_byte(&self, address: u16, bank: usize, bank_size: BankSize) -> u8 {
@@ -86,8 +108,7 @@ impl MapperInternal {
 
     pub fn get_chr_byte(&self, address: u16, bank: usize, bank_size: BankSize) -> u8 {
         if self.chr_rom_size == 0 {
-            let index = Self::get_address_index(address, bank, bank_size);
-            self.chr_ram[index]
+            self.get_chr_ram_byte(address, bank, bank_size)
         } else {
             let bank_count = self.chr_rom_size / bank_size as usize;
             let bank = if bank_count > 0 { bank % bank_count } else { 0 };
@@ -96,16 +117,35 @@ impl MapperInternal {
         }
     }
 
+    pub fn get_chr_ram_byte(&self, address: u16, bank: usize, bank_size: BankSize) -> u8 {
+        let bank_count = self.chr_ram.len() / bank_size as usize;
+        let index = Self::get_address_index(address, bank % bank_count, bank_size);
+        self.chr_ram[index]
+    }
+
     pub fn store_chr_byte(&mut self, address: u16, bank: usize, bank_size: BankSize, byte: u8) {
-        let index = Self::get_address_index(address, bank, bank_size);
+        let bank_count = self.chr_ram.len() / bank_size as usize;
+        let index = Self::get_address_index(address, bank % bank_count, bank_size);
         self.chr_ram[index] = byte;
     }
 
+    pub fn load_trainer(&mut self, trainer: &[u8], prg_ram_offset: usize) {
+        self.trainer = trainer.to_vec();
+        self.trainer_prg_ram_offset = prg_ram_offset;
+        self.copy_trainer_to_prg_ram();
+    }
+
+    fn copy_trainer_to_prg_ram(&mut self) {
+        let offset = self.trainer_prg_ram_offset;
+        self.prg_ram[offset..offset + self.trainer.len()].copy_from_slice(&self.trainer);
+    }
+
     pub fn get_prg_rom_bank_count(&self, prg_bank_size: BankSize) -> usize {
         self.prg_rom_size / prg_bank_size as usize
     }
 
     pub fn power_cycle(&mut self) {
         self.chr_ram.iter_mut().for_each(|m| *m = 0);
+        self.copy_trainer_to_prg_ram();
     }
 }
@@ -0,0 +1,305 @@
+use super::Mapper;
+use super::fds_audio::FdsAudio;
+use super::mapper_internal::BankSize::*;
+use super::mapper_internal::MapperInternal;
+use super::mmc5_audio::Mmc5Audio;
+use crate::nes::common::Mirroring;
+use crate::nes::nsf_file::NSF_BANK_SIZE;
+use crate::nes::nsf_file::NSF_BANK_SLOT_COUNT;
+use crate::nes::nsf_file::NSF_CHIP_FDS;
+use crate::nes::nsf_file::NSF_CHIP_MMC5;
+use crate::nes::nsf_file::NsfFile;
+use crate::nes::nsf_file::NsfInfo;
+use serde::{Deserialize, Serialize};
+
+const DRIVER_START: u16 = 0x4100;
+const DRIVER_END: u16 = DRIVER_START + DRIVER_NMI_OFFSET;
+const DRIVER_IDLE_LOOP_OFFSET: u16 = 0x20;
+const DRIVER_IRQ_OFFSET: u16 = 0x23;
+const DRIVER_NMI_OFFSET: u16 = 0x2B;
+const PLAY_TIMER_ENABLE_REGISTER: u16 = 0x4180;
+const PLAY_TIMER_ACK_REGISTER: u16 = 0x4181;
+const TRACK_REGISTER: u16 = 0x4182;
+const FDS_AUDIO_REGISTERS_START: u16 = 0x4040;
+const FDS_AUDIO_REGISTERS_END: u16 = 0x409F;
+const MMC5_AUDIO_REGISTERS_START: u16 = 0x5000;
+const MMC5_AUDIO_REGISTERS_END: u16 = 0x5015;
+const MMC5_MULTIPLIER_A_REGISTER: u16 = 0x5205;
+const MMC5_MULTIPLIER_B_REGISTER: u16 = 0x5206;
+const MMC5_EXPANSION_RAM_START: u16 = 0x5C00;
+const MMC5_EXPANSION_RAM_END: u16 = 0x5FF5;
+const MMC5_EXPANSION_RAM_SIZE: usize =
+    (MMC5_EXPANSION_RAM_END - MMC5_EXPANSION_RAM_START + 1) as usize;
+const BANK_REGISTER_START: u16 = 0x5FF6;
+const BANK_REGISTER_END: u16 = 0x5FFF;
+const PRG_RAM_START: u16 = 0x6000;
+const PRG_ROM_START: u16 = 0x8000;
+const VECTORS_START: u16 = 0xFFFA;
+
+#[rustfmt::skip]
+fn build_driver(init_address: u16, play_address: u16, is_pal_only: bool) -> Vec<u8> {
+    let [init_lo, init_hi] = init_address.to_le_bytes();
+    let [play_lo, play_hi] = play_address.to_le_bytes();
+    let [timer_lo, timer_hi] = PLAY_TIMER_ENABLE_REGISTER.to_le_bytes();
+    let [ack_lo, ack_hi] = PLAY_TIMER_ACK_REGISTER.to_le_bytes();
+    let [track_lo, track_hi] = TRACK_REGISTER.to_le_bytes();
+    let [idle_lo, idle_hi] = (DRIVER_START + DRIVER_IDLE_LOOP_OFFSET).to_le_bytes();
+    let region = if is_pal_only { 0x01 } else { 0x00 };
+    vec![
+        0x78, // SEI
+        0xD8, // CLD
+        0xA2, 0xFF, // LDX #$FF
+        0x9A, // TXS
+        0xA9, 0x00, // LDA #$00
+        0x8D, 0x15, 0x40, // STA $4015
+        0xA9, 0x0F, // LDA #$0F
+        0x8D, 0x15, 0x40, // STA $4015
+        0xA9, 0x40, // LDA #$40
+        0x8D, 0x17, 0x40, // STA $4017
+        0xAD, track_lo, track_hi, // LDA TRACK_REGISTER
+        0xA2, region, // LDX #region
+        0x20, init_lo, init_hi, // JSR INIT
+        0x8D, timer_lo, timer_hi, // STA PLAY_TIMER_ENABLE_REGISTER
+        0x58, // CLI
+        0x4C, idle_lo, idle_hi, // JMP idle loop
+        0xAD, ack_lo, ack_hi, // LDA PLAY_TIMER_ACK_REGISTER
+        0xF0, 0x03, // BEQ RTI
+        0x20, play_lo, play_hi, // JSR PLAY
+        0x40, // RTI
+    ]
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MapperNsf {
+    mapper_internal: MapperInternal,
+    info: NsfInfo,
+    driver: Vec<u8>,
+    expansion_chips: u8,
+    initial_banks: [u8; NSF_BANK_SLOT_COUNT],
+    banks: [u8; NSF_BANK_SLOT_COUNT],
+    play_period: u32,
+    play_timer_counter: u32,
+    play_timer_enabled: bool,
+    play_timer_pending: bool,
+    fds_audio: FdsAudio,
+    mmc5_audio: Mmc5Audio,
+    mmc5_expansion_ram: Vec<u8>,
+    mmc5_multiplier_a: u8,
+    mmc5_multiplier_b: u8,
+}
+
+impl MapperNsf {
+    pub fn new(nsf_file: NsfFile) -> Self {
+        Self {
+            mapper_internal: MapperInternal::new(nsf_file.data, vec![]),
+            info: nsf_file.info,
+            driver: build_driver(
+                nsf_file.init_address,
+                nsf_file.play_address,
+                nsf_file.is_pal_only,
+            ),
+            expansion_chips: nsf_file.expansion_chips,
+            initial_banks: nsf_file.initial_banks,
+            banks: nsf_file.initial_banks,
+            play_period: nsf_file.play_period,
+            play_timer_counter: nsf_file.play_period,
+            play_timer_enabled: false,
+            play_timer_pending: false,
+            fds_audio: FdsAudio::new(),
+            mmc5_audio: Mmc5Audio::new(),
+            mmc5_expansion_ram: vec![0; MMC5_EXPANSION_RAM_SIZE],
+            mmc5_multiplier_a: 0xFF,
+            mmc5_multiplier_b: 0xFF,
+        }
+    }
+
+    pub fn get_info(&self) -> &NsfInfo {
+        &self.info
+    }
+
+    pub fn select_track(&mut self, track: usize) {
+        self.info.current_track = track;
+    }
+
+    fn is_fds(&self) -> bool {
+        self.expansion_chips & NSF_CHIP_FDS != 0
+    }
+
+    fn is_mmc5(&self) -> bool {
+        self.expansion_chips & NSF_CHIP_MMC5 != 0
+    }
+
+    fn get_slot(address: u16) -> usize {
+        (address - PRG_RAM_START) as usize / NSF_BANK_SIZE
+    }
+
+    fn switch_bank(&mut self, slot: usize, bank: u8) {
+        self.banks[slot] = bank;
+        if self.is_fds() {
+            for offset in 0..NSF_BANK_SIZE as u16 {
+                let byte = self
+                    .mapper_internal
+                    .get_prg_rom_byte(offset, bank as usize, _4KB);
+                self.mapper_internal
+                    .store_prg_ram_byte(offset, slot, _4KB, byte);
+            }
+        }
+    }
+
+    fn get_vector_byte(&self, address: u16) -> u8 {
+        let vectors = [
+            DRIVER_START + DRIVER_NMI_OFFSET,
+            DRIVER_START,
+            DRIVER_START + DRIVER_IRQ_OFFSET,
+        ];
+        let offset = (address - VECTORS_START) as usize;
+        vectors[offset / 2].to_le_bytes()[offset % 2]
+    }
+}
+
+impl Mapper for MapperNsf {
+    fn get_chr_byte(&mut self, address: u16) -> u8 {
+        self.mapper_internal.get_chr_byte(address, 0, _8KB)
+    }
+
+    fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        self.mapper_internal.store_chr_byte(address, 0, _8KB, byte)
+    }
+
+    fn get_prg_byte(&mut self, address: u16) -> u8 {
+        let byte = self.peek_prg_byte(address);
+        match address {
+            PLAY_TIMER_ACK_REGISTER => self.play_timer_pending = false,
+            MMC5_AUDIO_REGISTERS_START..=MMC5_AUDIO_REGISTERS_END if self.is_mmc5() => {
+                self.mmc5_audio.read(address);
+            }
+            PRG_ROM_START..VECTORS_START if !self.is_fds() && self.is_mmc5() => {
+                self.mmc5_audio.notify_prg_read(address, byte);
+            }
+            _ => (),
+        }
+        byte
+    }
+
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        match address {
+            PLAY_TIMER_ACK_REGISTER => self.play_timer_pending as u8,
+            TRACK_REGISTER => self.info.current_track as u8,
+            DRIVER_START..=DRIVER_END => self.driver[(address - DRIVER_START) as usize],
+            FDS_AUDIO_REGISTERS_START..=FDS_AUDIO_REGISTERS_END if self.is_fds() => {
+                self.fds_audio.read(address).unwrap_or(0)
+            }
+            MMC5_AUDIO_REGISTERS_START..=MMC5_AUDIO_REGISTERS_END if self.is_mmc5() => {
+                self.mmc5_audio.peek(address).unwrap_or(0)
+            }
+            MMC5_MULTIPLIER_A_REGISTER if self.is_mmc5() => {
+                (self.mmc5_multiplier_a as u16 * self.mmc5_multiplier_b as u16) as u8
+            }
+            MMC5_MULTIPLIER_B_REGISTER if self.is_mmc5() => {
+                ((self.mmc5_multiplier_a as u16 * self.mmc5_multiplier_b as u16) >> 8) as u8
+            }
+            MMC5_EXPANSION_RAM_START..=MMC5_EXPANSION_RAM_END if self.is_mmc5() => {
+                self.mmc5_expansion_ram[(address - MMC5_EXPANSION_RAM_START) as usize]
+            }
+            VECTORS_START..=0xFFFF => self.get_vector_byte(address),
+            PRG_RAM_START..=0xFFFF if self.is_fds() => {
+                let slot = Self::get_slot(address);
+                self.mapper_internal
+                    .get_prg_ram_byte(address - PRG_RAM_START, slot, _4KB)
+            }
+            PRG_RAM_START..PRG_ROM_START => {
+                self.mapper_internal
+                    .get_prg_ram_byte(address - PRG_RAM_START, 0, _8KB)
+            }
+            PRG_ROM_START..=0xFFFF => {
+                let bank = self.banks[Self::get_slot(address)];
+                self.mapper_internal
+                    .get_prg_rom_byte(address, bank as usize, _4KB)
+            }
+            _ => 0,
+        }
+    }
+
+    fn store_prg_byte(&mut self, address: u16, byte: u8) {
+        match address {
+            PLAY_TIMER_ENABLE_REGISTER => {
+                self.play_timer_enabled = true;
+                self.play_timer_counter = self.play_period;
+            }
+            FDS_AUDIO_REGISTERS_START..=FDS_AUDIO_REGISTERS_END if self.is_fds() => {
+                self.fds_audio.write(address, byte);
+            }
+            MMC5_AUDIO_REGISTERS_START..=MMC5_AUDIO_REGISTERS_END if self.is_mmc5() => {
+                self.mmc5_audio.write(address, byte);
+            }
+            MMC5_MULTIPLIER_A_REGISTER if self.is_mmc5() => self.mmc5_multiplier_a = byte,
+            MMC5_MULTIPLIER_B_REGISTER if self.is_mmc5() => self.mmc5_multiplier_b = byte,
+            MMC5_EXPANSION_RAM_START..=MMC5_EXPANSION_RAM_END if self.is_mmc5() => {
+                self.mmc5_expansion_ram[(address - MMC5_EXPANSION_RAM_START) as usize] = byte;
+            }
+            BANK_REGISTER_START..=BANK_REGISTER_END => {
+                let slot = (address - BANK_REGISTER_START) as usize;
+                if slot >= 2 || self.is_fds() {
+                    self.switch_bank(slot, byte);
+                }
+            }
+            PRG_RAM_START..=0xFFFF if self.is_fds() => {
+                let slot = Self::get_slot(address);
+                self.mapper_internal
+                    .store_prg_ram_byte(address - PRG_RAM_START, slot, _4KB, byte);
+            }
+            PRG_RAM_START..PRG_ROM_START => {
+                self.mapper_internal
+                    .store_prg_ram_byte(address - PRG_RAM_START, 0, _8KB, byte);
+            }
+            _ => (),
+        }
+    }
+
+    fn get_mirroring(&self) -> Mirroring {
+        Mirroring::VERTICAL
+    }
+
+    fn power_cycle(&mut self) {
+        self.mapper_internal.power_cycle();
+        for address in PRG_RAM_START..PRG_ROM_START {
+            self.store_prg_byte(address, 0);
+        }
+        for slot in 0..NSF_BANK_SLOT_COUNT {
+            self.switch_bank(slot, self.initial_banks[slot]);
+        }
+        self.play_timer_counter = self.play_period;
+        self.play_timer_enabled = false;
+        self.play_timer_pending = false;
+        self.fds_audio.power_cycle();
+        self.mmc5_audio.power_cycle();
+        self.mmc5_expansion_ram.fill(0);
+        self.mmc5_multiplier_a = 0xFF;
+        self.mmc5_multiplier_b = 0xFF;
+    }
+
+    fn is_irq_pending(&self) -> bool {
+        self.play_timer_pending || (self.is_mmc5() && self.mmc5_audio.is_irq_pending())
+    }
+
+    fn notify_cpu_cycle(&mut self) {
+        if self.play_timer_enabled {
+            self.play_timer_counter = self.play_timer_counter.saturating_sub(1);
+            if self.play_timer_counter == 0 {
+                self.play_timer_pending = true;
+                self.play_timer_counter = self.play_period;
+            }
+        }
+    }
+
+    fn clock_audio(&mut self) -> Option<f32> {
+        let mut sample = None;
+        if self.is_fds() {
+            sample = Some(self.fds_audio.clock());
+        }
+        if self.is_mmc5() {
+            sample = Some(sample.unwrap_or(0.0) + self.mmc5_audio.clock());
+        }
+        sample
+    }
+}
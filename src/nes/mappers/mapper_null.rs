@@ -1,7 +1,7 @@
 use crate::nes::common::Mirroring;
 
 use serde::{Deserialize, Serialize};
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MapperNull {}
 
 impl MapperNull {
@@ -15,7 +15,7 @@ impl super::Mapper for MapperNull {
         0
     }
 
-    fn get_prg_byte(&mut self, _address: u16) -> u8 {
+    fn peek_prg_byte(&self, _address: u16) -> u8 {
         0
     }
 
@@ -3,22 +3,41 @@ use super::PRG_RAM_RANGE;
 use super::mapper_internal::BankSelect;
 use super::mapper_internal::BankSize::*;
 use super::mapper_internal::MapperInternal;
+use super::mapper_internal::TRAINER_PRG_RAM_OFFSET;
 use crate::nes::common::Mirroring;
+use crate::nes::common::NametableSource;
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize)]
+const MMC6_PRG_RAM_START: u16 = 0x7000;
+const MMC6_PRG_RAM_HALF_SIZE: u16 = 0x200;
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
 pub(super) enum MMC3_6Variant {
     MMC3HkROM,
-    _MMC3TkTlSROM,
-    _MMC3TqSROM,
-    _MMC6,
+    MMC3TkTlSROM,
+    MMC3TqSROM,
+    MMC6,
+}
+
+#[derive(Clone, Serialize, Deserialize, PartialEq)]
+pub(super) enum MMC3IrqRevision {
+    A,
+    B,
+}
+
+enum MMC6PrgRamProtectFlag {
+    LowerHalfWrite = 0b0001_0000,
+    LowerHalfRead = 0b0010_0000,
+    UpperHalfWrite = 0b0100_0000,
+    UpperHalfRead = 0b1000_0000,
 }
 
 trait BankSelectRegister {
     fn get_selected_bank(&self) -> usize;
     fn get_prg_rom_mode(&self) -> usize;
     fn get_chr_inversion_mode(&self) -> usize;
+    fn is_mmc6_prg_ram_enabled(&self) -> bool;
 }
 
 impl BankSelectRegister for u8 {
@@ -33,13 +52,19 @@ impl BankSelectRegister for u8 {
     fn get_chr_inversion_mode(&self) -> usize {
         ((self & 0b1000_0000) >> 7) as usize
     }
+
+    fn is_mmc6_prg_ram_enabled(&self) -> bool {
+        self & 0b0010_0000 != 0
+    }
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub(super) struct MMC3_6 {
     mapper_internal: MapperInternal,
-    _variant: MMC3_6Variant,
+    variant: MMC3_6Variant,
+    irq_revision: MMC3IrqRevision,
     prg_rom_banks: [BankSelect; 4],
     chr_rom_banks: [BankSelect; 8],
+    chr_bank_values: [u8; 8],
     prg_rom_banks_count: usize,
     bank_select: u8,
     bank_data: u8,
@@ -53,17 +78,24 @@ pub(super) struct MMC3_6 {
 }
 
 impl MMC3_6 {
-    pub fn new(prg_rom: Vec<u8>, chr_rom: Vec<u8>, variant: MMC3_6Variant) -> Self {
+    pub fn new(
+        prg_rom: Vec<u8>,
+        chr_rom: Vec<u8>,
+        variant: MMC3_6Variant,
+        irq_revision: MMC3IrqRevision,
+    ) -> Self {
         let mapper_internal = MapperInternal::new(prg_rom, chr_rom);
         let prg_rom_banks_count = mapper_internal.get_prg_rom_bank_count(_8KB);
         let mut mapper = Self {
             mapper_internal,
-            _variant: variant,
+            variant,
+            irq_revision,
             prg_rom_banks: [BankSelect {
                 size: _8KB,
                 bank: prg_rom_banks_count - 1,
             }; 4],
             chr_rom_banks: [Default::default(); 8],
+            chr_bank_values: [0; 8],
             prg_rom_banks_count,
             bank_select: 0,
             bank_data: 0,
@@ -105,6 +137,8 @@ impl MMC3_6 {
         self.chr_rom_banks[6].size = _1KB;
         self.chr_rom_banks[7].bank = 7;
         self.chr_rom_banks[7].size = _1KB;
+
+        self.chr_bank_values = [0, 0, 2, 2, 4, 5, 6, 7];
     }
 
     fn update_selected_bank(&mut self, selected_bank: usize) {
@@ -132,6 +166,8 @@ impl MMC3_6 {
                 self.chr_rom_banks[bank_index_1].size = _1KB;
                 self.chr_rom_banks[bank_index_1].bank = _1kb_bank;
             }
+            self.chr_bank_values[bank_index_1] = self.bank_data;
+            self.chr_bank_values[bank_index_2] = self.bank_data;
         } else {
             let mode = self.bank_select.get_prg_rom_mode();
             let bank = (self.bank_data & 0b00111111) as usize;
@@ -148,17 +184,74 @@ impl MMC3_6 {
             }
         }
     }
+
+    fn is_chr_ram_selected(&self, address: u16) -> bool {
+        self.variant == MMC3_6Variant::MMC3TqSROM
+            && self.chr_bank_values[address as usize / _1KB as usize] & 0b0100_0000 != 0
+    }
+
+    fn is_mmc6_prg_ram_flag_enabled(&self, flag: MMC6PrgRamProtectFlag) -> bool {
+        self.prg_ram_protect & flag as u8 != 0
+    }
+
+    fn get_mmc6_prg_ram_byte(&self, address: u16) -> u8 {
+        use MMC6PrgRamProtectFlag::*;
+        let lower_half_readable = self.is_mmc6_prg_ram_flag_enabled(LowerHalfRead);
+        let upper_half_readable = self.is_mmc6_prg_ram_flag_enabled(UpperHalfRead);
+        let is_upper_half = address & (_1KB as u16 - 1) >= MMC6_PRG_RAM_HALF_SIZE;
+        let is_readable = if is_upper_half {
+            upper_half_readable
+        } else {
+            lower_half_readable
+        };
+        if address < MMC6_PRG_RAM_START
+            || !self.bank_select.is_mmc6_prg_ram_enabled()
+            || !lower_half_readable && !upper_half_readable
+        {
+            // Nothing drives the bus, the last byte on it is the high byte of the address.
+            return (address >> 8) as u8;
+        }
+        if !is_readable {
+            // A disabled half reads 0 while the other half is enabled.
+            return 0;
+        }
+        self.mapper_internal.get_prg_ram_byte(address, 0, _1KB)
+    }
+
+    fn store_mmc6_prg_ram_byte(&mut self, address: u16, byte: u8) {
+        use MMC6PrgRamProtectFlag::*;
+        let is_upper_half = address & (_1KB as u16 - 1) >= MMC6_PRG_RAM_HALF_SIZE;
+        let is_writable = if is_upper_half {
+            self.is_mmc6_prg_ram_flag_enabled(UpperHalfWrite)
+        } else {
+            self.is_mmc6_prg_ram_flag_enabled(LowerHalfWrite)
+        };
+        if address >= MMC6_PRG_RAM_START
+            && self.bank_select.is_mmc6_prg_ram_enabled()
+            && is_writable
+        {
+            self.mapper_internal
+                .store_prg_ram_byte(address, 0, _1KB, byte);
+        }
+    }
 }
 
 impl Mapper for MMC3_6 {
     fn get_chr_byte(&mut self, address: u16) -> u8 {
         let bank_select = self.chr_rom_banks[address as usize / _1KB as usize];
-        self.mapper_internal
-            .get_chr_byte(address, bank_select.bank, bank_select.size)
+        if self.is_chr_ram_selected(address) {
+            self.mapper_internal
+                .get_chr_ram_byte(address, bank_select.bank, bank_select.size)
+        } else {
+            self.mapper_internal
+                .get_chr_byte(address, bank_select.bank, bank_select.size)
+        }
     }
 
-    fn get_prg_byte(&mut self, address: u16) -> u8 {
-        if PRG_RAM_RANGE.contains(&address) {
+    fn peek_prg_byte(&self, address: u16) -> u8 {
+        if PRG_RAM_RANGE.contains(&address) && self.variant == MMC3_6Variant::MMC6 {
+            self.get_mmc6_prg_ram_byte(address)
+        } else if PRG_RAM_RANGE.contains(&address) {
             self.mapper_internal.get_prg_ram_byte(address, 0, _8KB)
         } else if address >= PRG_RAM_RANGE.end {
             let bank_select =
@@ -171,13 +264,18 @@ impl Mapper for MMC3_6 {
     }
 
     fn store_chr_byte(&mut self, address: u16, byte: u8) {
+        if self.variant == MMC3_6Variant::MMC3TqSROM && !self.is_chr_ram_selected(address) {
+            return;
+        }
         let bank_select = self.chr_rom_banks[address as usize / _1KB as usize];
         self.mapper_internal
             .store_chr_byte(address, bank_select.bank, bank_select.size, byte);
     }
 
     fn store_prg_byte(&mut self, address: u16, byte: u8) {
-        if PRG_RAM_RANGE.contains(&address) {
+        if PRG_RAM_RANGE.contains(&address) && self.variant == MMC3_6Variant::MMC6 {
+            self.store_mmc6_prg_ram_byte(address, byte);
+        } else if PRG_RAM_RANGE.contains(&address) {
             self.mapper_internal
                 .store_prg_ram_byte(address, 0, _8KB, byte)
         } else {
@@ -194,7 +292,9 @@ impl Mapper for MMC3_6 {
                 0xA000..=0xBFFF => {
                     if is_even {
                         self.mirroring = byte;
-                    } else {
+                    } else if self.variant != MMC3_6Variant::MMC6
+                        || self.bank_select.is_mmc6_prg_ram_enabled()
+                    {
                         self.prg_ram_protect = byte;
                     }
                 }
@@ -218,7 +318,15 @@ impl Mapper for MMC3_6 {
     }
 
     fn get_mirroring(&self) -> Mirroring {
-        if self.mirroring & 1 == 1 {
+        if self.variant == MMC3_6Variant::MMC3TkTlSROM {
+            let mut tables = [NametableSource::Vram0; 4];
+            for (nametable, source) in tables.iter_mut().enumerate() {
+                if self.chr_bank_values[nametable] & 0b1000_0000 != 0 {
+                    *source = NametableSource::Vram1;
+                }
+            }
+            Mirroring { tables }
+        } else if self.mirroring & 1 == 1 {
             Mirroring::HORIZONTAL
         } else {
             Mirroring::VERTICAL
@@ -226,6 +334,8 @@ impl Mapper for MMC3_6 {
     }
 
     fn ppu_a12_rising_edge_triggered(&mut self) {
+        let previous_scanline_counter = self.scanline_counter;
+        let is_reload_requested = self.reload_scanline_counter_at_next_edge;
         if self.reload_scanline_counter_at_next_edge || self.scanline_counter == 0 {
             self.scanline_counter = self.scanline_counter_reload_value;
         } else {
@@ -233,7 +343,11 @@ impl Mapper for MMC3_6 {
         }
         self.reload_scanline_counter_at_next_edge = false;
 
-        if self.scanline_counter == 0 && self.irq_enabled {
+        let is_irq_edge = match self.irq_revision {
+            MMC3IrqRevision::A => previous_scanline_counter > 0 || is_reload_requested,
+            MMC3IrqRevision::B => true,
+        };
+        if self.scanline_counter == 0 && self.irq_enabled && is_irq_edge {
             self.irq_triggered = true;
         }
     }
@@ -255,4 +369,13 @@ impl Mapper for MMC3_6 {
         self.irq_triggered = false;
         self.scanline_counter = 0;
     }
+
+    fn load_trainer(&mut self, trainer: &[u8]) {
+        let prg_ram_offset = if self.variant == MMC3_6Variant::MMC6 {
+            0
+        } else {
+            TRAINER_PRG_RAM_OFFSET
+        };
+        self.mapper_internal.load_trainer(trainer, prg_ram_offset);
+    }
 }
@@ -0,0 +1,296 @@
+use crate::nes::apu::DUTY_CYCLE_SEQUENCES;
+use crate::nes::apu::Envelope;
+use crate::nes::apu::FRAME_COUNTER_HALF_FRAME_0_MOD_0_CPU_CYCLES;
+use crate::nes::apu::FRAME_COUNTER_HALF_FRAME_1_CPU_CYCLES;
+use crate::nes::apu::FRAME_COUNTER_QUARTER_FRAME_1_CPU_CYCLES;
+use crate::nes::apu::FRAME_COUNTER_QUARTER_FRAME_3_CPU_CYCLES;
+use crate::nes::apu::LengthCounterChannel;
+use crate::nes::apu::StatusRegister;
+use crate::nes::apu::StatusRegisterFlag::{Pulse1Enabled, Pulse2Enabled};
+
+use serde::{Deserialize, Serialize};
+
+const PULSE_REGISTER_1: u16 = 0x5000;
+const PULSE_REGISTER_8: u16 = 0x5007;
+const AUDIO_STATUS_REGISTER: u16 = 0x5015;
+const PCM_MODE_REGISTER: u16 = 0x5010;
+const PCM_RAW_REGISTER: u16 = 0x5011;
+
+#[derive(Clone, Serialize, Deserialize)]
+struct PulseWave {
+    data: [u8; 4],
+    length_counter: u8,
+    sequencer_position: u8,
+    timer_tick: u16,
+    envelope: Envelope,
+    current_period: u16,
+}
+
+impl PulseWave {
+    fn new() -> Self {
+        PulseWave {
+            data: [0; 4],
+            length_counter: 0,
+            timer_tick: 0,
+            sequencer_position: 0,
+            envelope: Envelope::default(),
+            current_period: 0,
+        }
+    }
+
+    fn power_cycle(&mut self) {
+        self.data = [0; 4];
+        self.length_counter = 0;
+        self.timer_tick = 0;
+        self.sequencer_position = 0;
+        self.current_period = 0;
+        self.envelope = Envelope::default();
+    }
+    fn update_period(&mut self) {
+        self.current_period = self.get_raw_timer_period();
+    }
+
+    fn reset_phase(&mut self) {
+        self.sequencer_position = 0;
+        self.envelope.start_flag = true;
+    }
+
+    fn get_duty_cycle(&self) -> u8 {
+        (self.data[0] & 0b11000000) >> 6
+    }
+
+    fn is_length_counter_halt_envelope_loop_flag_set(&self) -> bool {
+        (self.data[0] & 0b00100000) != 0
+    }
+
+    fn is_constant_volume_set(&self) -> bool {
+        (self.data[0] & 0b00010000) != 0
+    }
+
+    fn get_constant_volume_or_envelope_divider_reload_value(&self) -> u8 {
+        self.data[0] & 0x0F
+    }
+
+    fn get_raw_timer_period(&self) -> u16 {
+        let timer_hi = ((self.data[3] & 0x7) as u16) << 8;
+        self.data[2] as u16 + timer_hi
+    }
+
+    fn clock_timer(&mut self) {
+        if self.timer_tick == 0 {
+            if self.sequencer_position > 0 {
+                self.sequencer_position -= 1;
+            } else {
+                self.sequencer_position = 7;
+            }
+            self.timer_tick = self.current_period;
+        } else {
+            self.timer_tick -= 1;
+        }
+    }
+
+    fn get_sample(&self) -> u8 {
+        DUTY_CYCLE_SEQUENCES[self.get_duty_cycle() as usize][self.sequencer_position as usize]
+            * self.get_volume()
+    }
+
+    fn get_volume(&self) -> u8 {
+        if self.length_counter == 0 {
+            0
+        } else if self.is_constant_volume_set() {
+            self.get_constant_volume_or_envelope_divider_reload_value()
+        } else {
+            self.envelope.decay_level_counter
+        }
+    }
+
+    fn clock_envelope(&mut self) {
+        self.envelope.clock(
+            self.get_constant_volume_or_envelope_divider_reload_value(),
+            self.is_length_counter_halt_envelope_loop_flag_set(),
+        )
+    }
+
+    fn clock_length_counter(&mut self) {
+        if self.length_counter > 0 && !self.is_length_counter_halt_envelope_loop_flag_set() {
+            self.length_counter -= 1;
+        }
+    }
+}
+
+impl LengthCounterChannel for PulseWave {
+    fn get_length_counter_load(&self) -> u8 {
+        (self.data[3] & 0b11111000) >> 3
+    }
+
+    fn set_length_counter(&mut self, counter: u8) {
+        self.length_counter = counter
+    }
+
+    fn get_length_counter(&self) -> u8 {
+        self.length_counter
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(super) struct Mmc5Audio {
+    pulse_1: PulseWave,
+    pulse_2: PulseWave,
+    cpu_cycle: u16,
+    audio_status_register: StatusRegister,
+    pcm_mode_register: u8,
+    raw_pcm: u8,
+    pcm_irq_pending: bool,
+}
+
+impl Mmc5Audio {
+    pub fn new() -> Self {
+        Self {
+            cpu_cycle: 8,
+            pulse_1: PulseWave::new(),
+            pulse_2: PulseWave::new(),
+            audio_status_register: StatusRegister { data: 0 },
+            pcm_mode_register: 0,
+            raw_pcm: 0,
+            pcm_irq_pending: false,
+        }
+    }
+
+    pub fn power_cycle(&mut self) {
+        self.cpu_cycle = 8;
+        self.audio_status_register.data = 0;
+        self.pcm_mode_register = 0;
+        self.raw_pcm = 0;
+        self.pcm_irq_pending = false;
+        self.pulse_1.power_cycle();
+        self.pulse_2.power_cycle();
+    }
+
+    fn is_half_frame_reached(&self) -> bool {
+        self.cpu_cycle == FRAME_COUNTER_HALF_FRAME_1_CPU_CYCLES
+            || self.cpu_cycle == FRAME_COUNTER_HALF_FRAME_0_MOD_0_CPU_CYCLES
+    }
+
+    fn is_quarter_frame_reached(&self) -> bool {
+        self.is_half_frame_reached()
+            || self.cpu_cycle == FRAME_COUNTER_QUARTER_FRAME_1_CPU_CYCLES
+            || self.cpu_cycle == FRAME_COUNTER_QUARTER_FRAME_3_CPU_CYCLES
+    }
+
+    fn store_pcm(&mut self, byte: u8) {
+        if byte == 0 {
+            self.pcm_irq_pending = true;
+        } else {
+            self.pcm_irq_pending = false;
+            self.raw_pcm = byte;
+        }
+    }
+
+    pub fn is_irq_pending(&self) -> bool {
+        self.pcm_mode_register & 0b1000_0000 != 0 && self.pcm_irq_pending
+    }
+
+    pub fn notify_prg_read(&mut self, address: u16, byte: u8) {
+        if (0x8000..=0xBFFF).contains(&address) && self.pcm_mode_register & 1 == 1 {
+            self.store_pcm(byte);
+        }
+    }
+
+    pub fn read(&mut self, address: u16) -> Option<u8> {
+        let value = self.peek(address);
+        if address == PCM_MODE_REGISTER {
+            self.pcm_irq_pending = false;
+        }
+        value
+    }
+
+    pub fn peek(&self, address: u16) -> Option<u8> {
+        match address {
+            AUDIO_STATUS_REGISTER => {
+                let mut out = StatusRegister { data: 0 };
+                out.set_flag_status(Pulse1Enabled, self.pulse_1.length_counter > 0);
+                out.set_flag_status(Pulse2Enabled, self.pulse_2.length_counter > 0);
+                Some(out.data)
+            }
+            PCM_MODE_REGISTER => {
+                let mut out = self.pcm_mode_register & 1;
+                let irq_enabled = self.pcm_mode_register & 0b1000_0000 != 0;
+                if irq_enabled && self.pcm_irq_pending {
+                    out |= 0b1000_0000;
+                }
+                Some(out)
+            }
+            _ => None,
+        }
+    }
+
+    pub fn write(&mut self, address: u16, byte: u8) -> bool {
+        match address {
+            PCM_MODE_REGISTER => {
+                self.pcm_mode_register = byte;
+            }
+            PCM_RAW_REGISTER => {
+                let write_mode = self.pcm_mode_register & 1 == 0;
+                if write_mode {
+                    self.store_pcm(byte);
+                }
+            }
+            AUDIO_STATUS_REGISTER => {
+                self.audio_status_register.data = byte;
+                if !self.audio_status_register.is_flag_enabled(Pulse1Enabled) {
+                    self.pulse_1.reset_length_counter();
+                }
+                if !self.audio_status_register.is_flag_enabled(Pulse2Enabled) {
+                    self.pulse_2.reset_length_counter();
+                }
+            }
+            PULSE_REGISTER_1..=PULSE_REGISTER_8 => {
+                let index = (address - PULSE_REGISTER_1) as usize;
+                let (pulse, status_flag) = if index < 4 {
+                    (&mut self.pulse_1, Pulse1Enabled)
+                } else {
+                    (&mut self.pulse_2, Pulse2Enabled)
+                };
+                match index % 4 {
+                    0 => pulse.data[0] = byte,
+                    1 => pulse.data[1] = byte,
+                    2 => {
+                        pulse.data[2] = byte;
+                        pulse.update_period();
+                    }
+                    3 => {
+                        pulse.data[3] = byte;
+                        pulse.update_period();
+                        pulse.reset_phase();
+                        if self.audio_status_register.is_flag_enabled(status_flag) {
+                            pulse.reload_length_counter();
+                        }
+                    }
+                    _ => unreachable!(),
+                }
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn clock(&mut self) -> f32 {
+        self.pulse_1.clock_timer();
+        self.pulse_2.clock_timer();
+        if self.is_quarter_frame_reached() {
+            self.pulse_1.clock_length_counter();
+            self.pulse_2.clock_length_counter();
+            self.pulse_1.clock_envelope();
+            self.pulse_2.clock_envelope();
+        }
+        let n = self.pulse_1.get_sample() + self.pulse_2.get_sample();
+        let pulse_out = if n != 0 {
+            95.52 / ((8128.0 / (n as f32)) + 100.0)
+        } else {
+            0.0
+        };
+        let pcm_out = self.raw_pcm as f32 / 256.0;
+        self.cpu_cycle = (self.cpu_cycle + 1) % (FRAME_COUNTER_HALF_FRAME_0_MOD_0_CPU_CYCLES + 1);
+        pulse_out + pcm_out
+    }
+}
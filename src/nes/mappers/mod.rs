@@ -1,22 +1,54 @@
 use super::common::Mirroring;
 
+mod bandai_fcg;
+mod datach_barcode_reader;
+mod eeprom_24c0x;
+mod fds_audio;
+mod flash_sst39sf040;
+mod four_screen_ram;
 mod mapper0;
 mod mapper1;
 mod mapper10;
+mod mapper11;
+mod mapper118;
+mod mapper119;
+mod mapper13;
+mod mapper140;
+mod mapper153;
+mod mapper157;
+mod mapper159;
+mod mapper16;
+mod mapper180;
 mod mapper2;
+mod mapper200;
+mod mapper201;
+mod mapper203;
+mod mapper206;
 mod mapper227;
+mod mapper228;
+mod mapper232;
 mod mapper3;
+mod mapper30;
+mod mapper34;
+mod mapper38;
 mod mapper4;
 mod mapper5;
 mod mapper66;
 mod mapper7;
 mod mapper71;
+mod mapper79;
 mod mapper9;
+mod mapper99;
+mod mapper_fds;
+mod mapper_nsf;
 mod mapper_null;
 mod mmc3_6;
+mod mmc5_audio;
 
 mod mapper_internal;
 
+pub(crate) use self::mapper_fds::MapperFds;
+pub(crate) use self::mapper_nsf::MapperNsf;
 pub(crate) use self::mapper_null::MapperNull;
 pub(crate) use self::mapper0::Mapper0;
 pub(crate) use self::mapper1::Mapper1;
@@ -27,9 +59,31 @@ pub(crate) use self::mapper5::Mapper5;
 pub(crate) use self::mapper7::Mapper7;
 pub(crate) use self::mapper9::Mapper9;
 pub(crate) use self::mapper10::Mapper10;
+pub(crate) use self::mapper11::Mapper11;
+pub(crate) use self::mapper13::Mapper13;
+pub(crate) use self::mapper16::Mapper16;
+pub(crate) use self::mapper30::Mapper30;
+pub(crate) use self::mapper30::Mapper30Mirroring;
+pub(crate) use self::mapper34::Mapper34;
+pub(crate) use self::mapper38::Mapper38;
 pub(crate) use self::mapper66::Mapper66;
 pub(crate) use self::mapper71::Mapper71;
+pub(crate) use self::mapper79::Mapper79;
+pub(crate) use self::mapper99::Mapper99;
+pub(crate) use self::mapper118::Mapper118;
+pub(crate) use self::mapper119::Mapper119;
+pub(crate) use self::mapper140::Mapper140;
+pub(crate) use self::mapper153::Mapper153;
+pub(crate) use self::mapper157::Mapper157;
+pub(crate) use self::mapper159::Mapper159;
+pub(crate) use self::mapper180::Mapper180;
+pub(crate) use self::mapper200::Mapper200;
+pub(crate) use self::mapper201::Mapper201;
+pub(crate) use self::mapper203::Mapper203;
+pub(crate) use self::mapper206::Mapper206;
 pub(crate) use self::mapper227::Mapper227;
+pub(crate) use self::mapper228::Mapper228;
+pub(crate) use self::mapper232::Mapper232;
 
 const PRG_RAM_RANGE: std::ops::Range<u16> = std::ops::Range {
     start: 0x6000,
@@ -45,7 +99,12 @@ pub trait Mapper {
     fn get_chr_byte(&mut self, address: u16) -> u8;
     fn store_chr_byte(&mut self, address: u16, byte: u8);
 
-    fn get_prg_byte(&mut self, address: u16) -> u8;
+    fn get_prg_byte(&mut self, address: u16) -> u8 {
+        self.peek_prg_byte(address)
+    }
+
+    // Reads like get_prg_byte, but leaves out the side effects reading some registers has.
+    fn peek_prg_byte(&self, address: u16) -> u8;
     fn store_prg_byte(&mut self, address: u16, byte: u8);
 
     fn get_mirroring(&self) -> Mirroring;
@@ -77,6 +136,8 @@ pub trait Mapper {
 
     fn notify_oam_dma_write(&mut self) {}
 
+    fn notify_controller_port_write(&mut self, _value: u8) {}
+
     fn notify_background_pattern_data_fetch(&mut self) {}
 
     fn notify_sprite_pattern_data_fetch(&mut self) {}
@@ -91,10 +152,22 @@ pub trait Mapper {
     fn clock_audio(&mut self) -> Option<f32> {
         None
     }
+
+    fn notify_cpu_cycle(&mut self) {}
+
+    fn has_bus_conflicts(&self) -> bool {
+        false
+    }
+
+    fn get_flash_image(&self) -> Option<Vec<u8>> {
+        None
+    }
+
+    fn load_trainer(&mut self, _trainer: &[u8]) {}
 }
 
 #[enum_dispatch::enum_dispatch]
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(Clone, serde::Serialize, serde::Deserialize)]
 #[allow(clippy::large_enum_variant)]
 pub enum MapperEnum {
     MapperNull(self::mapper_null::MapperNull),
@@ -107,7 +180,30 @@ pub enum MapperEnum {
     Mapper7(self::mapper7::Mapper7),
     Mapper9(self::mapper9::Mapper9),
     Mapper10(self::mapper10::Mapper10),
+    Mapper11(self::mapper11::Mapper11),
+    Mapper13(self::mapper13::Mapper13),
+    Mapper16(self::mapper16::Mapper16),
+    Mapper30(self::mapper30::Mapper30),
+    Mapper34(self::mapper34::Mapper34),
+    Mapper38(self::mapper38::Mapper38),
     Mapper66(self::mapper66::Mapper66),
     Mapper71(self::mapper71::Mapper71),
+    Mapper79(self::mapper79::Mapper79),
+    Mapper99(self::mapper99::Mapper99),
+    Mapper118(self::mapper118::Mapper118),
+    Mapper119(self::mapper119::Mapper119),
+    Mapper140(self::mapper140::Mapper140),
+    Mapper153(self::mapper153::Mapper153),
+    Mapper157(self::mapper157::Mapper157),
+    Mapper159(self::mapper159::Mapper159),
+    Mapper180(self::mapper180::Mapper180),
+    Mapper200(self::mapper200::Mapper200),
+    Mapper201(self::mapper201::Mapper201),
+    Mapper203(self::mapper203::Mapper203),
+    Mapper206(self::mapper206::Mapper206),
     Mapper227(self::mapper227::Mapper227),
+    Mapper228(self::mapper228::Mapper228),
+    Mapper232(self::mapper232::Mapper232),
+    MapperFds(self::mapper_fds::MapperFds),
+    MapperNsf(self::mapper_nsf::MapperNsf),
 }
@@ -8,7 +8,7 @@ pub trait Memory {
     fn store_byte(&mut self, addr: u16, byte: u8, bus: &mut RamBus);
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct MemoryImpl<const N: usize> {
     #[serde(with = "serde_arrays")]
     memory: [u8; N],
@@ -27,6 +27,14 @@ impl<const N: usize> MemoryImpl<N> {
         self.memory[addr as usize] = byte;
     }
 
+    pub fn as_slice(&self) -> &[u8] {
+        &self.memory
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        &mut self.memory
+    }
+
     pub fn clear(&mut self) {
         self.memory.iter_mut().for_each(|m| *m = 0);
     }
@@ -4,30 +4,62 @@ mod common;
 mod controllers;
 mod cpu;
 mod errors;
+mod fds_file;
+mod font;
+mod hooks;
+mod ips;
 mod mappers;
 mod memory;
 mod nes_file;
+mod nsf_file;
+mod ntsc_filter;
+mod playchoice10;
 mod ppu;
 mod ram;
 mod ram_apu;
 mod ram_controllers;
 mod ram_ppu;
+mod save_state;
+mod video_output;
 mod vram;
+mod vs_system;
 
 use apu::Apu;
+use colors::NtscColorMapper;
+use colors::PalColorMapper;
 use controllers::Controllers;
 use cpu::Cpu;
+use fds_file::FdsFile;
+use hooks::Hooks;
 use mappers::Mapper;
+use mappers::Mapper157;
 use mappers::MapperEnum;
 use mappers::MapperNull;
+use memory::Memory;
 use nes_file::NesFile;
+use nsf_file::NsfFile;
+use ntsc_filter::NtscFilter;
+use playchoice10::PlayChoice10;
 use ppu::Ppu;
+use ppu::PpuModel;
 use ppu::PpuState;
 use ram::Ram;
+use std::ops::RangeInclusive;
+use vs_system::VsProtection;
 
+pub use colors::BuiltinPalette;
+pub use colors::NtscPaletteSettings;
 pub use errors::*;
-
-const SERIALIZATION_VER: &str = "1";
+pub use hooks::{HookId, Interrupt, MemoryHookType};
+pub use ips::*;
+pub use nsf_file::NsfInfo;
+pub use ntsc_filter::{
+    NTSC_VIDEO_FRAME_SIZE, NTSC_VIDEO_FRAME_WIDTH, NtscFilterSettings, NtscVideoFrame,
+};
+pub use save_state::{SAVE_STATE_THUMBNAIL_HEIGHT, SAVE_STATE_THUMBNAIL_WIDTH, SaveStateInfo};
+pub use video_output::{
+    CropRect, Overscan, PixelAspectRatio, VideoOutputImage, VideoOutputSettings,
+};
 
 #[derive(Copy, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub enum ControllerId {
@@ -60,9 +92,31 @@ pub enum ZapperTarget {
     OnScreen(u8, u8),
 }
 
+#[derive(Copy, Clone, PartialEq, Eq, Debug)]
+pub enum VsCoinSlot {
+    Coin1,
+    Coin2,
+}
+
 pub trait ControllerCallback {
     fn is_button_pressed(&self, id: ControllerId, button: StdNesControllerButton) -> bool;
     fn is_zapper_trigger_pressed(&self, id: ControllerId) -> Option<ZapperTarget>;
+
+    fn is_vs_coin_inserted(&self, _slot: VsCoinSlot) -> bool {
+        false
+    }
+
+    fn is_vs_service_button_pressed(&self) -> bool {
+        false
+    }
+
+    fn get_vs_dip_switches(&self) -> u8 {
+        0
+    }
+
+    fn is_playchoice_coin_inserted(&self) -> bool {
+        false
+    }
 }
 
 trait ControllerCallbackRef {
@@ -87,7 +141,9 @@ struct CpuBus<'a> {
     pub apu: &'a mut Apu,
     pub mapper: &'a mut MapperEnum,
     pub controllers: &'a mut Controllers,
+    pub vs_protection: &'a mut Option<VsProtection>,
     pub callback: Option<&'a dyn ControllerCallback>,
+    pub hooks: Option<&'a mut Hooks>,
 }
 
 macro_rules! cpu_bus {
@@ -98,7 +154,22 @@ macro_rules! cpu_bus {
             apu: &mut $nes.apu,
             mapper: &mut $nes.mapper,
             controllers: &mut $nes.controllers,
+            vs_protection: &mut $nes.vs_protection,
             callback: $callback,
+            hooks: $nes.hooks.as_deref_mut().filter(|hooks| !hooks.is_empty()),
+        }
+    }};
+}
+
+macro_rules! ram_bus {
+    ($nes:expr) => {{
+        RamBus {
+            ppu: &mut $nes.ppu,
+            apu: &mut $nes.apu,
+            mapper: &mut $nes.mapper,
+            controllers: &mut $nes.controllers,
+            vs_protection: &mut $nes.vs_protection,
+            callback: None,
         }
     }};
 }
@@ -112,6 +183,7 @@ struct RamBus<'a> {
     pub ppu: &'a mut Ppu,
     pub mapper: &'a mut MapperEnum,
     pub controllers: &'a mut Controllers,
+    pub vs_protection: &'a mut Option<VsProtection>,
     pub callback: Option<&'a dyn ControllerCallback>,
 }
 pub const DEFAULT_FPS: u16 = 60;
@@ -121,6 +193,7 @@ pub const VIDEO_FRAME_HEIGHT: usize = 240;
 pub const VIDEO_FRAME_SIZE: usize = VIDEO_FRAME_HEIGHT * VIDEO_FRAME_WIDTH * PIXEL_SIZE;
 pub const MAX_AUDIO_FRAME_SIZE: usize = 2048;
 pub const SAMPLING_RATE: usize = 44100;
+pub const MAX_RUN_AHEAD_FRAMES: u8 = 4;
 
 #[derive(Clone)]
 pub struct VideoFrame {
@@ -154,6 +227,40 @@ impl VideoFrame {
     }
 }
 
+#[derive(Clone)]
+pub struct IndexFrame {
+    indices: Box<[u16; VIDEO_FRAME_WIDTH * VIDEO_FRAME_HEIGHT]>,
+}
+
+impl IndexFrame {
+    pub(crate) fn new() -> Self {
+        Self {
+            indices: Box::new([0; VIDEO_FRAME_WIDTH * VIDEO_FRAME_HEIGHT]),
+        }
+    }
+
+    pub fn get_indices(&self) -> &[u16; VIDEO_FRAME_WIDTH * VIDEO_FRAME_HEIGHT] {
+        &self.indices
+    }
+
+    // Bits 0-5 hold the NES color, bits 6-8 the R/G/B emphasis.
+    pub fn get_index(&self, x: u8, y: u8) -> u16 {
+        self.indices[y as usize * VIDEO_FRAME_WIDTH + x as usize]
+    }
+
+    pub fn get_color(&self, x: u8, y: u8) -> u8 {
+        (self.get_index(x, y) & 0x3F) as u8
+    }
+
+    pub fn get_emphasis(&self, x: u8, y: u8) -> u8 {
+        (self.get_index(x, y) >> 6) as u8
+    }
+
+    pub(crate) fn set_index(&mut self, x: u8, y: u8, index: u16) {
+        self.indices[y as usize * VIDEO_FRAME_WIDTH + x as usize] = index;
+    }
+}
+
 #[derive(Clone)]
 pub struct AudioFrame {
     samples: Box<[f32; MAX_AUDIO_FRAME_SIZE]>,
@@ -186,10 +293,40 @@ impl AudioFrame {
     }
 }
 
+impl Default for AudioFrame {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 #[derive(Clone)]
 pub struct EmulationFrame {
     pub video: VideoFrame,
     pub audio: AudioFrame,
+    pub ntsc_video: Option<NtscVideoFrame>,
+    pub indices: Option<IndexFrame>,
+    is_index_frame_requested: bool,
+}
+
+impl EmulationFrame {
+    fn set_ntsc_video_enabled(&mut self, enabled: bool) {
+        self.ntsc_video = enabled.then(NtscVideoFrame::new);
+        self.update_index_frame();
+    }
+
+    fn set_index_frame_enabled(&mut self, enabled: bool) {
+        self.is_index_frame_requested = enabled;
+        self.update_index_frame();
+    }
+
+    fn update_index_frame(&mut self) {
+        let is_needed = self.is_index_frame_requested || self.ntsc_video.is_some();
+        if !is_needed {
+            self.indices = None;
+        } else if self.indices.is_none() {
+            self.indices = Some(IndexFrame::new());
+        }
+    }
 }
 
 impl Default for EmulationFrame {
@@ -197,6 +334,9 @@ impl Default for EmulationFrame {
         Self {
             video: VideoFrame::new(),
             audio: AudioFrame::new(),
+            ntsc_video: None,
+            indices: None,
+            is_index_frame_requested: false,
         }
     }
 }
@@ -216,9 +356,41 @@ impl Default for AudioConfig {
     }
 }
 
+/// In-memory copy of the emulation state, meant for frequent save and restore.
+#[derive(Clone, Default)]
+pub struct Snapshot {
+    state: Option<Box<EmulationState>>,
+    playtime_frames: u64,
+}
+
+// What a save state keeps of a `Nes`, without the frontend settings and the presentation buffers.
+#[derive(Clone)]
+struct EmulationState {
+    cpu: Cpu,
+    ram: Ram,
+    ppu: Ppu,
+    apu: Apu,
+    controllers: Controllers,
+    mapper: MapperEnum,
+    playchoice10: Option<PlayChoice10>,
+    vs_protection: Option<VsProtection>,
+}
+
+#[derive(Default)]
+pub(crate) struct RunAhead {
+    frames: u8,
+    secondary: Option<Box<Nes>>,
+    snapshot: Snapshot,
+    audio: AudioFrame,
+}
+
 pub struct Config<'a> {
     audio_config: &'a mut AudioConfig,
+    run_ahead: &'a mut RunAhead,
     controllers: &'a mut Controllers,
+    ppu: &'a mut Ppu,
+    ntsc_filter: &'a mut Option<NtscFilter>,
+    emulation_frame: &'a mut EmulationFrame,
 }
 
 impl Config<'_> {
@@ -241,6 +413,69 @@ impl Config<'_> {
     pub fn get_controller_type(&self, id: ControllerId) -> ControllerType {
         self.controllers.get_controller_type(id)
     }
+
+    pub fn set_palette(&mut self, pal: &[u8]) -> Result<(), Error> {
+        self.ppu
+            .set_color_mapper(Box::new(PalColorMapper::new(pal)?));
+        Ok(())
+    }
+
+    pub fn set_builtin_palette(&mut self, palette: BuiltinPalette) {
+        self.ppu.set_color_mapper(palette.create_color_mapper());
+    }
+
+    pub fn set_ntsc_palette(&mut self, settings: NtscPaletteSettings) {
+        self.ppu
+            .set_color_mapper(Box::new(NtscColorMapper::new(settings)));
+    }
+
+    pub fn set_ntsc_filter(&mut self, settings: Option<NtscFilterSettings>) {
+        *self.ntsc_filter = settings.map(NtscFilter::new);
+        self.emulation_frame
+            .set_ntsc_video_enabled(settings.is_some());
+    }
+
+    pub fn get_ntsc_filter(&self) -> Option<NtscFilterSettings> {
+        self.ntsc_filter
+            .as_ref()
+            .map(|filter| filter.get_settings())
+    }
+
+    pub fn set_index_frame_enabled(&mut self, enabled: bool) {
+        self.emulation_frame.set_index_frame_enabled(enabled);
+    }
+
+    pub fn is_index_frame_enabled(&self) -> bool {
+        self.emulation_frame.is_index_frame_requested
+    }
+
+    pub fn set_sprite_limit_enabled(&mut self, enabled: bool) {
+        self.ppu.set_sprite_limit_enabled(enabled);
+    }
+
+    pub fn is_sprite_limit_enabled(&self) -> bool {
+        self.ppu.is_sprite_limit_enabled()
+    }
+
+    pub fn set_run_ahead_frames(&mut self, frames: u8) {
+        self.run_ahead.frames = frames.min(MAX_RUN_AHEAD_FRAMES);
+    }
+
+    pub fn get_run_ahead_frames(&self) -> u8 {
+        self.run_ahead.frames
+    }
+
+    // A second instance runs ahead instead, so the state of the presented instance is never
+    // restored from a snapshot and its audio stays continuous.
+    pub fn set_run_ahead_second_instance_enabled(&mut self, enabled: bool) {
+        if enabled != self.run_ahead.secondary.is_some() {
+            self.run_ahead.secondary = enabled.then(|| Box::new(Nes::new()));
+        }
+    }
+
+    pub fn is_run_ahead_second_instance_enabled(&self) -> bool {
+        self.run_ahead.secondary.is_some()
+    }
 }
 
 pub(crate) struct ApuBus<'a> {
@@ -251,93 +486,418 @@ pub(crate) struct ApuBus<'a> {
 }
 #[derive(serde::Serialize, serde::Deserialize)]
 pub struct Nes {
-    version: String,
     cpu: Cpu,
     ram: Ram,
     ppu: Ppu,
     apu: Apu,
     controllers: Controllers,
     mapper: MapperEnum,
+    playchoice10: Option<PlayChoice10>,
+    #[serde(default)]
+    vs_protection: Option<VsProtection>,
     #[serde(skip, default)]
     audio_config: AudioConfig,
     #[serde(skip, default)]
     emulation_frame: EmulationFrame,
+    #[serde(skip, default)]
+    ntsc_filter: Option<NtscFilter>,
+    #[serde(skip, default)]
+    hooks: Option<Box<Hooks>>,
+    #[serde(skip, default)]
+    rom_crc32: Option<u32>,
+    #[serde(skip, default)]
+    playtime_frames: u64,
+    #[serde(skip, default)]
+    run_ahead: RunAhead,
+    #[serde(skip, default)]
+    is_playchoice_coin_inserted: bool,
 }
 
 impl Nes {
     pub fn new() -> Self {
         Nes {
-            version: SERIALIZATION_VER.to_string(),
             cpu: Cpu::new(),
             ram: Ram::new(),
             ppu: Ppu::new(),
             apu: Apu::new(),
             controllers: Controllers::new(),
             mapper: MapperEnum::MapperNull(MapperNull::new()),
+            playchoice10: None,
+            vs_protection: None,
             audio_config: AudioConfig::default(),
             emulation_frame: EmulationFrame::default(),
+            ntsc_filter: None,
+            hooks: None,
+            rom_crc32: None,
+            playtime_frames: 0,
+            run_ahead: RunAhead::default(),
+            is_playchoice_coin_inserted: false,
         }
     }
 
     pub fn save_state(&self) -> Result<Vec<u8>, Error> {
-        let serialized =
-            serde_json::to_vec(self).map_err(|e| Error::SaveStateInternalError(e.to_string()))?;
-        yazi::compress(
-            serialized.as_slice(),
-            yazi::Format::Zlib,
-            yazi::CompressionLevel::Default,
-        )
-        .map_err(Error::LoadStateCompressionError)
+        save_state::encode(self, self.rom_crc32, self.playtime_frames, None)
+    }
+
+    pub fn save_state_with_thumbnail(&self) -> Result<Vec<u8>, Error> {
+        let thumbnail = save_state::create_thumbnail(&self.emulation_frame.video);
+        save_state::encode(self, self.rom_crc32, self.playtime_frames, Some(&thumbnail))
+    }
+
+    pub fn get_rom_crc32(&self) -> Option<u32> {
+        self.rom_crc32
+    }
+
+    // Save states are tied to this CRC32. Frontends that patch an image before loading it set the
+    // CRC32 of the unpatched file, so the states keep matching the file on disk.
+    pub fn set_rom_crc32(&mut self, rom_crc32: u32) {
+        self.rom_crc32 = Some(rom_crc32);
+    }
+
+    pub fn get_save_state_info(state: &[u8]) -> Result<SaveStateInfo, Error> {
+        let (header, _) = save_state::split(state)?;
+        header.to_info()
+    }
+
+    pub fn save_snapshot(&self, snapshot: &mut Snapshot) {
+        match snapshot.state.as_mut() {
+            Some(state) => {
+                state.cpu.clone_from(&self.cpu);
+                state.ram.clone_from(&self.ram);
+                state.ppu.clone_from(&self.ppu);
+                state.apu.clone_from(&self.apu);
+                state.controllers.clone_from(&self.controllers);
+                state.mapper.clone_from(&self.mapper);
+                state.playchoice10.clone_from(&self.playchoice10);
+                state.vs_protection.clone_from(&self.vs_protection);
+            }
+            None => {
+                snapshot.state = Some(Box::new(EmulationState {
+                    cpu: self.cpu.clone(),
+                    ram: self.ram.clone(),
+                    ppu: self.ppu.clone(),
+                    apu: self.apu.clone(),
+                    controllers: self.controllers.clone(),
+                    mapper: self.mapper.clone(),
+                    playchoice10: self.playchoice10.clone(),
+                    vs_protection: self.vs_protection.clone(),
+                }));
+            }
+        }
+        snapshot.playtime_frames = self.playtime_frames;
+    }
+
+    pub fn load_snapshot(&mut self, snapshot: &Snapshot) -> Result<(), Error> {
+        let Some(state) = snapshot.state.as_ref() else {
+            return Err(Error::LoadStateInternalError(
+                "Snapshot is empty".to_string(),
+            ));
+        };
+        let color_mapper = self.ppu.take_color_mapper();
+        let sprite_limit_enabled = self.ppu.is_sprite_limit_enabled();
+        let audio_buffer = self.apu.take_audio_buffer();
+        self.cpu.clone_from(&state.cpu);
+        self.ram.clone_from(&state.ram);
+        self.ppu.clone_from(&state.ppu);
+        self.apu.clone_from(&state.apu);
+        self.controllers.clone_from(&state.controllers);
+        self.mapper.clone_from(&state.mapper);
+        self.playchoice10.clone_from(&state.playchoice10);
+        self.vs_protection.clone_from(&state.vs_protection);
+        self.ppu.set_color_mapper(color_mapper);
+        self.ppu.set_sprite_limit_enabled(sprite_limit_enabled);
+        self.apu.set_audio_buffer(audio_buffer);
+        self.playtime_frames = snapshot.playtime_frames;
+        Ok(())
+    }
+
+    // Moves the snapshot back in place instead of copying it and leaves the replaced state in the
+    // snapshot. Unlike `load_snapshot` it also restores the presentation settings of the PPU, so it's
+    // only meant for a snapshot of this instance taken within the same frame.
+    fn swap_snapshot(&mut self, snapshot: &mut Snapshot) -> Result<(), Error> {
+        let Some(state) = snapshot.state.as_mut() else {
+            return Err(Error::LoadStateInternalError(
+                "Snapshot is empty".to_string(),
+            ));
+        };
+        std::mem::swap(&mut self.cpu, &mut state.cpu);
+        std::mem::swap(&mut self.ram, &mut state.ram);
+        std::mem::swap(&mut self.ppu, &mut state.ppu);
+        std::mem::swap(&mut self.apu, &mut state.apu);
+        std::mem::swap(&mut self.controllers, &mut state.controllers);
+        std::mem::swap(&mut self.mapper, &mut state.mapper);
+        std::mem::swap(&mut self.playchoice10, &mut state.playchoice10);
+        std::mem::swap(&mut self.vs_protection, &mut state.vs_protection);
+        std::mem::swap(&mut self.playtime_frames, &mut snapshot.playtime_frames);
+        Ok(())
     }
 
     pub fn config(&mut self) -> Config<'_> {
         Config {
             audio_config: &mut self.audio_config,
+            run_ahead: &mut self.run_ahead,
             controllers: &mut self.controllers,
+            ppu: &mut self.ppu,
+            ntsc_filter: &mut self.ntsc_filter,
+            emulation_frame: &mut self.emulation_frame,
         }
     }
 
     pub fn load_state(&mut self, state: Vec<u8>) -> Result<(), Error> {
-        let (decompressed, checksum) = yazi::decompress(state.as_slice(), yazi::Format::Zlib)
-            .map_err(Error::LoadStateDecompressionError)?;
-
-        if let Some(checksum) = checksum
-            && checksum != yazi::Adler32::from_buf(&decompressed).finish()
+        let (header, body) = save_state::split(&state)?;
+        if let (Some(expected), Some(found)) = (self.rom_crc32, header.rom_crc32)
+            && expected != found
         {
-            return Err(Error::LoadStateInternalError(
-                "Checksum mismatch".to_string(),
-            ));
-        }
-        let mut deserializer = serde_json::Deserializer::from_slice(&decompressed);
-        let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
-        let value = <serde_json::Value as serde::Deserialize>::deserialize(deserializer)
-            .map_err(|e| Error::LoadStateInternalError(e.to_string()))?;
-        let new_nes: Nes = serde_json::from_value(value)
-            .map_err(|e| Error::LoadStateInternalError(e.to_string()))?;
-        if new_nes.version != SERIALIZATION_VER {
-            return Err(Error::LoadStateVersionMismatch(
-                SERIALIZATION_VER.to_string(),
-                new_nes.version,
-            ));
+            return Err(Error::LoadStateRomMismatch(expected, found));
         }
+        let new_nes: Nes = save_state::decode(&header, body)?;
+        let rom_crc32 = header.rom_crc32.or(self.rom_crc32);
+        let playtime_frames = header.playtime_frames.unwrap_or(self.playtime_frames);
+        self.replace_state(new_nes);
+        self.rom_crc32 = rom_crc32;
+        self.playtime_frames = playtime_frames;
+        Ok(())
+    }
+
+    fn replace_state(&mut self, new_nes: Nes) {
         let old_audio_config = self.audio_config.clone();
+        let old_color_mapper = self.ppu.take_color_mapper();
+        let old_sprite_limit_enabled = self.ppu.is_sprite_limit_enabled();
+        let old_emulation_frame = std::mem::take(&mut self.emulation_frame);
+        let old_audio_buffer = self.apu.take_audio_buffer();
+        let old_ntsc_filter = self.ntsc_filter.take();
+        let old_hooks = self.hooks.take();
+        let old_rom_crc32 = self.rom_crc32;
+        let old_run_ahead = std::mem::take(&mut self.run_ahead);
+        let old_playchoice_coin_inserted = self.is_playchoice_coin_inserted;
         *self = new_nes;
         self.audio_config = old_audio_config;
-        Ok(())
+        self.ppu.set_color_mapper(old_color_mapper);
+        self.ppu.set_sprite_limit_enabled(old_sprite_limit_enabled);
+        self.emulation_frame = old_emulation_frame;
+        self.apu.set_audio_buffer(old_audio_buffer);
+        self.ntsc_filter = old_ntsc_filter;
+        self.hooks = old_hooks;
+        self.rom_crc32 = old_rom_crc32;
+        self.run_ahead = old_run_ahead;
+        self.is_playchoice_coin_inserted = old_playchoice_coin_inserted;
     }
 
     pub fn load_rom(&mut self, rom: &[u8]) -> Result<(), Error> {
         let nes_file = NesFile::new(rom)?;
         self.mapper = nes_file.create_mapper()?;
+        self.ppu.set_model(nes_file.get_ppu_model());
+        let vs_hardware_type = nes_file.get_vs_hardware_type();
+        self.controllers.set_vs_system(vs_hardware_type.is_some());
+        self.vs_protection = vs_hardware_type.map(VsProtection::new);
+        self.playchoice10 = nes_file.is_playchoice10().then(|| {
+            let mut playchoice10 = PlayChoice10::new();
+            playchoice10.insert_credit();
+            playchoice10
+        });
+        self.rom_crc32 = Some(crc32fast::hash(rom));
+        self.playtime_frames = 0;
         self.power_cycle();
         Ok(())
     }
 
+    pub fn load_fds(&mut self, image: &[u8], bios: &[u8]) -> Result<(), Error> {
+        let fds_file = FdsFile::new(image, bios)?;
+        self.mapper = fds_file.create_mapper();
+        self.ppu.set_model(PpuModel::Rp2C02);
+        self.controllers.set_vs_system(false);
+        self.vs_protection = None;
+        self.playchoice10 = None;
+        self.rom_crc32 = Some(crc32fast::hash(image));
+        self.playtime_frames = 0;
+        self.power_cycle();
+        Ok(())
+    }
+
+    pub fn get_disk_side_count(&self) -> Option<usize> {
+        match &self.mapper {
+            MapperEnum::MapperFds(fds) => Some(fds.get_disk_side_count()),
+            _ => None,
+        }
+    }
+
+    pub fn get_inserted_disk_side(&self) -> Option<usize> {
+        match &self.mapper {
+            MapperEnum::MapperFds(fds) => fds.get_inserted_disk_side(),
+            _ => None,
+        }
+    }
+
+    pub fn insert_disk_side(&mut self, disk_side: Option<usize>) -> Result<(), Error> {
+        let MapperEnum::MapperFds(fds) = &mut self.mapper else {
+            return Err(Error::FdsNotLoaded);
+        };
+        if let Some(side) = disk_side
+            && side >= fds.get_disk_side_count()
+        {
+            return Err(Error::FdsDiskSideUnavailable(side));
+        }
+        fds.insert_disk_side(disk_side);
+        Ok(())
+    }
+
+    pub fn get_disk_image(&self) -> Option<Vec<u8>> {
+        match &self.mapper {
+            MapperEnum::MapperFds(fds) => Some(fds.get_disk_image()),
+            _ => None,
+        }
+    }
+
+    pub fn load_nsf(&mut self, nsf: &[u8]) -> Result<(), Error> {
+        let nsf_file = NsfFile::new(nsf)?;
+        self.mapper = nsf_file.create_mapper();
+        self.ppu.set_model(PpuModel::Rp2C02);
+        self.controllers.set_vs_system(false);
+        self.vs_protection = None;
+        self.playchoice10 = None;
+        self.rom_crc32 = Some(crc32fast::hash(nsf));
+        self.playtime_frames = 0;
+        self.power_cycle();
+        Ok(())
+    }
+
+    pub fn get_nsf_info(&self) -> Option<&NsfInfo> {
+        match &self.mapper {
+            MapperEnum::MapperNsf(nsf) => Some(nsf.get_info()),
+            _ => None,
+        }
+    }
+
+    pub fn select_nsf_track(&mut self, track: usize) -> Result<(), Error> {
+        let MapperEnum::MapperNsf(nsf) = &mut self.mapper else {
+            return Err(Error::NsfNotLoaded);
+        };
+        if track >= nsf.get_info().track_count {
+            return Err(Error::NsfTrackUnavailable(track));
+        }
+        nsf.select_track(track);
+        self.power_cycle();
+        Ok(())
+    }
+
+    pub fn scan_barcode(&mut self, barcode: &str) -> Result<(), Error> {
+        let MapperEnum::Mapper157(datach) = &mut self.mapper else {
+            return Err(Error::BarcodeReaderUnavailable);
+        };
+        if !Mapper157::is_valid_barcode(barcode) {
+            return Err(Error::InvalidBarcode(barcode.to_string()));
+        }
+        datach.scan_barcode(barcode);
+        Ok(())
+    }
+
+    pub fn get_flash_image(&self) -> Option<Vec<u8>> {
+        self.mapper.get_flash_image()
+    }
+
+    pub fn insert_playchoice_credit(&mut self) -> Result<(), Error> {
+        let Some(playchoice10) = self.playchoice10.as_mut() else {
+            return Err(Error::PlayChoiceNotLoaded);
+        };
+        playchoice10.insert_credit();
+        Ok(())
+    }
+
+    pub fn get_playchoice_time_remaining(&self) -> Option<u32> {
+        self.playchoice10
+            .as_ref()
+            .map(|playchoice10| playchoice10.get_seconds_remaining())
+    }
+
+    // The game keeps running once the time is up, it is up to the frontend to ask for a coin.
+    pub fn is_playchoice_time_expired(&self) -> bool {
+        self.playchoice10
+            .as_ref()
+            .is_some_and(|playchoice10| playchoice10.is_time_expired())
+    }
+
+    pub fn read_cpu_memory(&mut self, address: u16) -> u8 {
+        let mut ram_bus = ram_bus!(self);
+        self.ram.get_byte(address, &mut ram_bus)
+    }
+
+    pub fn peek_cpu_memory(&self, address: u16) -> u8 {
+        self.ram
+            .peek_byte(address, &self.mapper, self.vs_protection.as_ref())
+    }
+
+    pub fn write_cpu_memory(&mut self, address: u16, value: u8) {
+        let mut ram_bus = ram_bus!(self);
+        self.ram.store_byte(address, value, &mut ram_bus);
+    }
+
+    pub fn add_memory_hook<F>(
+        &mut self,
+        hook_type: MemoryHookType,
+        addresses: RangeInclusive<u16>,
+        hook: F,
+    ) -> HookId
+    where
+        F: FnMut(u16, u8) + Send + 'static,
+    {
+        self.hooks
+            .get_or_insert_default()
+            .add_memory_hook(hook_type, addresses, Box::new(hook))
+    }
+
+    pub fn add_interrupt_hook<F>(&mut self, hook: F) -> HookId
+    where
+        F: FnMut(Interrupt, u16) + Send + 'static,
+    {
+        self.hooks
+            .get_or_insert_default()
+            .add_interrupt_hook(Box::new(hook))
+    }
+
+    pub fn remove_hook(&mut self, id: HookId) -> bool {
+        self.hooks
+            .as_mut()
+            .is_some_and(|hooks| hooks.remove_hook(id))
+    }
+
+    pub fn get_internal_ram(&self) -> &[u8] {
+        self.ram.get_internal_ram()
+    }
+
+    pub fn get_internal_ram_mut(&mut self) -> &mut [u8] {
+        self.ram.get_internal_ram_mut()
+    }
+
+    pub fn get_emulation_frame(&self) -> &EmulationFrame {
+        &self.emulation_frame
+    }
+
+    pub fn get_playtime_frames(&self) -> u64 {
+        self.playtime_frames
+    }
+
+    pub fn draw_text(&mut self, x: u8, y: u8, text: &str, color: (u8, u8, u8)) {
+        let emulation_frame = &mut self.emulation_frame;
+        font::draw_text(x as usize, y as usize, text, |x, y| {
+            emulation_frame.video.set_pixel(x as u8, y as u8, color);
+            if let Some(ntsc_video) = emulation_frame.ntsc_video.as_mut() {
+                let ntsc_x = x * NTSC_VIDEO_FRAME_WIDTH / VIDEO_FRAME_WIDTH;
+                let ntsc_x_end = (x + 1) * NTSC_VIDEO_FRAME_WIDTH / VIDEO_FRAME_WIDTH;
+                for ntsc_x in ntsc_x..ntsc_x_end {
+                    ntsc_video.set_pixel(ntsc_x, y, color);
+                }
+            }
+        });
+    }
+
     pub fn power_cycle(&mut self) {
         self.ppu.power_cycle();
         self.apu.power_cycle();
         self.ram.power_cycle();
         self.mapper.power_cycle();
+        if let Some(vs_protection) = self.vs_protection.as_mut() {
+            vs_protection.power_cycle();
+        }
         let mut cpu_bus = cpu_bus!(self, None);
         self.cpu.power_cycle(&mut cpu_bus);
         self.controllers.power_cycle();
@@ -349,15 +909,79 @@ impl Nes {
         C: ControllerCallbackRef,
     {
         let callback = callback.as_option();
+        self.run_frame(callback)?;
+        if self.run_ahead.frames > 0 {
+            self.run_ahead(callback)?;
+        }
+        Ok(&self.emulation_frame)
+    }
+
+    fn run_frame(&mut self, callback: Option<&dyn ControllerCallback>) -> Result<(), Error> {
         self.emulation_frame.audio.reset();
         self.apu.reset_audio_buffer();
+        let is_coin_inserted =
+            callback.is_some_and(|callback| callback.is_playchoice_coin_inserted());
+        let is_new_coin = is_coin_inserted && !self.is_playchoice_coin_inserted;
+        self.is_playchoice_coin_inserted = is_coin_inserted;
+        if let Some(playchoice10) = self.playchoice10.as_mut() {
+            if is_new_coin {
+                playchoice10.insert_credit();
+            }
+            playchoice10.notify_frame();
+        }
         let current_frame = self.ppu.get_time().frame;
         while self.ppu.get_time().frame == current_frame {
             self.run_single_cpu_cycle(callback)?;
         }
+        self.playtime_frames += 1;
         self.controllers
             .update_zappers(&self.emulation_frame, self.ppu.get_time().frame);
-        Ok(&self.emulation_frame)
+        if let Some(ntsc_filter) = self.ntsc_filter.as_mut() {
+            ntsc_filter.apply(&mut self.emulation_frame);
+        }
+        Ok(())
+    }
+
+    // Presents the video of a frame emulated `run_ahead.frames` into the future, while the
+    // audio and the emulation state stay those of the current frame.
+    fn run_ahead(&mut self, callback: Option<&dyn ControllerCallback>) -> Result<(), Error> {
+        let frames = self.run_ahead.frames;
+        let mut snapshot = std::mem::take(&mut self.run_ahead.snapshot);
+        self.save_snapshot(&mut snapshot);
+        std::mem::swap(&mut self.emulation_frame.audio, &mut self.run_ahead.audio);
+        let hooks = self.hooks.take();
+        let result = match self.run_ahead.secondary.take() {
+            Some(mut secondary) => {
+                let result = secondary.load_snapshot(&snapshot).and_then(|_| {
+                    self.swap_presentation(&mut secondary);
+                    let result = (0..frames).try_for_each(|_| secondary.run_frame(callback));
+                    self.swap_presentation(&mut secondary);
+                    result
+                });
+                self.run_ahead.secondary = Some(secondary);
+                result
+            }
+            None => {
+                let result = (0..frames).try_for_each(|_| self.run_frame(callback));
+                self.swap_snapshot(&mut snapshot).and(result)
+            }
+        };
+        self.hooks = hooks;
+        std::mem::swap(&mut self.emulation_frame.audio, &mut self.run_ahead.audio);
+        self.run_ahead.snapshot = snapshot;
+        result
+    }
+
+    fn swap_presentation(&mut self, other: &mut Nes) {
+        let color_mapper = self.ppu.take_color_mapper();
+        self.ppu.set_color_mapper(other.ppu.take_color_mapper());
+        other.ppu.set_color_mapper(color_mapper);
+        let sprite_limit_enabled = self.ppu.is_sprite_limit_enabled();
+        self.ppu
+            .set_sprite_limit_enabled(other.ppu.is_sprite_limit_enabled());
+        other.ppu.set_sprite_limit_enabled(sprite_limit_enabled);
+        std::mem::swap(&mut self.emulation_frame, &mut other.emulation_frame);
+        std::mem::swap(&mut self.ntsc_filter, &mut other.ntsc_filter);
     }
 
     fn run_single_cpu_cycle(
@@ -378,6 +1002,7 @@ impl Nes {
             config: &self.audio_config,
         };
         self.apu.run_single_cpu_cycle(&mut apu_bus);
+        self.mapper.notify_cpu_cycle();
         let mut cpu_bus = cpu_bus!(self, callback);
         self.cpu.run_single_cycle(&mut cpu_bus);
         Ok(())
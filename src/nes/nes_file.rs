@@ -1,6 +1,8 @@
 use super::common;
 use super::errors::Error;
 use super::mappers::*;
+use super::ppu::PpuModel;
+use super::vs_system::VsHardwareType;
 use Error::*;
 
 #[derive(PartialEq, Debug)]
@@ -10,13 +12,13 @@ enum NesFormat {
 }
 enum HeaderFlag6 {
     MirroringVertical = 0b00000001,
-    _PrgRAMPresent = 0b00000010,
+    Battery = 0b00000010,
     TrainerPresent = 0b00000100,
-    _IgnoreMirroring = 0b00001000,
+    IgnoreMirroring = 0b00001000,
 }
 
 enum HeaderFlag7 {
-    _VsSystem = 0b00000001,
+    VsSystem = 0b00000001,
     PlayChoice10 = 0b00000010,
     _Flags8_15InNes2 = 0b00001100,
 }
@@ -28,7 +30,7 @@ enum _HeaderFlag9 {
 enum HeaderFlag10 {
     _TvSystem = 0b00000011,
     PrgRAMPresent = 0b00010000,
-    _BusConflictPresent = 0b00100000,
+    BusConflictPresent = 0b00100000,
 }
 
 #[derive(Debug)]
@@ -58,13 +60,21 @@ struct PlayChoiceRom {
 }
 
 pub struct NesFile {
-    _trainer: Option<Trainer>,
+    trainer: Option<Trainer>,
     prg_rom: Vec<PrgRomUnit>,
     chr_rom: Vec<ChrRomUnit>,
-    _play_choice_rom: Option<PlayChoiceRom>,
+    play_choice_rom: Option<PlayChoiceRom>,
     _prg_ram_size: u32,
     mapper_number: u32,
+    submapper: u8,
+    bus_conflicts: Option<bool>,
     mirroring: common::Mirroring,
+    ignore_mirroring: bool,
+    has_battery: bool,
+    image_header: Vec<u8>,
+    image_footer: Vec<u8>,
+    vs_hardware_type: Option<VsHardwareType>,
+    ppu_model: PpuModel,
 }
 
 fn read_to_array(array: &mut [u8], in_bytes: &[u8]) -> usize {
@@ -85,26 +95,37 @@ impl NesFile {
             chr_rom.extend_from_slice(chr_rom_chunk);
         }
 
-        match self.mapper_number {
+        let mut mapper = match self.mapper_number {
             0 => Ok(MapperEnum::Mapper0(Mapper0::new(
                 prg_rom,
                 chr_rom,
-                self.mirroring,
+                self.get_mirroring(),
             ))),
             1 => Ok(MapperEnum::Mapper1(Mapper1::new(prg_rom, chr_rom))),
             2 => Ok(MapperEnum::Mapper2(Mapper2::new(
                 prg_rom,
                 chr_rom,
                 self.mirroring,
+                self.bus_conflicts.unwrap_or(false),
             ))),
             3 => Ok(MapperEnum::Mapper3(Mapper3::new(
                 prg_rom,
                 chr_rom,
                 self.mirroring,
+                self.bus_conflicts.unwrap_or(false),
+            ))),
+            4 => Ok(MapperEnum::Mapper4(Mapper4::new(
+                prg_rom,
+                chr_rom,
+                self.submapper,
+                self.ignore_mirroring,
             ))),
-            4 => Ok(MapperEnum::Mapper4(Mapper4::new(prg_rom, chr_rom))),
             5 => Ok(MapperEnum::Mapper5(Mapper5::new(prg_rom, chr_rom))),
-            7 => Ok(MapperEnum::Mapper7(Mapper7::new(prg_rom, chr_rom))),
+            7 => Ok(MapperEnum::Mapper7(Mapper7::new(
+                prg_rom,
+                chr_rom,
+                self.bus_conflicts.unwrap_or(false),
+            ))),
             9 => Ok(MapperEnum::Mapper9(Mapper9::new(
                 prg_rom,
                 chr_rom,
@@ -115,14 +136,129 @@ impl NesFile {
                 chr_rom,
                 self.mirroring,
             ))),
+            11 => Ok(MapperEnum::Mapper11(Mapper11::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+            ))),
+            13 => Ok(MapperEnum::Mapper13(Mapper13::new(prg_rom, self.mirroring))),
+            16 => Ok(MapperEnum::Mapper16(Mapper16::new(
+                prg_rom,
+                chr_rom,
+                self.submapper,
+            ))),
+            30 => Ok(MapperEnum::Mapper30(Mapper30::new(
+                prg_rom,
+                self.get_mapper30_mirroring(),
+                self.has_battery,
+                self.image_header.clone(),
+                self.image_footer.clone(),
+            ))),
+            34 => Ok(MapperEnum::Mapper34(Mapper34::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+                self.submapper,
+            ))),
+            38 => Ok(MapperEnum::Mapper38(Mapper38::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+            ))),
             66 => Ok(MapperEnum::Mapper66(Mapper66::new(
                 prg_rom,
                 chr_rom,
                 self.mirroring,
+                self.bus_conflicts.unwrap_or(true),
+            ))),
+            71 => Ok(MapperEnum::Mapper71(Mapper71::new(
+                prg_rom,
+                self.mirroring,
+                self.submapper,
+            ))),
+            79 => Ok(MapperEnum::Mapper79(Mapper79::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+            ))),
+            99 => Ok(MapperEnum::Mapper99(Mapper99::new(
+                prg_rom,
+                chr_rom,
+                self.get_mirroring(),
+            ))),
+            118 => Ok(MapperEnum::Mapper118(Mapper118::new(prg_rom, chr_rom))),
+            119 => Ok(MapperEnum::Mapper119(Mapper119::new(prg_rom, chr_rom))),
+            140 => Ok(MapperEnum::Mapper140(Mapper140::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+            ))),
+            153 => Ok(MapperEnum::Mapper153(Mapper153::new(prg_rom, chr_rom))),
+            157 => Ok(MapperEnum::Mapper157(Mapper157::new(prg_rom, chr_rom))),
+            159 => Ok(MapperEnum::Mapper159(Mapper159::new(prg_rom, chr_rom))),
+            180 => Ok(MapperEnum::Mapper180(Mapper180::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+            ))),
+            200 => Ok(MapperEnum::Mapper200(Mapper200::new(prg_rom, chr_rom))),
+            201 => Ok(MapperEnum::Mapper201(Mapper201::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+            ))),
+            203 => Ok(MapperEnum::Mapper203(Mapper203::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+            ))),
+            206 => Ok(MapperEnum::Mapper206(Mapper206::new(
+                prg_rom,
+                chr_rom,
+                self.get_mirroring(),
             ))),
-            71 => Ok(MapperEnum::Mapper71(Mapper71::new(prg_rom, self.mirroring))),
             227 => Ok(MapperEnum::Mapper227(Mapper227::new(prg_rom, chr_rom))),
-            _ => Err(NesUnsupportedMapper(self.mapper_number as u8)),
+            228 => Ok(MapperEnum::Mapper228(Mapper228::new(prg_rom, chr_rom))),
+            232 => Ok(MapperEnum::Mapper232(Mapper232::new(
+                prg_rom,
+                chr_rom,
+                self.mirroring,
+                self.submapper,
+            ))),
+            _ => Err(NesUnsupportedMapper(self.mapper_number as u16)),
+        }?;
+        if let Some(trainer) = self.trainer.as_ref() {
+            mapper.load_trainer(trainer);
+        }
+        Ok(mapper)
+    }
+
+    pub fn get_vs_hardware_type(&self) -> Option<VsHardwareType> {
+        self.vs_hardware_type
+    }
+
+    pub fn is_playchoice10(&self) -> bool {
+        self.play_choice_rom.is_some()
+    }
+
+    pub fn get_ppu_model(&self) -> PpuModel {
+        self.ppu_model
+    }
+
+    // Only boards that carry the extra nametable RAM are given the four-screen mirroring.
+    fn get_mirroring(&self) -> common::Mirroring {
+        if self.ignore_mirroring {
+            common::Mirroring::FOUR_SCREEN
+        } else {
+            self.mirroring
+        }
+    }
+
+    fn get_mapper30_mirroring(&self) -> Mapper30Mirroring {
+        match (self.ignore_mirroring, self.mirroring) {
+            (false, mirroring) => Mapper30Mirroring::Fixed(mirroring),
+            (true, common::Mirroring::HORIZONTAL) => Mapper30Mirroring::SingleScreen,
+            (true, _) => Mapper30Mirroring::FourScreen,
         }
     }
 
@@ -153,7 +289,6 @@ impl NesFile {
 
     pub fn new(in_bytes: &[u8]) -> Result<NesFile, Error> {
         let format = Self::get_format(in_bytes)?;
-        assert!(format == NesFormat::INes);
 
         let mut read_index = 4;
 
@@ -176,7 +311,7 @@ impl NesFile {
         };
         read_index = 16;
         let mut trainer = Option::None;
-        if header.flag_6 & (HeaderFlag6::TrainerPresent as u8) == 1 {
+        if header.flag_6 & (HeaderFlag6::TrainerPresent as u8) != 0 {
             let mut trainer_data: Trainer = [0; 512];
             let trainer_slice = &in_bytes[read_index..];
             if trainer_slice.len() < 512 {
@@ -186,6 +321,7 @@ impl NesFile {
             trainer = Option::Some(trainer_data);
         }
 
+        let prg_rom_start = read_index;
         let mut prg_rom = Vec::<PrgRomUnit>::new();
         for unit in 0..header.prg_rom_units {
             let mut prg_rom_unit: PrgRomUnit = [0; 16384];
@@ -197,6 +333,8 @@ impl NesFile {
             prg_rom.push(prg_rom_unit);
         }
 
+        let prg_rom_end = read_index;
+
         let mut chr_rom = Vec::<ChrRomUnit>::new();
 
         for unit in 0..header.chr_rom_units {
@@ -211,7 +349,7 @@ impl NesFile {
 
         let mut play_choice_rom = Option::None;
 
-        if header.flag_7 & (HeaderFlag7::PlayChoice10 as u8) == 1 {
+        if header.flag_7 & 0b11 == HeaderFlag7::PlayChoice10 as u8 {
             if in_bytes[read_index..].len() < 8224 {
                 return Err(NesPlayChoiceRomTooShort(in_bytes[read_index..].len()));
             }
@@ -240,27 +378,81 @@ impl NesFile {
             }
         }
 
-        let ho_n_mapper_number = if in_bytes[12] as u32
-            + in_bytes[13] as u32
-            + in_bytes[14] as u32
-            + in_bytes[15] as u32
-            != 0
-        {
-            0
+        let (mapper_number, submapper) = if format == NesFormat::Nes2_0 {
+            let mapper_number = ((in_bytes[8] as u32 & 0x0F) << 8)
+                + ((header.ho_n_mapper_number as u32) << 4)
+                + header.lo_n_mapper_number as u32;
+            (mapper_number, (in_bytes[8] & 0xF0) >> 4)
+        } else {
+            let ho_n_mapper_number = if in_bytes[12] as u32
+                + in_bytes[13] as u32
+                + in_bytes[14] as u32
+                + in_bytes[15] as u32
+                != 0
+            {
+                0
+            } else {
+                header.ho_n_mapper_number as u32
+            };
+            (
+                (ho_n_mapper_number << 4) + header.lo_n_mapper_number as u32,
+                0,
+            )
+        };
+
+        let bus_conflicts = if format == NesFormat::Nes2_0 {
+            // Only the discrete UxROM, CNROM and AxROM boards define these submappers.
+            match (mapper_number, submapper) {
+                (2 | 3 | 7, 1) => Some(false),
+                (2 | 3 | 7, 2) => Some(true),
+                _ => None,
+            }
+        } else if header.flag_10 & HeaderFlag10::BusConflictPresent as u8 != 0 {
+            Some(true)
         } else {
-            header.ho_n_mapper_number as u32
+            None
         };
 
-        let mapper_number = (ho_n_mapper_number << 4) + header.lo_n_mapper_number as u32;
+        let is_vs_system = header.flag_7 & 0b11 == HeaderFlag7::VsSystem as u8;
+        let vs_hardware_type = match format {
+            _ if !is_vs_system => None,
+            NesFormat::Nes2_0 => Some(VsHardwareType::from_header(in_bytes[13] >> 4)),
+            NesFormat::INes => Some(VsHardwareType::UniSystem),
+        };
+        let ppu_model = if play_choice_rom.is_some() {
+            PpuModel::Rp2C03
+        } else if !is_vs_system {
+            PpuModel::Rp2C02
+        } else if format == NesFormat::Nes2_0 {
+            let vs_ppu_type = in_bytes[13] & 0x0F;
+            match vs_ppu_type {
+                0x2..=0x5 => PpuModel::Rp2C04(vs_ppu_type as usize - 2),
+                0x8 | 0xB => PpuModel::Rc2C05(0x1B),
+                0x9 => PpuModel::Rc2C05(0x3D),
+                0xA => PpuModel::Rc2C05(0x1C),
+                0xC => PpuModel::Rc2C05(0x00),
+                _ => PpuModel::Rp2C03,
+            }
+        } else {
+            PpuModel::Rp2C03
+        };
 
         Ok(NesFile {
-            _trainer: trainer,
+            trainer,
             prg_rom,
             chr_rom,
-            _play_choice_rom: play_choice_rom,
+            play_choice_rom,
             _prg_ram_size: prg_ram_size,
             mapper_number,
+            submapper,
+            bus_conflicts,
             mirroring,
+            ignore_mirroring: header.flag_6 & HeaderFlag6::IgnoreMirroring as u8 != 0,
+            has_battery: header.flag_6 & HeaderFlag6::Battery as u8 != 0,
+            image_header: in_bytes[..prg_rom_start].to_vec(),
+            image_footer: in_bytes[prg_rom_end..].to_vec(),
+            vs_hardware_type,
+            ppu_model,
         })
     }
 }
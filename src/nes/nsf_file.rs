@@ -0,0 +1,247 @@
+use super::errors::Error;
+use super::mappers::*;
+use Error::*;
+use serde::{Deserialize, Serialize};
+
+pub(crate) const NSF_CHIP_FDS: u8 = 0b0000_0100;
+pub(crate) const NSF_CHIP_MMC5: u8 = 0b0000_1000;
+pub(crate) const NSF_BANK_SIZE: usize = 0x1000;
+pub(crate) const NSF_BANK_SLOT_COUNT: usize = 10;
+const NSF_HEADER_SIZE: usize = 0x80;
+const NSF_MAGIC: &[u8] = b"NESM\x1A";
+const NSFE_MAGIC: &[u8] = b"NSFE";
+const NSF_MAX_DATA_SIZE: usize = 0x80000;
+const NSF_DEFAULT_NTSC_SPEED: u16 = 16639;
+const NSF_DEFAULT_PAL_SPEED: u16 = 19997;
+const NTSC_CPU_CLOCK_HZ: u64 = 1_789_773;
+const FDS_RAM_START: u16 = 0x6000;
+const PRG_ROM_START: u16 = 0x8000;
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct NsfInfo {
+    pub title: String,
+    pub artist: String,
+    pub copyright: String,
+    pub track_count: usize,
+    pub current_track: usize,
+    pub track_labels: Vec<String>,
+}
+
+pub struct NsfFile {
+    pub(crate) info: NsfInfo,
+    pub(crate) data: Vec<u8>,
+    pub(crate) init_address: u16,
+    pub(crate) play_address: u16,
+    pub(crate) play_period: u32,
+    pub(crate) is_pal_only: bool,
+    pub(crate) expansion_chips: u8,
+    pub(crate) initial_banks: [u8; NSF_BANK_SLOT_COUNT],
+}
+
+struct NsfHeader {
+    load_address: u16,
+    init_address: u16,
+    play_address: u16,
+    ntsc_speed: u16,
+    pal_speed: u16,
+    region: u8,
+    expansion_chips: u8,
+    banks: Option<[u8; 8]>,
+}
+
+fn read_u16(data: &[u8], index: usize) -> u16 {
+    u16::from_le_bytes([data[index], data[index + 1]])
+}
+
+fn read_strings(data: &[u8]) -> Vec<String> {
+    let data = data.strip_suffix(&[0]).unwrap_or(data);
+    data.split(|b| *b == 0)
+        .map(|s| String::from_utf8_lossy(s).trim().to_string())
+        .collect()
+}
+
+fn read_string(data: &[u8]) -> String {
+    read_strings(data).into_iter().next().unwrap_or_default()
+}
+
+fn parse_nsf(nsf: &[u8]) -> Result<(NsfHeader, NsfInfo, &[u8]), Error> {
+    if nsf.len() < NSF_HEADER_SIZE {
+        return Err(NsfHeaderTooShort(nsf.len()));
+    }
+    let banks: [u8; 8] = nsf[0x70..0x78].try_into().unwrap();
+    let header = NsfHeader {
+        load_address: read_u16(nsf, 0x08),
+        init_address: read_u16(nsf, 0x0A),
+        play_address: read_u16(nsf, 0x0C),
+        ntsc_speed: read_u16(nsf, 0x6E),
+        pal_speed: read_u16(nsf, 0x78),
+        region: nsf[0x7A],
+        expansion_chips: nsf[0x7B],
+        banks: banks.iter().any(|b| *b != 0).then_some(banks),
+    };
+    let track_count = nsf[0x06] as usize;
+    let info = NsfInfo {
+        title: read_string(&nsf[0x0E..0x2E]),
+        artist: read_string(&nsf[0x2E..0x4E]),
+        copyright: read_string(&nsf[0x4E..0x6E]),
+        track_count,
+        current_track: (nsf[0x07] as usize).saturating_sub(1),
+        track_labels: Vec::new(),
+    };
+    let data_length = nsf[0x7D] as usize | (nsf[0x7E] as usize) << 8 | (nsf[0x7F] as usize) << 16;
+    let data = &nsf[NSF_HEADER_SIZE..];
+    let data = if nsf[0x05] >= 2 && data_length != 0 {
+        &data[..data_length.min(data.len())]
+    } else {
+        data
+    };
+    Ok((header, info, data))
+}
+
+fn parse_nsfe(nsfe: &[u8]) -> Result<(NsfHeader, NsfInfo, &[u8]), Error> {
+    let mut header = None;
+    let mut info = NsfInfo::default();
+    let mut data = None;
+    let mut rates = None;
+    let mut index = NSFE_MAGIC.len();
+    loop {
+        let chunk_header = nsfe
+            .get(index..index + 8)
+            .ok_or(NsfeMissingChunk("NEND".to_string()))?;
+        let size = u32::from_le_bytes(chunk_header[0..4].try_into().unwrap()) as usize;
+        let id = String::from_utf8_lossy(&chunk_header[4..8]).to_string();
+        let chunk = nsfe
+            .get(index + 8..index + 8 + size)
+            .ok_or(NsfeChunkCorrupted(id.clone()))?;
+        index += 8 + size;
+        match id.as_str() {
+            "INFO" => {
+                if chunk.len() < 9 {
+                    return Err(NsfeChunkCorrupted(id));
+                }
+                info.track_count = chunk.get(8).copied().unwrap_or(1) as usize;
+                info.current_track = chunk.get(9).copied().unwrap_or(0) as usize;
+                header = Some(NsfHeader {
+                    load_address: read_u16(chunk, 0),
+                    init_address: read_u16(chunk, 2),
+                    play_address: read_u16(chunk, 4),
+                    ntsc_speed: 0,
+                    pal_speed: 0,
+                    region: chunk[6],
+                    expansion_chips: chunk[7],
+                    banks: None,
+                });
+            }
+            "DATA" => data = Some(chunk),
+            "BANK" => {
+                let mut banks = [0; 8];
+                let size = chunk.len().min(8);
+                banks[..size].copy_from_slice(&chunk[..size]);
+                if let Some(header) = header.as_mut() {
+                    header.banks = Some(banks);
+                } else {
+                    return Err(NsfeMissingChunk("INFO".to_string()));
+                }
+            }
+            "RATE" => {
+                if chunk.len() < 2 {
+                    return Err(NsfeChunkCorrupted(id));
+                }
+                let pal_speed = if chunk.len() >= 4 {
+                    read_u16(chunk, 2)
+                } else {
+                    0
+                };
+                rates = Some((read_u16(chunk, 0), pal_speed));
+            }
+            "auth" => {
+                let mut strings = read_strings(chunk).into_iter();
+                info.title = strings.next().unwrap_or_default();
+                info.artist = strings.next().unwrap_or_default();
+                info.copyright = strings.next().unwrap_or_default();
+            }
+            "tlbl" => info.track_labels = read_strings(chunk),
+            "NEND" => break,
+            _ => (),
+        }
+    }
+    let mut header = header.ok_or(NsfeMissingChunk("INFO".to_string()))?;
+    let data = data.ok_or(NsfeMissingChunk("DATA".to_string()))?;
+    if let Some((ntsc_speed, pal_speed)) = rates {
+        header.ntsc_speed = ntsc_speed;
+        header.pal_speed = pal_speed;
+    }
+    Ok((header, info, data))
+}
+
+impl NsfFile {
+    pub fn create_mapper(self) -> MapperEnum {
+        MapperEnum::MapperNsf(MapperNsf::new(self))
+    }
+
+    pub fn new(nsf: &[u8]) -> Result<NsfFile, Error> {
+        let (header, info, data) = if nsf.starts_with(NSF_MAGIC) {
+            parse_nsf(nsf)?
+        } else if nsf.starts_with(NSFE_MAGIC) {
+            parse_nsfe(nsf)?
+        } else {
+            return Err(UnknownNsfFormat);
+        };
+
+        if info.track_count == 0 {
+            return Err(UnknownNsfFormat);
+        }
+
+        let is_fds = header.expansion_chips & NSF_CHIP_FDS != 0;
+        let base_address = if is_fds { FDS_RAM_START } else { PRG_ROM_START };
+        let first_slot = (base_address - FDS_RAM_START) as usize / NSF_BANK_SIZE;
+        let mut initial_banks = [0; NSF_BANK_SLOT_COUNT];
+        let padding = if let Some(banks) = header.banks {
+            initial_banks[0] = banks[6];
+            initial_banks[1] = banks[7];
+            initial_banks[2..].copy_from_slice(&banks);
+            header.load_address as usize % NSF_BANK_SIZE
+        } else {
+            if header.load_address < base_address {
+                return Err(NsfInvalidLoadAddress(header.load_address));
+            }
+            for (bank, slot) in (first_slot..NSF_BANK_SLOT_COUNT).enumerate() {
+                initial_banks[slot] = bank as u8;
+            }
+            (header.load_address - base_address) as usize
+        };
+
+        let padded_size = (padding + data.len()).next_multiple_of(NSF_BANK_SIZE);
+        if padded_size > NSF_MAX_DATA_SIZE {
+            return Err(NsfDataTooLarge(padded_size));
+        }
+        let mut padded_data = vec![0; padded_size];
+        padded_data[padding..padding + data.len()].copy_from_slice(data);
+
+        // The console always runs at the NTSC clock. PAL-only tunes are approximated by calling
+        // PLAY at their PAL rate and telling INIT they run on PAL, which keeps the tempo right but
+        // leaves the pitch of period tables tuned for the slower PAL APU a semitone sharp.
+        let is_pal_only = header.region & 0b11 == 1;
+        let speed = match (is_pal_only, header.ntsc_speed, header.pal_speed) {
+            (false, 0, _) => NSF_DEFAULT_NTSC_SPEED,
+            (false, speed, _) => speed,
+            (true, _, 0) => NSF_DEFAULT_PAL_SPEED,
+            (true, _, speed) => speed,
+        };
+        let play_period = (speed as u64 * NTSC_CPU_CLOCK_HZ / 1_000_000) as u32;
+
+        let mut info = info;
+        info.current_track = info.current_track.min(info.track_count - 1);
+
+        Ok(NsfFile {
+            info,
+            data: padded_data,
+            init_address: header.init_address,
+            play_address: header.play_address,
+            play_period,
+            is_pal_only,
+            expansion_chips: header.expansion_chips,
+            initial_banks,
+        })
+    }
+}
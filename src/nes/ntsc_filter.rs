@@ -0,0 +1,175 @@
+use super::colors::{NTSC_SAMPLES_PER_CYCLE, NtscColorMapper, NtscPaletteSettings};
+use super::{EmulationFrame, PIXEL_SIZE, VIDEO_FRAME_HEIGHT, VIDEO_FRAME_WIDTH};
+
+pub const NTSC_VIDEO_FRAME_WIDTH: usize =
+    ((VIDEO_FRAME_WIDTH - 1) / INPUT_PIXELS_PER_CHUNK + 1) * OUTPUT_PIXELS_PER_CHUNK;
+pub const NTSC_VIDEO_FRAME_SIZE: usize = NTSC_VIDEO_FRAME_WIDTH * VIDEO_FRAME_HEIGHT * PIXEL_SIZE;
+const PALETTE_INDEX_COUNT: usize = 512;
+
+const SAMPLES_PER_PIXEL: usize = 8;
+const INPUT_PIXELS_PER_CHUNK: usize = 3;
+const OUTPUT_PIXELS_PER_CHUNK: usize = 7;
+const SAMPLES_PER_SCANLINE: usize =
+    NTSC_VIDEO_FRAME_WIDTH / OUTPUT_PIXELS_PER_CHUNK * INPUT_PIXELS_PER_CHUNK * SAMPLES_PER_PIXEL;
+const PHASE_STEP: usize = 4;
+const BURST_PHASE_COUNT: usize = 3;
+const LUMA_WINDOW: usize = NTSC_SAMPLES_PER_CYCLE;
+const CHROMA_WINDOW: usize = 2 * NTSC_SAMPLES_PER_CYCLE;
+const BORDER_COLOR: u16 = 0x0F;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct NtscFilterSettings {
+    pub palette: NtscPaletteSettings,
+    pub merge_fields: bool,
+}
+
+impl Default for NtscFilterSettings {
+    fn default() -> Self {
+        Self {
+            palette: Default::default(),
+            merge_fields: true,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct NtscVideoFrame {
+    pixels: Box<[u8; NTSC_VIDEO_FRAME_SIZE]>,
+}
+
+impl NtscVideoFrame {
+    pub(crate) fn new() -> Self {
+        Self {
+            pixels: Box::new([0; NTSC_VIDEO_FRAME_SIZE]),
+        }
+    }
+
+    pub fn get_pixels(&self) -> &[u8; NTSC_VIDEO_FRAME_SIZE] {
+        &self.pixels
+    }
+
+    pub fn get_pixel(&self, x: u16, y: u8) -> (u8, u8, u8) {
+        let index = (y as usize * NTSC_VIDEO_FRAME_WIDTH + x as usize) * PIXEL_SIZE;
+        (
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+        )
+    }
+
+    pub(crate) fn set_pixel(&mut self, x: usize, y: usize, color: (u8, u8, u8)) {
+        let index = (y * NTSC_VIDEO_FRAME_WIDTH + x) * PIXEL_SIZE;
+        self.pixels[index] = color.0;
+        self.pixels[index + 1] = color.1;
+        self.pixels[index + 2] = color.2;
+    }
+}
+
+pub(crate) struct NtscFilter {
+    settings: NtscFilterSettings,
+    signal_levels: Vec<[f32; NTSC_SAMPLES_PER_CYCLE]>,
+    subcarrier: [(f32, f32); NTSC_SAMPLES_PER_CYCLE],
+    burst_phase: usize,
+    luma_sums: Vec<f32>,
+    u_sums: Vec<f32>,
+    v_sums: Vec<f32>,
+}
+
+impl NtscFilter {
+    pub fn new(settings: NtscFilterSettings) -> Self {
+        let signal_levels = (0..PALETTE_INDEX_COUNT)
+            .map(|index| {
+                std::array::from_fn(|phase| {
+                    NtscColorMapper::get_signal((index & 0x3F) as u8, (index >> 6) as u8, phase)
+                })
+            })
+            .collect();
+        let subcarrier = std::array::from_fn(|phase| {
+            let angle = NtscColorMapper::get_subcarrier_angle(phase, &settings.palette);
+            (angle.cos(), angle.sin())
+        });
+        let padded_size = SAMPLES_PER_SCANLINE + CHROMA_WINDOW + 1;
+        Self {
+            settings,
+            signal_levels,
+            subcarrier,
+            burst_phase: 0,
+            luma_sums: vec![0.0; padded_size],
+            u_sums: vec![0.0; padded_size],
+            v_sums: vec![0.0; padded_size],
+        }
+    }
+
+    pub fn get_settings(&self) -> NtscFilterSettings {
+        self.settings
+    }
+
+    pub fn apply(&mut self, emulation_frame: &mut EmulationFrame) {
+        let (Some(indices), Some(ntsc_video)) = (
+            emulation_frame.indices.as_ref(),
+            emulation_frame.ntsc_video.as_mut(),
+        ) else {
+            return;
+        };
+        let burst_phase = self.burst_phase;
+        self.burst_phase = (self.burst_phase + 1) % BURST_PHASE_COUNT;
+        for (y, scanline) in indices.get_indices().chunks(VIDEO_FRAME_WIDTH).enumerate() {
+            let phase = (burst_phase + y) * PHASE_STEP % NTSC_SAMPLES_PER_CYCLE;
+            let colors = self.decode_scanline(scanline, phase);
+            if self.settings.merge_fields {
+                let next_phase = (phase + PHASE_STEP) % NTSC_SAMPLES_PER_CYCLE;
+                let next_colors = self.decode_scanline(scanline, next_phase);
+                let average = |a: u8, b: u8| ((a as u16 + b as u16) / 2) as u8;
+                for (x, (color, next_color)) in colors.iter().zip(next_colors).enumerate() {
+                    let merged = (
+                        average(color.0, next_color.0),
+                        average(color.1, next_color.1),
+                        average(color.2, next_color.2),
+                    );
+                    ntsc_video.set_pixel(x, y, merged);
+                }
+            } else {
+                for (x, color) in colors.into_iter().enumerate() {
+                    ntsc_video.set_pixel(x, y, color);
+                }
+            }
+        }
+    }
+
+    fn get_sample(&self, scanline: &[u16], position: usize, phase: usize) -> f32 {
+        let pixel = position
+            .checked_sub(CHROMA_WINDOW / 2)
+            .map(|position| position / SAMPLES_PER_PIXEL)
+            .and_then(|x| scanline.get(x))
+            .copied()
+            .unwrap_or(BORDER_COLOR);
+        self.signal_levels[pixel as usize % PALETTE_INDEX_COUNT][phase]
+    }
+
+    fn decode_scanline(&mut self, scanline: &[u16], start_phase: usize) -> Vec<(u8, u8, u8)> {
+        for position in 0..self.luma_sums.len() - 1 {
+            let phase = (start_phase + position) % NTSC_SAMPLES_PER_CYCLE;
+            let signal = self.get_sample(scanline, position, phase);
+            let (cos, sin) = self.subcarrier[phase];
+            self.luma_sums[position + 1] = self.luma_sums[position] + signal;
+            self.u_sums[position + 1] = self.u_sums[position] + signal * cos;
+            self.v_sums[position + 1] = self.v_sums[position] + signal * sin;
+        }
+        let window_sum = |sums: &[f32], center: usize, window: usize| {
+            sums[center + window / 2] - sums[center - window / 2]
+        };
+        (0..NTSC_VIDEO_FRAME_WIDTH)
+            .map(|x| {
+                let center = CHROMA_WINDOW / 2
+                    + ((2 * x + 1) * INPUT_PIXELS_PER_CHUNK * SAMPLES_PER_PIXEL)
+                        / (2 * OUTPUT_PIXELS_PER_CHUNK);
+                let y = window_sum(&self.luma_sums, center, LUMA_WINDOW) / LUMA_WINDOW as f32;
+                let u =
+                    2.0 * window_sum(&self.u_sums, center, CHROMA_WINDOW) / CHROMA_WINDOW as f32;
+                let v =
+                    2.0 * window_sum(&self.v_sums, center, CHROMA_WINDOW) / CHROMA_WINDOW as f32;
+                NtscColorMapper::decode_yuv(y, u, v, &self.settings.palette)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+const FRAMES_PER_SECOND: u32 = 60;
+const SECONDS_PER_CREDIT: u32 = 300;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct PlayChoice10 {
+    frames_remaining: u32,
+}
+
+impl PlayChoice10 {
+    pub fn new() -> Self {
+        Self {
+            frames_remaining: 0,
+        }
+    }
+
+    pub fn insert_credit(&mut self) {
+        self.frames_remaining = self
+            .frames_remaining
+            .saturating_add(SECONDS_PER_CREDIT * FRAMES_PER_SECOND);
+    }
+
+    pub fn get_seconds_remaining(&self) -> u32 {
+        self.frames_remaining.div_ceil(FRAMES_PER_SECOND)
+    }
+
+    pub fn is_time_expired(&self) -> bool {
+        self.frames_remaining == 0
+    }
+
+    pub fn notify_frame(&mut self) {
+        self.frames_remaining = self.frames_remaining.saturating_sub(1);
+    }
+}
@@ -1,5 +1,5 @@
 use super::PpuBus;
-use super::colors::{ColorMapper, DefaultColorMapper, RgbColor};
+use super::colors::{ColorMapper, DefaultColorMapper, RgbColor, RgbColorMapper};
 use super::memory::VideoMemory;
 use super::vram::VRam;
 use super::{mappers::Mapper, mappers::MapperEnum, ram_ppu::*};
@@ -47,7 +47,7 @@ const FETCH_HIGH_PATTERN_DATA_CYCLE_OFFSET: u16 = FETCH_LOW_PATTERN_DATA_CYCLE_O
 
 const VBLANK_START_CYCLE: u16 = 4;
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 struct ControlRegister {
     value: u8,
 }
@@ -99,7 +99,7 @@ enum MaskRegisterFlag {
     EmphasizeBlue = 0b10000000,
 }
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 struct MaskRegister {
     value: u8,
 }
@@ -135,7 +135,7 @@ impl StatusRegister {
     }
 }
 
-type Palette = [RgbColor; 4];
+type Palette = [u8; 4];
 type Palettes = [Palette; 4];
 
 #[derive(Copy, Clone, Default, Serialize, Deserialize)]
@@ -184,7 +184,7 @@ impl Sprite {
     }
 }
 
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 struct PrimaryOam {
     #[serde(with = "serde_arrays")]
     data: [u8; 256],
@@ -202,7 +202,7 @@ impl Default for PrimaryOam {
     }
 }
 
-#[derive(Default, Serialize, Deserialize)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 struct SecondaryOam {
     sprites: [Sprite; 8],
     sprite_count: usize,
@@ -298,10 +298,26 @@ impl Display for VRAMAddress {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub(crate) enum PpuModel {
+    #[default]
+    Rp2C02,
+    Rp2C03,
+    Rp2C04(usize),
+    Rc2C05(u8),
+}
+
+const RP2C04_COLOR_MAPPERS: [RgbColorMapper; 4] = [
+    RgbColorMapper::rp2c04(0),
+    RgbColorMapper::rp2c04(1),
+    RgbColorMapper::rp2c04(2),
+    RgbColorMapper::rp2c04(3),
+];
+
 fn default_color_mapper() -> Box<dyn ColorMapper> {
     Box::new(DefaultColorMapper::new())
 }
-#[derive(Serialize, Deserialize)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Ppu {
     vram: VRam,
     control_reg: ControlRegister,
@@ -315,6 +331,7 @@ pub struct Ppu {
     frame: u128,
     #[serde(skip, default = "default_color_mapper")]
     color_mapper: Box<dyn ColorMapper>,
+    model: PpuModel,
     write_toggle: bool,
     nmi_pending: bool,
     vbl_flag_supressed: bool,
@@ -326,6 +343,10 @@ pub struct Ppu {
     tile_data: [TileData; 3],
     render_sprites: [Sprite; 8],
     render_sprite_count: usize,
+    #[serde(skip, default)]
+    extra_sprites: Vec<Sprite>,
+    #[serde(skip, default)]
+    is_sprite_limit_disabled: bool,
 }
 
 impl Default for Ppu {
@@ -342,6 +363,7 @@ impl Default for Ppu {
             scanline: 0,
             frame: 1,
             color_mapper: default_color_mapper(),
+            model: PpuModel::Rp2C02,
             vram_address: Default::default(),
             t_vram_address: Default::default(),
             fine_x_scroll: 0,
@@ -353,6 +375,8 @@ impl Default for Ppu {
             tile_data: [Default::default(); 3],
             render_sprites: [Default::default(); 8],
             render_sprite_count: 0,
+            extra_sprites: Vec::new(),
+            is_sprite_limit_disabled: false,
         }
     }
 }
@@ -371,6 +395,7 @@ impl Ppu {
             scanline: 0,
             frame: 1,
             color_mapper: Box::new(DefaultColorMapper::new()),
+            model: PpuModel::Rp2C02,
             vram_address: Default::default(),
             t_vram_address: Default::default(),
             fine_x_scroll: 0,
@@ -382,6 +407,8 @@ impl Ppu {
             tile_data: [Default::default(); 3],
             render_sprites: [Default::default(); 8],
             render_sprite_count: 0,
+            extra_sprites: Vec::new(),
+            is_sprite_limit_disabled: false,
         }
     }
 
@@ -404,6 +431,39 @@ impl Ppu {
         self.vbl_flag_supressed = false;
         self.render_sprites = [Default::default(); 8];
         self.render_sprite_count = 0;
+        self.extra_sprites.clear();
+    }
+
+    pub fn set_color_mapper(&mut self, color_mapper: Box<dyn ColorMapper>) {
+        self.color_mapper = color_mapper;
+    }
+
+    pub fn take_color_mapper(&mut self) -> Box<dyn ColorMapper> {
+        std::mem::replace(&mut self.color_mapper, default_color_mapper())
+    }
+
+    pub fn set_sprite_limit_enabled(&mut self, enabled: bool) {
+        self.is_sprite_limit_disabled = !enabled;
+    }
+
+    pub fn is_sprite_limit_enabled(&self) -> bool {
+        !self.is_sprite_limit_disabled
+    }
+
+    pub fn set_model(&mut self, model: PpuModel) {
+        self.model = model;
+    }
+
+    pub fn has_status_id(&self) -> bool {
+        matches!(self.model, PpuModel::Rc2C05(_))
+    }
+
+    fn get_color_mapper(&self) -> &dyn ColorMapper {
+        match self.model {
+            PpuModel::Rp2C02 => self.color_mapper.as_ref(),
+            PpuModel::Rp2C03 | PpuModel::Rc2C05(_) => &RgbColorMapper::RP2C03,
+            PpuModel::Rp2C04(palette) => &RP2C04_COLOR_MAPPERS[palette],
+        }
     }
 
     fn fetch_garbage_nametable_byte(&mut self, bus: &mut PpuBus) {
@@ -464,15 +524,9 @@ impl Ppu {
         }
     }
 
-    fn fetch_next_sprite_tile_data(&mut self, bus: &mut PpuBus) {
-        let nametable_index = self.vram_address.get(NM_TABLE) as u8;
-        let tile_x = self.vram_address.get(COARSE_X) as u8;
-        let tile_y = self.vram_address.get(COARSE_Y) as u8;
+    fn get_sprite_pattern_location(&self, sprite: &Sprite) -> (u8, u8, u8) {
         let is_8x16_mode = self.control_reg.get_sprite_size_height() == 16;
         let sprite_height = self.control_reg.get_sprite_size_height();
-        let sprite_slot = ((self.ppu_cycle - 257) / 8) as usize;
-
-        let sprite = self.secondary_oam.sprites[sprite_slot];
         let pattern_table_index = if is_8x16_mode {
             sprite.get_pattern_table_index_for_8x16_mode()
         } else {
@@ -497,6 +551,16 @@ impl Ppu {
                 tile_index += 1;
             }
         }
+        (pattern_table_index, tile_index, fine_y)
+    }
+
+    fn fetch_next_sprite_tile_data(&mut self, bus: &mut PpuBus) {
+        let nametable_index = self.vram_address.get(NM_TABLE) as u8;
+        let tile_x = self.vram_address.get(COARSE_X) as u8;
+        let tile_y = self.vram_address.get(COARSE_Y) as u8;
+        let sprite_slot = ((self.ppu_cycle - 257) / 8) as usize;
+        let (pattern_table_index, tile_index, fine_y) =
+            self.get_sprite_pattern_location(&self.secondary_oam.sprites[sprite_slot]);
 
         match self.ppu_cycle % 8 {
             FETCH_NAMETABLE_DATA_CYCLE_OFFSET => {
@@ -523,6 +587,22 @@ impl Ppu {
         }
     }
 
+    fn get_palette_index(&self, color: u8) -> u16 {
+        let color = if self.mask_reg.is_flag_enabled(MaskRegisterFlag::GrayScale) {
+            color & 0x30
+        } else {
+            color
+        };
+        color as u16 | ((self.mask_reg.value >> 5) as u16) << 6
+    }
+
+    fn map_color(&self, color: u8, palette_index: u16) -> RgbColor {
+        let color_mapper = self.get_color_mapper();
+        color_mapper
+            .map_nes_color_with_emphasis((palette_index & 0x3F) as u8, (palette_index >> 6) as u8)
+            .unwrap_or_else(|| self.apply_emphasis_and_grayscale(color_mapper.map_nes_color(color)))
+    }
+
     fn apply_emphasis_and_grayscale(&self, color: (u8, u8, u8)) -> (u8, u8, u8) {
         let mut final_color = color;
 
@@ -569,7 +649,11 @@ impl Ppu {
                 &self.background_palletes[bg_palette_index as usize],
                 &self.sprite_palettes,
             );
-        let color = self.apply_emphasis_and_grayscale(color);
+        let palette_index = self.get_palette_index(color);
+        if let Some(indices) = bus.emulation_frame.indices.as_mut() {
+            indices.set_index(x as u8, self.scanline as u8, palette_index);
+        }
+        let color = self.map_color(color, palette_index);
         bus.emulation_frame
             .video
             .set_pixel(x as u8, self.scanline as u8, color);
@@ -629,6 +713,9 @@ impl Ppu {
                 257..=320 => {
                     if self.is_rendering_enabled() {
                         self.fetch_next_sprite_tile_data(bus);
+                        if self.ppu_cycle == 320 {
+                            self.fetch_extra_sprites(bus);
+                        }
                         if (280..=304).contains(&self.ppu_cycle) {
                             self.vram_address
                                 .set(FINE_Y, self.t_vram_address.get(FINE_Y));
@@ -738,6 +825,9 @@ impl Ppu {
                 257..=320 => {
                     if self.is_rendering_enabled() {
                         self.fetch_next_sprite_tile_data(bus);
+                        if self.ppu_cycle == 320 {
+                            self.fetch_extra_sprites(bus);
+                        }
                     }
                 }
 
@@ -796,7 +886,7 @@ impl Ppu {
         sprite: &Sprite,
         bg_pallete: &Palette,
         sprite_palletes: &Palettes,
-    ) -> (RgbColor, bool) {
+    ) -> (u8, bool) {
         let bg_color = bg_pallete[bg_color_index as usize];
         let sprite_color =
             sprite_palletes[sprite.get_palette_index() as usize][sprite_color_index as usize];
@@ -848,8 +938,10 @@ impl Ppu {
                 .is_flag_enabled(MaskRegisterFlag::ShowSpritesdInLeftMost8Pixels)
                 || x >= 8)
         {
-            for i in 0..self.render_sprite_count {
-                let sprite = &self.render_sprites[i];
+            let sprites = self.render_sprites[..self.render_sprite_count]
+                .iter()
+                .chain(self.extra_sprites.iter());
+            for sprite in sprites {
                 if sprite.get_y() == 0 {
                     continue;
                 }
@@ -870,6 +962,43 @@ impl Ppu {
         }
         (0, Default::default())
     }
+    // Sprites past the eighth one are only fetched when the sprite limit is disabled.
+    // Their pattern data is read in one go after the regular sprite fetches.
+    fn fetch_extra_sprites(&mut self, bus: &mut PpuBus) {
+        self.extra_sprites.clear();
+        if !self.is_sprite_limit_disabled || self.secondary_oam.sprite_count < 8 {
+            return;
+        }
+        let sprite_height = self.control_reg.get_sprite_size_height() as i16;
+        let first_sprite_index = self.secondary_oam.sprites[7].oam_index as usize + 1;
+        for sprite_index in first_sprite_index..64 {
+            let sprite_data = &self.primary_oam.data[sprite_index * 4..sprite_index * 4 + 4];
+            let line_in_sprite = self.scanline - sprite_data[0] as i16;
+            if !(0..sprite_height).contains(&line_in_sprite) {
+                continue;
+            }
+            let mut sprite = Sprite {
+                oam_index: sprite_index as u8,
+                data: sprite_data.try_into().unwrap(),
+                low_pattern_byte: 0,
+                high_pattern_byte: 0,
+            };
+            let (pattern_table_index, tile_index, fine_y) =
+                self.get_sprite_pattern_location(&sprite);
+            bus.mapper.notify_sprite_pattern_data_fetch();
+            sprite.low_pattern_byte =
+                self.vram
+                    .get_low_pattern_byte(pattern_table_index, tile_index, fine_y, bus.mapper);
+            sprite.high_pattern_byte = self.vram.get_high_pattern_byte(
+                pattern_table_index,
+                tile_index,
+                fine_y,
+                bus.mapper,
+            );
+            self.extra_sprites.push(sprite);
+        }
+    }
+
     fn get_sprites_for_scanline_and_check_for_overflow(&self) -> bool {
         let sprite_height = self.control_reg.get_sprite_size_height() as i16;
         let count = self
@@ -894,11 +1023,10 @@ impl Ppu {
                 self.vram.get_sprite_palette(i as u8, bus.mapper)
             };
             *p = [
-                self.color_mapper
-                    .map_nes_color(raw_universal_bckg_color & 0x3F),
-                self.color_mapper.map_nes_color(raw_colors[0] & 0x3F),
-                self.color_mapper.map_nes_color(raw_colors[1] & 0x3F),
-                self.color_mapper.map_nes_color(raw_colors[2] & 0x3F),
+                raw_universal_bckg_color & 0x3F,
+                raw_colors[0] & 0x3F,
+                raw_colors[1] & 0x3F,
+                raw_colors[2] & 0x3F,
             ];
         }
         palletes
@@ -943,6 +1071,11 @@ impl Ppu {
 
 impl WritePpuRegisters for Ppu {
     fn write(&mut self, register: WriteAccessRegister, value: u8, mapper: &mut MapperEnum) {
+        let register = match (self.model, register) {
+            (PpuModel::Rc2C05(_), WriteAccessRegister::PpuCtrl) => WriteAccessRegister::PpuMask,
+            (PpuModel::Rc2C05(_), WriteAccessRegister::PpuMask) => WriteAccessRegister::PpuCtrl,
+            (_, register) => register,
+        };
         match register {
             WriteAccessRegister::PpuCtrl => {
                 let new_control_register = ControlRegister { value };
@@ -1039,7 +1172,10 @@ impl ReadPpuRegisters for Ppu {
                     self.nmi_pending = false;
                 }
                 self.write_toggle = false;
-                let current_status = self.status_reg.value;
+                let mut current_status = self.status_reg.value;
+                if let PpuModel::Rc2C05(id) = self.model {
+                    current_status = (current_status & 0xE0) | id;
+                }
                 self.status_reg
                     .set_flag(StatusRegisterFlag::VerticalBlankStarted, false);
                 current_status
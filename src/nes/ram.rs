@@ -5,6 +5,7 @@ use super::ram_apu::ReadAccessRegisters;
 use super::ram_apu::WriteAcessRegisters;
 use super::ram_controllers::*;
 use super::ram_ppu::*;
+use super::vs_system::VsProtection;
 use super::{mappers::Mapper, memory::*};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
@@ -39,8 +40,10 @@ const CARTRIDGE_SPACE_RANGE: Range<u32> = Range {
     end: CARTRIDGE_SPACE_END,
 };
 
+const PRG_ROM_SPACE_START: u16 = 0x8000;
+
 type RegisterLatch = RefCell<u8>;
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct Ram {
     memory: MemoryImpl<0x0808>,
     dmc_sample_address: usize,
@@ -70,6 +73,45 @@ impl Ram {
         *self.oam_dma_register_latch.borrow_mut() = 0;
     }
 
+    pub fn get_internal_ram(&self) -> &[u8] {
+        &self.memory.as_slice()[..INTERNAL_MIRROR_SIZE as usize]
+    }
+
+    pub fn get_internal_ram_mut(&mut self) -> &mut [u8] {
+        &mut self.memory.as_mut_slice()[..INTERNAL_MIRROR_SIZE as usize]
+    }
+
+    // Reads without touching any register: I/O registers return the last value on their bus latch.
+    pub fn peek_byte(
+        &self,
+        address_org: u16,
+        mapper: &MapperEnum,
+        vs_protection: Option<&VsProtection>,
+    ) -> u8 {
+        let addr = self.get_real_address(address_org);
+        if ReadAccessRegister::try_from(addr).is_ok() || WriteAccessRegister::try_from(addr).is_ok()
+        {
+            *self.ppu_register_latch.borrow()
+        } else if ram_apu::ReadAccessRegister::try_from(addr).is_ok() {
+            *self.apu_register_latch.borrow()
+        } else if InputRegister::try_from(addr).is_ok() || OutputRegister::try_from(addr).is_ok() {
+            *self.controller_register_latch.borrow()
+        } else if DmaWriteAccessRegister::try_from(addr).is_ok() {
+            *self.oam_dma_register_latch.borrow()
+        } else if ram_apu::WriteAccessRegister::try_from(addr).is_ok() {
+            *self.apu_register_latch.borrow()
+        } else if CARTRIDGE_SPACE_RANGE.contains(&(addr as u32)) {
+            vs_protection
+                .and_then(|vs_protection| vs_protection.peek(addr))
+                .unwrap_or_else(|| mapper.peek_prg_byte(addr))
+        } else if addr >= CPU_TEST_MODE_SPACE_START {
+            self.memory
+                .get_byte(INTERNAL_MIRROR_SIZE + addr - CPU_TEST_MODE_SPACE_START)
+        } else {
+            self.memory.get_byte(addr)
+        }
+    }
+
     fn get_real_address(&self, address: u16) -> u16 {
         if PPU_REGISTERS_RANGE.contains(&address) {
             PPU_REGISTERS_START + (address % PPU_REGISTERS_MIRROR_SIZE)
@@ -87,7 +129,7 @@ impl Memory for Ram {
         if let Ok(reg) = ReadAccessRegister::try_from(addr) {
             bus.mapper.notify_ppu_register_read(address_org);
             let mut ppu_register_value = bus.ppu.read(reg, bus.mapper);
-            if reg == ReadAccessRegister::PpuStatus {
+            if reg == ReadAccessRegister::PpuStatus && !bus.ppu.has_status_id() {
                 const LOW_5_BITS: u8 = 0b00011111;
                 ppu_register_value &= !LOW_5_BITS;
                 ppu_register_value |= *self.ppu_register_latch.borrow() & LOW_5_BITS
@@ -110,7 +152,10 @@ impl Memory for Ram {
         } else if ram_apu::WriteAccessRegister::try_from(addr).is_ok() {
             *self.apu_register_latch.borrow()
         } else if CARTRIDGE_SPACE_RANGE.contains(&(addr as u32)) {
-            bus.mapper.get_prg_byte(addr)
+            bus.vs_protection
+                .as_mut()
+                .and_then(|vs_protection| vs_protection.read(addr))
+                .unwrap_or_else(|| bus.mapper.get_prg_byte(addr))
         } else if addr >= CPU_TEST_MODE_SPACE_START {
             self.memory
                 .get_byte(INTERNAL_MIRROR_SIZE + addr - CPU_TEST_MODE_SPACE_START)
@@ -138,6 +183,7 @@ impl Memory for Ram {
             *self.oam_dma_register_latch.borrow_mut() = byte;
         } else if let Ok(output_port) = OutputRegister::try_from(addr) {
             bus.controllers.write(output_port, byte);
+            bus.mapper.notify_controller_port_write(byte);
             *self.controller_register_latch.borrow_mut() = byte;
         } else if let Ok(reg) = ram_apu::WriteAccessRegister::try_from(addr) {
             bus.apu.write(reg, byte);
@@ -149,6 +195,11 @@ impl Memory for Ram {
         } else if ram_apu::ReadAccessRegister::try_from(addr).is_ok() {
             *self.apu_register_latch.borrow_mut() = byte;
         } else if CARTRIDGE_SPACE_RANGE.contains(&(addr as u32)) {
+            let byte = if addr >= PRG_ROM_SPACE_START && bus.mapper.has_bus_conflicts() {
+                byte & bus.mapper.peek_prg_byte(addr)
+            } else {
+                byte
+            };
             bus.mapper.store_prg_byte(addr, byte)
         } else if addr < CPU_TEST_MODE_SPACE_START {
             assert!(addr < INTERNAL_MIRROR_SIZE);
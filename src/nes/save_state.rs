@@ -0,0 +1,317 @@
+use super::colors::{ColorMapper, DefaultColorMapper, RgbColor};
+use super::{Error, VIDEO_FRAME_HEIGHT, VIDEO_FRAME_WIDTH, VideoFrame};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Layout, all numbers little endian:
+//   magic (8 bytes), version (u32), flags (u32), ROM CRC32 (u32), timestamp (u64, UNIX seconds),
+//   playtime (u64, frames), thumbnail size (u32, 0 if none), zlib compressed RGB24 thumbnail,
+//   zlib compressed MessagePack body.
+// Version 1 states predate the header and are a zlib compressed JSON body.
+const MAGIC: &[u8; 8] = b"NESRSST\x1a";
+const HEADER_SIZE: usize = 40;
+const VERSION: u32 = 2;
+
+const FLAG_ROM_CRC32: u32 = 0b0001;
+
+// MIGRATIONS[n] converts the body of a version n + 1 state into version n + 2.
+// Changing the serialized structs requires bumping VERSION and appending a migration.
+const MIGRATIONS: [fn(&mut Value); VERSION as usize - 1] = [migrate_v1];
+
+pub const SAVE_STATE_THUMBNAIL_WIDTH: usize = VIDEO_FRAME_WIDTH / 2;
+pub const SAVE_STATE_THUMBNAIL_HEIGHT: usize = VIDEO_FRAME_HEIGHT / 2;
+
+const VRAM_PALETTES_OFFSET: usize = 0x0800;
+const VRAM_PALETTES_SIZE: usize = 0x20;
+const TRAINER_PRG_RAM_OFFSET: u64 = 0x1000;
+
+fn migrate_v1(state: &mut Value) {
+    let Some(nes) = state.as_object_mut() else {
+        return;
+    };
+    // The version used to be stored inside the body.
+    nes.remove("version");
+    nes.entry("playchoice10").or_insert(Value::Null);
+    if let Some(controllers) = nes.get_mut("controllers").and_then(Value::as_object_mut) {
+        controllers
+            .entry("is_vs_system")
+            .or_insert(Value::Bool(false));
+    }
+    if let Some(ppu) = nes.get_mut("ppu") {
+        migrate_v1_ppu(ppu);
+    }
+    if let Some(mapper) = nes.get_mut("mapper") {
+        migrate_v1_mapper(mapper);
+    }
+}
+
+fn migrate_v1_ppu(ppu: &mut Value) {
+    let mut palette_ram = [0u8; VRAM_PALETTES_SIZE];
+    if let Some(memory) = ppu
+        .pointer("/vram/memory/memory")
+        .and_then(Value::as_array)
+        .and_then(|memory| memory.get(VRAM_PALETTES_OFFSET..))
+    {
+        for (color, byte) in palette_ram.iter_mut().zip(memory) {
+            *color = byte.as_u64().unwrap_or(0) as u8;
+        }
+    }
+    let Some(ppu) = ppu.as_object_mut() else {
+        return;
+    };
+    ppu.entry("model").or_insert(Value::from("Rp2C02"));
+    // Cached palettes held colors already mapped to RGB, now they hold the palette RAM values.
+    for (key, palette_ram_offset) in [("background_palletes", 0x01), ("sprite_palettes", 0x11)] {
+        let Some(palettes) = ppu.get_mut(key).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for (palette_index, palette) in palettes.iter_mut().enumerate() {
+            let Some(palette) = palette.as_array_mut() else {
+                continue;
+            };
+            for (color_index, color) in palette.iter_mut().enumerate() {
+                let palette_ram_index = if color_index == 0 {
+                    0
+                } else {
+                    palette_ram_offset + 4 * palette_index + color_index - 1
+                };
+                *color = Value::from(rgb_to_nes_color(color, palette_ram[palette_ram_index]));
+            }
+        }
+    }
+}
+
+fn rgb_to_nes_color(rgb: &Value, palette_ram_color: u8) -> u8 {
+    let color_mapper = DefaultColorMapper::new();
+    let Ok(rgb) = serde_json::from_value::<RgbColor>(rgb.clone()) else {
+        return palette_ram_color & 0x3F;
+    };
+    // Several NES colors map to the same RGB value, prefer the one in palette RAM.
+    std::iter::once(palette_ram_color & 0x3F)
+        .chain(0..0x40)
+        .find(|&color| color_mapper.map_nes_color(color) == rgb)
+        .unwrap_or(palette_ram_color & 0x3F)
+}
+
+fn migrate_v1_mapper(mapper: &mut Value) {
+    let Some((name, mapper)) = mapper
+        .as_object_mut()
+        .and_then(|mapper| mapper.iter_mut().next())
+    else {
+        return;
+    };
+    let Some(mapper) = mapper.as_object_mut() else {
+        return;
+    };
+    // Version 1 emulated none of these features, keep them disabled.
+    match name.as_str() {
+        "Mapper2" | "Mapper3" | "Mapper7" | "Mapper66" => {
+            mapper.entry("bus_conflicts").or_insert(Value::Bool(false));
+        }
+        "Mapper71" => {
+            mapper.entry("is_fire_hawk").or_insert(Value::Bool(false));
+        }
+        _ => (),
+    }
+    if let Some(mmc3) = mapper.get_mut("mmc3").and_then(Value::as_object_mut) {
+        if let Some(variant) = mmc3.remove("_variant") {
+            let variant = variant.as_str().unwrap_or_default().trim_start_matches('_');
+            mmc3.insert("variant".to_string(), Value::from(variant));
+        }
+        mmc3.entry("irq_revision").or_insert(Value::from("B"));
+        mmc3.entry("chr_bank_values")
+            .or_insert(Value::from(vec![0u8; 8]));
+        migrate_v1_mapper_internal(mmc3);
+    }
+    migrate_v1_mapper_internal(mapper);
+}
+
+fn migrate_v1_mapper_internal(mapper: &mut Map<String, Value>) {
+    if let Some(mapper_internal) = mapper
+        .get_mut("mapper_internal")
+        .and_then(Value::as_object_mut)
+    {
+        mapper_internal
+            .entry("trainer")
+            .or_insert(Value::Array(Vec::new()));
+        mapper_internal
+            .entry("trainer_prg_ram_offset")
+            .or_insert(Value::from(TRAINER_PRG_RAM_OFFSET));
+    }
+}
+
+pub struct SaveStateInfo {
+    pub version: u32,
+    pub rom_crc32: Option<u32>,
+    pub timestamp: Option<u64>,
+    pub playtime_frames: Option<u64>,
+    /// RGB24 pixels, `SAVE_STATE_THUMBNAIL_WIDTH` x `SAVE_STATE_THUMBNAIL_HEIGHT`.
+    pub thumbnail: Option<Vec<u8>>,
+}
+
+pub(crate) struct Header<'a> {
+    pub version: u32,
+    pub rom_crc32: Option<u32>,
+    timestamp: Option<u64>,
+    pub playtime_frames: Option<u64>,
+    thumbnail: &'a [u8],
+}
+
+impl Header<'_> {
+    pub fn to_info(&self) -> Result<SaveStateInfo, Error> {
+        let thumbnail = if self.thumbnail.is_empty() {
+            None
+        } else {
+            Some(decompress(self.thumbnail)?)
+        };
+        Ok(SaveStateInfo {
+            version: self.version,
+            rom_crc32: self.rom_crc32,
+            timestamp: self.timestamp,
+            playtime_frames: self.playtime_frames,
+            thumbnail,
+        })
+    }
+}
+
+fn corrupted_header() -> Error {
+    Error::LoadStateInternalError("Save state header is corrupted".to_string())
+}
+
+fn read_u32(state: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes(state[offset..offset + 4].try_into().unwrap())
+}
+
+fn read_u64(state: &[u8], offset: usize) -> u64 {
+    u64::from_le_bytes(state[offset..offset + 8].try_into().unwrap())
+}
+
+fn compress(data: &[u8]) -> Result<Vec<u8>, Error> {
+    yazi::compress(data, yazi::Format::Zlib, yazi::CompressionLevel::BestSpeed)
+        .map_err(Error::LoadStateCompressionError)
+}
+
+fn decompress(data: &[u8]) -> Result<Vec<u8>, Error> {
+    let (decompressed, checksum) =
+        yazi::decompress(data, yazi::Format::Zlib).map_err(Error::LoadStateDecompressionError)?;
+    if let Some(checksum) = checksum
+        && checksum != yazi::Adler32::from_buf(&decompressed).finish()
+    {
+        return Err(Error::LoadStateInternalError(
+            "Checksum mismatch".to_string(),
+        ));
+    }
+    Ok(decompressed)
+}
+
+pub(crate) fn create_thumbnail(video: &VideoFrame) -> Vec<u8> {
+    let mut thumbnail =
+        Vec::with_capacity(SAVE_STATE_THUMBNAIL_WIDTH * SAVE_STATE_THUMBNAIL_HEIGHT * 3);
+    for y in 0..SAVE_STATE_THUMBNAIL_HEIGHT {
+        for x in 0..SAVE_STATE_THUMBNAIL_WIDTH {
+            let mut color = [0u16; 3];
+            for (dx, dy) in [(0, 0), (1, 0), (0, 1), (1, 1)] {
+                let pixel = video.get_pixel((2 * x + dx) as u8, (2 * y + dy) as u8);
+                color[0] += pixel.0 as u16;
+                color[1] += pixel.1 as u16;
+                color[2] += pixel.2 as u16;
+            }
+            thumbnail.extend(color.map(|channel| (channel / 4) as u8));
+        }
+    }
+    thumbnail
+}
+
+pub(crate) fn encode<T: Serialize>(
+    body: &T,
+    rom_crc32: Option<u32>,
+    playtime_frames: u64,
+    thumbnail: Option<&[u8]>,
+) -> Result<Vec<u8>, Error> {
+    let body =
+        rmp_serde::to_vec_named(body).map_err(|e| Error::SaveStateInternalError(e.to_string()))?;
+    let body = compress(&body)?;
+    let thumbnail = thumbnail.map(compress).transpose()?.unwrap_or_default();
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs());
+
+    let flags = if rom_crc32.is_some() {
+        FLAG_ROM_CRC32
+    } else {
+        0
+    };
+
+    let mut state = Vec::with_capacity(HEADER_SIZE + thumbnail.len() + body.len());
+    state.extend_from_slice(MAGIC);
+    state.extend_from_slice(&VERSION.to_le_bytes());
+    state.extend_from_slice(&flags.to_le_bytes());
+    state.extend_from_slice(&rom_crc32.unwrap_or_default().to_le_bytes());
+    state.extend_from_slice(&timestamp.to_le_bytes());
+    state.extend_from_slice(&playtime_frames.to_le_bytes());
+    state.extend_from_slice(&(thumbnail.len() as u32).to_le_bytes());
+    state.extend_from_slice(&thumbnail);
+    state.extend_from_slice(&body);
+    Ok(state)
+}
+
+pub(crate) fn split(state: &[u8]) -> Result<(Header<'_>, &[u8]), Error> {
+    if !state.starts_with(MAGIC) {
+        let header = Header {
+            version: 1,
+            rom_crc32: None,
+            timestamp: None,
+            playtime_frames: None,
+            thumbnail: &[],
+        };
+        return Ok((header, state));
+    }
+    if state.len() < HEADER_SIZE {
+        return Err(corrupted_header());
+    }
+    let version = read_u32(state, 8);
+    if !(2..=VERSION).contains(&version) {
+        return Err(Error::LoadStateVersionMismatch(
+            VERSION.to_string(),
+            version.to_string(),
+        ));
+    }
+    let flags = read_u32(state, 12);
+    let rom_crc32 = (flags & FLAG_ROM_CRC32 != 0).then(|| read_u32(state, 16));
+    let timestamp = read_u64(state, 20);
+    let playtime_frames = read_u64(state, 28);
+    let thumbnail_size = read_u32(state, 36) as usize;
+    let (thumbnail, body) = state[HEADER_SIZE..]
+        .split_at_checked(thumbnail_size)
+        .ok_or_else(corrupted_header)?;
+    let header = Header {
+        version,
+        rom_crc32,
+        timestamp: Some(timestamp),
+        playtime_frames: Some(playtime_frames),
+        thumbnail,
+    };
+    Ok((header, body))
+}
+
+pub(crate) fn decode<T: DeserializeOwned>(header: &Header, body: &[u8]) -> Result<T, Error> {
+    let body = decompress(body)?;
+    let load_error = |e: &dyn std::fmt::Display| Error::LoadStateInternalError(e.to_string());
+    if header.version == VERSION {
+        return rmp_serde::from_slice(&body).map_err(|e| load_error(&e));
+    }
+    // Only version 1 bodies are JSON, later ones are MessagePack like the current version.
+    let mut value = if header.version == 1 {
+        let mut deserializer = serde_json::Deserializer::from_slice(&body);
+        let deserializer = serde_stacker::Deserializer::new(&mut deserializer);
+        <Value as serde::Deserialize>::deserialize(deserializer).map_err(|e| load_error(&e))?
+    } else {
+        rmp_serde::from_slice(&body).map_err(|e| load_error(&e))?
+    };
+    for migration in &MIGRATIONS[header.version as usize - 1..] {
+        migration(&mut value);
+    }
+    serde_json::from_value(value).map_err(|e| load_error(&e))
+}
@@ -0,0 +1,179 @@
+use super::{
+    EmulationFrame, NTSC_VIDEO_FRAME_WIDTH, PIXEL_SIZE, VIDEO_FRAME_HEIGHT, VIDEO_FRAME_WIDTH,
+};
+
+const NTSC_OVERSCAN_LINES: u8 = 8;
+const NTSC_PIXEL_ASPECT_RATIO: f32 = 8.0 / 7.0;
+const BMP_HEADER_SIZE: usize = 54;
+const BMP_INFO_HEADER_SIZE: u32 = 40;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct Overscan {
+    pub top: u8,
+    pub bottom: u8,
+    pub left: u8,
+    pub right: u8,
+}
+
+impl Overscan {
+    pub fn none() -> Self {
+        Self {
+            top: 0,
+            bottom: 0,
+            left: 0,
+            right: 0,
+        }
+    }
+}
+
+impl Default for Overscan {
+    fn default() -> Self {
+        Self {
+            top: NTSC_OVERSCAN_LINES,
+            bottom: NTSC_OVERSCAN_LINES,
+            left: 0,
+            right: 0,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub enum PixelAspectRatio {
+    #[default]
+    Square,
+    Ntsc,
+}
+
+impl PixelAspectRatio {
+    pub fn get_ratio(&self) -> f32 {
+        match self {
+            PixelAspectRatio::Square => 1.0,
+            PixelAspectRatio::Ntsc => NTSC_PIXEL_ASPECT_RATIO,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct CropRect {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+#[derive(Clone)]
+pub struct VideoOutputImage {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl VideoOutputImage {
+    pub fn get_width(&self) -> usize {
+        self.width
+    }
+
+    pub fn get_height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get_pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    pub fn encode_bmp(&self) -> Vec<u8> {
+        let row_size = (PIXEL_SIZE * self.width + 3) & !3;
+        let file_size = BMP_HEADER_SIZE + row_size * self.height;
+        let mut bmp = vec![0u8; BMP_HEADER_SIZE];
+        bmp[0..2].copy_from_slice(b"BM");
+        bmp[2..6].copy_from_slice(&(file_size as u32).to_le_bytes());
+        bmp[10..14].copy_from_slice(&(BMP_HEADER_SIZE as u32).to_le_bytes());
+        bmp[14..18].copy_from_slice(&BMP_INFO_HEADER_SIZE.to_le_bytes());
+        bmp[18..22].copy_from_slice(&(self.width as u32).to_le_bytes());
+        bmp[22..26].copy_from_slice(&(self.height as u32).to_le_bytes());
+        bmp[26..28].copy_from_slice(&1u16.to_le_bytes());
+        bmp[28..30].copy_from_slice(&(PIXEL_SIZE as u16 * 8).to_le_bytes());
+        for row in self.pixels.chunks(PIXEL_SIZE * self.width).rev() {
+            for pixel in row.chunks(PIXEL_SIZE) {
+                bmp.extend_from_slice(&[pixel[2], pixel[1], pixel[0]]);
+            }
+            bmp.resize(bmp.len() + row_size - PIXEL_SIZE * self.width, 0);
+        }
+        bmp
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Debug)]
+pub struct VideoOutputSettings {
+    pub overscan: Overscan,
+    pub pixel_aspect_ratio: PixelAspectRatio,
+    pub integer_scaling: bool,
+}
+
+impl VideoOutputSettings {
+    // Overscan is given in NES pixels, wider (NTSC filtered) frames are cropped proportionally.
+    pub fn get_crop_rect(&self, frame_width: usize) -> CropRect {
+        let top = (self.overscan.top as usize).min(VIDEO_FRAME_HEIGHT - 1);
+        let bottom = (self.overscan.bottom as usize).min(VIDEO_FRAME_HEIGHT - 1 - top);
+        let left = (self.overscan.left as usize).min(VIDEO_FRAME_WIDTH - 1);
+        let right = (self.overscan.right as usize).min(VIDEO_FRAME_WIDTH - 1 - left);
+        let scale = |pixels: usize| pixels * frame_width / VIDEO_FRAME_WIDTH;
+        CropRect {
+            x: scale(left),
+            y: top,
+            width: frame_width - scale(left) - scale(right),
+            height: VIDEO_FRAME_HEIGHT - top - bottom,
+        }
+    }
+
+    pub fn get_output_size(&self) -> [u32; 2] {
+        let crop_rect = self.get_crop_rect(VIDEO_FRAME_WIDTH);
+        let width = crop_rect.width as f32 * self.pixel_aspect_ratio.get_ratio();
+        [width.round() as u32, crop_rect.height as u32]
+    }
+
+    pub fn get_viewport(&self, area_size: [f32; 2]) -> ([f32; 2], [f32; 2]) {
+        if !self.integer_scaling {
+            return ([0.0, 0.0], area_size);
+        }
+        let [output_width, output_height] = self.get_output_size();
+        let scale = (area_size[0] / output_width as f32)
+            .min(area_size[1] / output_height as f32)
+            .floor()
+            .max(1.0);
+        let size = [output_width as f32 * scale, output_height as f32 * scale];
+        let position = [
+            ((area_size[0] - size[0]) / 2.0).floor(),
+            ((area_size[1] - size[1]) / 2.0).floor(),
+        ];
+        (position, size)
+    }
+
+    pub fn render(&self, emulation_frame: &EmulationFrame, scale: u32) -> VideoOutputImage {
+        let (frame_width, frame_pixels) = match emulation_frame.ntsc_video {
+            Some(ref ntsc_video) => (NTSC_VIDEO_FRAME_WIDTH, ntsc_video.get_pixels().as_slice()),
+            None => (
+                VIDEO_FRAME_WIDTH,
+                emulation_frame.video.get_pixels().as_slice(),
+            ),
+        };
+        let crop_rect = self.get_crop_rect(frame_width);
+        let [output_width, output_height] = self.get_output_size();
+        let width = (output_width * scale.max(1)) as usize;
+        let height = (output_height * scale.max(1)) as usize;
+        let mut pixels = Vec::with_capacity(width * height * PIXEL_SIZE);
+        for y in 0..height {
+            let frame_y = crop_rect.y + y * crop_rect.height / height;
+            for x in 0..width {
+                let frame_x = crop_rect.x + x * crop_rect.width / width;
+                let index = (frame_y * frame_width + frame_x) * PIXEL_SIZE;
+                pixels.extend_from_slice(&frame_pixels[index..index + PIXEL_SIZE]);
+            }
+        }
+        VideoOutputImage {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
@@ -23,7 +23,7 @@ const PALETTES_RANGE: Range<u16> = Range {
     end: PALETTES_END,
 };
 
-#[derive(Serialize, Deserialize, Default)]
+#[derive(Clone, Serialize, Deserialize, Default)]
 pub struct VRam {
     memory: super::memory::MemoryImpl<0x0820>,
     read_buffer: RefCell<u8>,
@@ -47,21 +47,20 @@ impl VRam {
     ) -> (NametableSource, u16) {
         let offset = address & 0x0FFF;
         let table = offset / 0x0400;
-        let inner = offset & 0x03FF;
         let source = mapper.get_mirroring().tables[table as usize];
-        (source, inner)
+        (source, offset)
     }
 
     fn get_target_address(&self, address: u16, mapper: &MapperEnum) -> u16 {
         if NAMETABLES_RANGE.contains(&address) {
-            let (source, inner) = self.get_nametable_source_and_offset(address, mapper);
+            let (source, offset) = self.get_nametable_source_and_offset(address, mapper);
             let vram_page = match source {
                 NametableSource::Vram0 => 0,
                 NametableSource::Vram1 => 1,
                 _ => 0,
             };
 
-            vram_page * 0x0400 + inner
+            vram_page * 0x0400 + (offset & 0x03FF)
         } else if PALETTES_RANGE.contains(&address) {
             let offset = address & 0x1F;
             let mirrored = match offset {
@@ -81,10 +80,8 @@ impl VRam {
         if address < NAMETABLES_START {
             mapper.get_chr_byte(address)
         } else if NAMETABLES_RANGE.contains(&address) {
-            let (source, inner) = self.get_nametable_source_and_offset(address, mapper);
-            if inner < 960
-                && let Some(byte) = mapper.get_nametable_byte(source, inner)
-            {
+            let (source, offset) = self.get_nametable_source_and_offset(address, mapper);
+            if let Some(byte) = mapper.get_nametable_byte(source, offset) {
                 return byte;
             }
             self.memory
@@ -124,8 +121,8 @@ impl VideoMemory for VRam {
         if address < NAMETABLES_START {
             mapper.store_chr_byte(address, byte);
         } else if NAMETABLES_RANGE.contains(&address) {
-            let (source, inner) = self.get_nametable_source_and_offset(address, mapper);
-            if !mapper.store_nametable_or_bg_palette_index(source, inner, byte) {
+            let (source, offset) = self.get_nametable_source_and_offset(address, mapper);
+            if !mapper.store_nametable_or_bg_palette_index(source, offset, byte) {
                 self.memory
                     .store_byte(self.get_target_address(address, mapper), byte);
             }
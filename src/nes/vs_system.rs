@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+
+const PROTECTION_DATA_SIZE: usize = 32;
+
+const TKO_BOXING_PROTECTION_DATA: [u8; PROTECTION_DATA_SIZE] = [
+    0xFF, 0xBF, 0xB7, 0x97, 0x97, 0x17, 0x57, 0x4F, 0x6F, 0x6B, 0xEB, 0xA9, 0xB1, 0x90, 0x94, 0x14,
+    0x56, 0x4E, 0x6F, 0x6B, 0xEB, 0xA9, 0xB1, 0x90, 0xD4, 0x5C, 0x3E, 0x26, 0x87, 0x83, 0x13, 0x00,
+];
+
+const RBI_BASEBALL_PROTECTION_DATA: [u8; PROTECTION_DATA_SIZE] = [
+    0x00, 0x00, 0x00, 0x00, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x6F, 0x00, 0x00, 0x00, 0x00, 0x94, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+const PROTECTION_RESET_ADDRESS: u16 = 0x5E00;
+const PROTECTION_DATA_ADDRESS: u16 = 0x5E01;
+
+// Values of the NES 2.0 VS. hardware type (high nibble of header byte 13).
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub(crate) enum VsHardwareType {
+    UniSystem,
+    RbiBaseballProtection,
+    TkoBoxingProtection,
+    SuperXeviousProtection,
+    // The Ice Climber and Raid on Bungeling Bay checks are not emulated, and only the main
+    // console of a Dual System is.
+    IceClimberProtection,
+    DualSystem,
+    RaidOnBungelingBayProtection,
+}
+
+impl VsHardwareType {
+    pub fn from_header(value: u8) -> Self {
+        match value {
+            1 => Self::RbiBaseballProtection,
+            2 => Self::TkoBoxingProtection,
+            3 => Self::SuperXeviousProtection,
+            4 => Self::IceClimberProtection,
+            5 => Self::DualSystem,
+            6 => Self::RaidOnBungelingBayProtection,
+            _ => Self::UniSystem,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct VsProtection {
+    hardware_type: VsHardwareType,
+    counter: u8,
+}
+
+impl VsProtection {
+    pub fn new(hardware_type: VsHardwareType) -> Self {
+        Self {
+            hardware_type,
+            counter: 0,
+        }
+    }
+
+    pub fn power_cycle(&mut self) {
+        self.counter = 0;
+    }
+
+    pub fn read(&mut self, address: u16) -> Option<u8> {
+        match self.hardware_type {
+            VsHardwareType::RbiBaseballProtection => {
+                self.read_counter_protection(address, &RBI_BASEBALL_PROTECTION_DATA)
+            }
+            VsHardwareType::TkoBoxingProtection => {
+                self.read_counter_protection(address, &TKO_BOXING_PROTECTION_DATA)
+            }
+            VsHardwareType::SuperXeviousProtection => self.read_super_xevious_protection(address),
+            _ => None,
+        }
+    }
+
+    pub fn peek(&self, address: u16) -> Option<u8> {
+        self.clone().read(address)
+    }
+
+    fn read_counter_protection(
+        &mut self,
+        address: u16,
+        data: &[u8; PROTECTION_DATA_SIZE],
+    ) -> Option<u8> {
+        match address {
+            PROTECTION_RESET_ADDRESS => {
+                self.counter = 0;
+                None
+            }
+            PROTECTION_DATA_ADDRESS => {
+                let value = data[self.counter as usize % PROTECTION_DATA_SIZE];
+                self.counter = self.counter.wrapping_add(1);
+                Some(value)
+            }
+            _ => None,
+        }
+    }
+
+    fn read_super_xevious_protection(&mut self, address: u16) -> Option<u8> {
+        let is_selected = self.counter != 0;
+        match address {
+            0x54FF => Some(0x05),
+            0x5678 => Some(if is_selected { 0x00 } else { 0x01 }),
+            0x578F => Some(if is_selected { 0xD1 } else { 0x89 }),
+            0x5567 => {
+                self.counter ^= 1;
+                Some(if is_selected { 0x3E } else { 0x37 })
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use crate::{
+    ControllerCallback, ControllerId, Error, Nes, StdNesControllerButton, VideoOutputSettings,
+    VsCoinSlot, ZapperTarget,
+};
+use rhai::{AST, Blob, CallFnOptions, Dynamic, Engine, EvalAltResult, INT, Map, Scope};
+use std::{cell::RefCell, rc::Rc};
+
+const FRAME_CALLBACK: &str = "on_frame";
+const BUTTON_COUNT: usize = StdNesControllerButton::Right as usize + 1;
+const DEFAULT_TEXT_COLOR: (u8, u8, u8) = (255, 255, 255);
+
+type ButtonOverrides = [[Option<bool>; BUTTON_COUNT]; 2];
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+#[derive(Default)]
+struct ScriptState {
+    buttons: ButtonOverrides,
+    frame: INT,
+    is_quit_requested: bool,
+    video_output_settings: VideoOutputSettings,
+}
+
+pub struct Script {
+    engine: Engine,
+    ast: AST,
+    scope: Scope<'static>,
+    this: Dynamic,
+    nes: Rc<RefCell<Nes>>,
+    state: Rc<RefCell<ScriptState>>,
+    has_frame_callback: bool,
+}
+
+impl Script {
+    pub fn new(source: &str, nes: &mut Nes) -> Result<Self, Error> {
+        let mut engine = Engine::new();
+        let script_nes = Rc::new(RefCell::new(Nes::new()));
+        let state = Rc::new(RefCell::new(ScriptState::default()));
+        register_api(&mut engine, &script_nes, &state);
+        let ast = engine
+            .compile(source)
+            .map_err(|e| Error::ScriptError(e.to_string()))?;
+        let has_frame_callback = ast
+            .iter_functions()
+            .any(|function| function.name == FRAME_CALLBACK);
+        let mut script = Self {
+            engine,
+            ast,
+            scope: Scope::new(),
+            this: Dynamic::from_map(Map::new()),
+            nes: script_nes,
+            state,
+            has_frame_callback,
+        };
+        script.with_nes(nes, |engine, scope, _, ast| {
+            engine.run_ast_with_scope(scope, ast)
+        })?;
+        Ok(script)
+    }
+
+    pub fn notify_frame(&mut self, nes: &mut Nes) -> Result<(), Error> {
+        self.state.borrow_mut().frame += 1;
+        if !self.has_frame_callback {
+            return Ok(());
+        }
+        self.with_nes(nes, |engine, scope, this, ast| {
+            let options = CallFnOptions::new().eval_ast(false).bind_this_ptr(this);
+            engine
+                .call_fn_with_options::<Dynamic>(options, scope, ast, FRAME_CALLBACK, ())
+                .map(|_| ())
+        })
+    }
+
+    pub fn is_quit_requested(&self) -> bool {
+        self.state.borrow().is_quit_requested
+    }
+
+    pub fn set_video_output_settings(&mut self, settings: VideoOutputSettings) {
+        self.state.borrow_mut().video_output_settings = settings;
+    }
+
+    pub fn get_controller_callback<'a>(
+        &self,
+        callback: Option<&'a dyn ControllerCallback>,
+    ) -> ScriptControllerCallback<'a> {
+        ScriptControllerCallback {
+            buttons: self.state.borrow().buttons,
+            callback,
+        }
+    }
+
+    // The script API works on a shared Nes, so the caller's instance is swapped in for the call.
+    fn with_nes<F>(&mut self, nes: &mut Nes, run: F) -> Result<(), Error>
+    where
+        F: FnOnce(&Engine, &mut Scope<'static>, &mut Dynamic, &AST) -> ScriptResult<()>,
+    {
+        std::mem::swap(nes, &mut *self.nes.borrow_mut());
+        let result = run(&self.engine, &mut self.scope, &mut self.this, &self.ast);
+        std::mem::swap(nes, &mut *self.nes.borrow_mut());
+        result.map_err(|e| Error::ScriptError(e.to_string()))
+    }
+}
+
+pub struct ScriptControllerCallback<'a> {
+    buttons: ButtonOverrides,
+    callback: Option<&'a dyn ControllerCallback>,
+}
+
+impl ControllerCallback for ScriptControllerCallback<'_> {
+    fn is_button_pressed(&self, id: ControllerId, button: StdNesControllerButton) -> bool {
+        self.buttons[id as usize][button as usize].unwrap_or_else(|| {
+            self.callback
+                .is_some_and(|callback| callback.is_button_pressed(id, button))
+        })
+    }
+
+    fn is_zapper_trigger_pressed(&self, id: ControllerId) -> Option<ZapperTarget> {
+        self.callback?.is_zapper_trigger_pressed(id)
+    }
+
+    fn is_vs_coin_inserted(&self, slot: VsCoinSlot) -> bool {
+        self.callback
+            .is_some_and(|callback| callback.is_vs_coin_inserted(slot))
+    }
+
+    fn is_vs_service_button_pressed(&self) -> bool {
+        self.callback
+            .is_some_and(|callback| callback.is_vs_service_button_pressed())
+    }
+
+    fn get_vs_dip_switches(&self) -> u8 {
+        self.callback
+            .map_or(0, |callback| callback.get_vs_dip_switches())
+    }
+
+    fn is_playchoice_coin_inserted(&self) -> bool {
+        self.callback
+            .is_some_and(|callback| callback.is_playchoice_coin_inserted())
+    }
+}
+
+fn get_address(address: INT) -> ScriptResult<u16> {
+    u16::try_from(address).map_err(|_| format!("Invalid address {}", address).into())
+}
+
+fn get_controller_id(player: INT) -> ScriptResult<ControllerId> {
+    usize::try_from(player - 1)
+        .ok()
+        .and_then(ControllerId::from_index)
+        .ok_or_else(|| format!("Invalid player {}", player).into())
+}
+
+fn get_button(name: &str) -> ScriptResult<StdNesControllerButton> {
+    (0..BUTTON_COUNT as u8)
+        .map(StdNesControllerButton::from)
+        .find(|button| button.to_string().eq_ignore_ascii_case(name))
+        .ok_or_else(|| format!("Invalid button '{}'", name).into())
+}
+
+fn get_color(r: INT, g: INT, b: INT) -> (u8, u8, u8) {
+    let clamp = |component: INT| component.clamp(0, u8::MAX as INT) as u8;
+    (clamp(r), clamp(g), clamp(b))
+}
+
+fn register_api(engine: &mut Engine, nes: &Rc<RefCell<Nes>>, state: &Rc<RefCell<ScriptState>>) {
+    let script_nes = nes.clone();
+    engine.register_fn("read_memory", move |address: INT| -> ScriptResult<INT> {
+        Ok(script_nes.borrow().peek_cpu_memory(get_address(address)?) as INT)
+    });
+
+    let script_nes = nes.clone();
+    engine.register_fn(
+        "write_memory",
+        move |address: INT, value: INT| -> ScriptResult<()> {
+            script_nes
+                .borrow_mut()
+                .write_cpu_memory(get_address(address)?, value as u8);
+            Ok(())
+        },
+    );
+
+    let script_state = state.clone();
+    engine.register_fn(
+        "set_button",
+        move |player: INT, button: &str, pressed: bool| -> ScriptResult<()> {
+            let id = get_controller_id(player)?;
+            let button = get_button(button)?;
+            script_state.borrow_mut().buttons[id as usize][button as usize] = Some(pressed);
+            Ok(())
+        },
+    );
+
+    let script_state = state.clone();
+    engine.register_fn("release_buttons", move || {
+        script_state.borrow_mut().buttons = Default::default();
+    });
+
+    let script_state = state.clone();
+    engine.register_fn("get_frame", move || script_state.borrow().frame);
+
+    let script_state = state.clone();
+    engine.register_fn("quit", move || {
+        script_state.borrow_mut().is_quit_requested = true;
+    });
+
+    let script_nes = nes.clone();
+    engine.register_fn("save_state", move || -> ScriptResult<Blob> {
+        script_nes
+            .borrow()
+            .save_state()
+            .map_err(|e| e.to_string().into())
+    });
+
+    let script_nes = nes.clone();
+    engine.register_fn("load_state", move |state: Blob| -> ScriptResult<()> {
+        script_nes
+            .borrow_mut()
+            .load_state(state)
+            .map_err(|e| e.to_string().into())
+    });
+
+    let script_nes = nes.clone();
+    let script_state = state.clone();
+    engine.register_fn("screenshot", move |path: &str| -> ScriptResult<()> {
+        let video_output_settings = script_state.borrow().video_output_settings;
+        let image = video_output_settings.render(script_nes.borrow().get_emulation_frame(), 1);
+        std::fs::write(path, image.encode_bmp()).map_err(|e| e.to_string().into())
+    });
+
+    let script_nes = nes.clone();
+    engine.register_fn("draw_text", move |x: INT, y: INT, text: &str| {
+        let (x, y) = (x.clamp(0, u8::MAX as INT), y.clamp(0, u8::MAX as INT));
+        script_nes
+            .borrow_mut()
+            .draw_text(x as u8, y as u8, text, DEFAULT_TEXT_COLOR);
+    });
+
+    let script_nes = nes.clone();
+    engine.register_fn(
+        "draw_text",
+        move |x: INT, y: INT, text: &str, r: INT, g: INT, b: INT| {
+            let (x, y) = (x.clamp(0, u8::MAX as INT), y.clamp(0, u8::MAX as INT));
+            script_nes
+                .borrow_mut()
+                .draw_text(x as u8, y as u8, text, get_color(r, g, b));
+        },
+    );
+}
@@ -0,0 +1,88 @@
+use crate::frontend::{STATE_SLOT_COUNT, StateSlot};
+use nes_rs::Nes;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+
+#[cfg(target_os = "emscripten")]
+const STATE_SLOTS_DIRECTORY: &str = "saves";
+#[cfg(not(target_os = "emscripten"))]
+const STATE_SLOTS_DIRECTORY: &str = "nes-rs/saves";
+
+pub struct StateSlots {
+    directory: PathBuf,
+    slots: [Option<Rc<StateSlot>>; STATE_SLOT_COUNT],
+}
+
+fn create_state_slot(state: &[u8]) -> Option<Rc<StateSlot>> {
+    let info = Nes::get_save_state_info(state).ok()?;
+    Some(Rc::new(StateSlot {
+        thumbnail: info.thumbnail,
+        timestamp: info.timestamp,
+        playtime_frames: info.playtime_frames,
+    }))
+}
+
+fn get_slot_path(directory: &Path, slot: usize) -> PathBuf {
+    directory.join(format!("slot{}.nesrs", slot + 1))
+}
+
+// The emscripten build keeps the slots in the IndexedDB backed "saves" mount, native builds use
+// the per-user data directory ($XDG_DATA_HOME, %APPDATA% or ~/.local/share).
+#[cfg(target_os = "emscripten")]
+fn get_data_directory() -> PathBuf {
+    PathBuf::new()
+}
+
+#[cfg(not(target_os = "emscripten"))]
+fn get_data_directory() -> PathBuf {
+    let env_directory = |name| std::env::var_os(name).filter(|value| !value.is_empty());
+    if let Some(directory) = env_directory("XDG_DATA_HOME") {
+        PathBuf::from(directory)
+    } else if cfg!(windows)
+        && let Some(directory) = env_directory("APPDATA")
+    {
+        PathBuf::from(directory)
+    } else if let Some(directory) = env_directory("HOME") {
+        Path::new(&directory).join(".local/share")
+    } else {
+        PathBuf::new()
+    }
+}
+
+impl StateSlots {
+    // Slots are keyed on the CRC32 of the loaded ROM, so renaming or moving the file keeps them and
+    // two different ROMs with the same file name don't share them.
+    pub fn new(nes: &Nes) -> Self {
+        let rom_crc32 = nes.get_rom_crc32().unwrap_or_default();
+        let directory = get_data_directory()
+            .join(STATE_SLOTS_DIRECTORY)
+            .join(format!("{:08x}", rom_crc32));
+        let slots = std::array::from_fn(|slot| {
+            std::fs::read(get_slot_path(&directory, slot))
+                .ok()
+                .and_then(|state| create_state_slot(&state))
+        });
+        Self { directory, slots }
+    }
+
+    pub fn get_slots(&self) -> &[Option<Rc<StateSlot>>; STATE_SLOT_COUNT] {
+        &self.slots
+    }
+
+    pub fn save(&mut self, nes: &Nes, slot: usize) -> Result<(), String> {
+        let state = nes.save_state_with_thumbnail().map_err(|e| e.to_string())?;
+        std::fs::create_dir_all(&self.directory).map_err(|e| e.to_string())?;
+        std::fs::write(get_slot_path(&self.directory, slot), &state).map_err(|e| e.to_string())?;
+        self.slots[slot] = create_state_slot(&state);
+        Ok(())
+    }
+
+    pub fn load(&self, nes: &mut Nes, slot: usize) -> Result<(), String> {
+        if self.slots[slot].is_none() {
+            return Err("Slot is empty".to_string());
+        }
+        let state =
+            std::fs::read(get_slot_path(&self.directory, slot)).map_err(|e| e.to_string())?;
+        nes.load_state(state).map_err(|e| e.to_string())
+    }
+}
@@ -17,3 +17,13 @@ pub fn run_simple_test(rom_path: &str, duration: Duration) {
 pub fn run_simple_short_test(rom_path: &str) {
     run_simple_test(rom_path, Duration::from_secs(3));
 }
+
+#[allow(dead_code)]
+pub fn run_short_test_with_rom(rom_path: &str, rom: &[u8]) {
+    let test_fn = move |nes_test: &mut NesTest| {
+        nes_test.run_for(Duration::from_secs(3));
+    };
+
+    let mut nes_test = NesTest::new_with_rom(rom_path, rom, None, test_fn);
+    assert!(nes_test.run());
+}
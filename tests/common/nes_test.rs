@@ -39,9 +39,17 @@ impl NesTest {
         rom_path: &str,
         suffix: Option<&str>,
         test_fn: impl Fn(&mut NesTest) + 'static,
+    ) -> Self {
+        Self::new_with_rom(rom_path, &get_bytes_from_file(rom_path), suffix, test_fn)
+    }
+
+    pub fn new_with_rom(
+        rom_path: &str,
+        rom: &[u8],
+        suffix: Option<&str>,
+        test_fn: impl Fn(&mut NesTest) + 'static,
     ) -> Self {
         let io_test = super::test_frontend::TestFrontend::new(rom_path);
-        let rom = get_bytes_from_file(rom_path);
         let mut nes = Nes::new();
         let mut dir = PathBuf::from(rom_path);
         let mut test_name = dir.file_name().unwrap().to_str().unwrap().to_owned();
@@ -53,7 +61,7 @@ impl NesTest {
         dir.pop();
         let output_frame_path = Self::create_frame_path(&dir, &test_name, "");
         let expected_frame_path = Self::create_frame_path(&dir, &test_name, ".expected");
-        nes.load_rom(&rom).unwrap();
+        nes.load_rom(rom).unwrap();
 
         NesTest {
             io_test,
@@ -0,0 +1,132 @@
+use nes_rs::{ControllerCallback, Interrupt, MemoryHookType, Nes};
+use std::sync::{Arc, Mutex};
+
+const FRAME_COUNT: usize = 10;
+
+fn run_frames(nes: &mut Nes, frame_count: usize) {
+    let callback: Option<&dyn ControllerCallback> = None;
+    for _ in 0..frame_count {
+        nes.run_single_frame(callback).unwrap();
+    }
+}
+
+#[test]
+fn hooks_test() {
+    let rom = std::fs::read("tests/nestest/nestest.nes").unwrap();
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+
+    let executed = Arc::new(Mutex::new(Vec::new()));
+    let ram_writes = Arc::new(Mutex::new(Vec::new()));
+    let ppu_register_writes = Arc::new(Mutex::new(Vec::new()));
+    let interrupts = Arc::new(Mutex::new(Vec::new()));
+
+    let hook_executed = executed.clone();
+    let exec_hook = nes.add_memory_hook(
+        MemoryHookType::CpuExecute,
+        0xC000..=0xFFFF,
+        move |address, _| hook_executed.lock().unwrap().push(address),
+    );
+    let hook_ram_writes = ram_writes.clone();
+    nes.add_memory_hook(
+        MemoryHookType::CpuWrite,
+        0x0000..=0x07FF,
+        move |address, value| hook_ram_writes.lock().unwrap().push((address, value)),
+    );
+    let hook_ppu_register_writes = ppu_register_writes.clone();
+    nes.add_memory_hook(
+        MemoryHookType::PpuRegisterWrite,
+        0x2000..=0x2007,
+        move |address, _| hook_ppu_register_writes.lock().unwrap().push(address),
+    );
+    let vector_reads = Arc::new(Mutex::new(Vec::new()));
+    let hook_vector_reads = vector_reads.clone();
+    nes.add_memory_hook(
+        MemoryHookType::CpuRead,
+        0xFFFA..=0xFFFB,
+        move |address, _| hook_vector_reads.lock().unwrap().push(address),
+    );
+    let hook_interrupts = interrupts.clone();
+    nes.add_interrupt_hook(move |interrupt, _| hook_interrupts.lock().unwrap().push(interrupt));
+
+    run_frames(&mut nes, FRAME_COUNT);
+
+    let executed_count = executed.lock().unwrap().len();
+    assert!(executed_count > 0);
+    assert!(
+        executed
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|&address| address >= 0xC000)
+    );
+    assert!(!ram_writes.lock().unwrap().is_empty());
+    assert!(
+        ram_writes
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|&(address, _)| address < 0x0800)
+    );
+    assert!(ppu_register_writes.lock().unwrap().contains(&0x2000));
+    assert!(interrupts.lock().unwrap().contains(&Interrupt::Nmi));
+    // Fetching the NMI vector is a CPU read as well.
+    assert_eq!(vector_reads.lock().unwrap()[..2], [0xFFFA, 0xFFFB]);
+
+    assert!(nes.remove_hook(exec_hook));
+    assert!(!nes.remove_hook(exec_hook));
+    run_frames(&mut nes, 1);
+    assert_eq!(executed.lock().unwrap().len(), executed_count);
+}
+
+#[test]
+fn mapper_write_hook_test() {
+    // STA $5000, STA $6000, STA $8000 and an endless loop.
+    let program = [
+        0xA9, 0x42, 0x8D, 0x00, 0x50, 0x8D, 0x00, 0x60, 0x8D, 0x00, 0x80, 0x4C, 0x0B, 0x80,
+    ];
+    let mut rom = vec![b'N', b'E', b'S', 0x1A, 0x02, 0x01, 0x00, 0x00];
+    rom.resize(0x10, 0x00);
+    let mut prg_rom = vec![0; 0x8000];
+    prg_rom[..program.len()].copy_from_slice(&program);
+    prg_rom[0x7FFC..0x7FFE].copy_from_slice(&[0x00, 0x80]);
+    rom.extend_from_slice(&prg_rom);
+    rom.extend_from_slice(&[0; 0x2000]);
+
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+    let mapper_writes = Arc::new(Mutex::new(Vec::new()));
+    let hook_mapper_writes = mapper_writes.clone();
+    nes.add_memory_hook(
+        MemoryHookType::MapperWrite,
+        0x0000..=0xFFFF,
+        move |address, value| hook_mapper_writes.lock().unwrap().push((address, value)),
+    );
+    run_frames(&mut nes, 1);
+    assert_eq!(
+        *mapper_writes.lock().unwrap(),
+        [(0x5000, 0x42), (0x8000, 0x42)]
+    );
+}
+
+#[test]
+fn peek_memory_test() {
+    let rom = std::fs::read("tests/nestest/nestest.nes").unwrap();
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+
+    nes.write_cpu_memory(0x0010, 0x5A);
+    assert_eq!(nes.peek_cpu_memory(0x0010), 0x5A);
+    assert_eq!(nes.peek_cpu_memory(0x0810), 0x5A);
+    assert_eq!(nes.peek_cpu_memory(0xFFFC), nes.read_cpu_memory(0xFFFC));
+
+    // Peeking $2002 between the two $2006 writes must not reset the address latch.
+    nes.write_cpu_memory(0x2006, 0x21);
+    nes.peek_cpu_memory(0x2002);
+    nes.write_cpu_memory(0x2006, 0x00);
+    nes.write_cpu_memory(0x2007, 0x77);
+    nes.write_cpu_memory(0x2006, 0x21);
+    nes.write_cpu_memory(0x2006, 0x00);
+    nes.read_cpu_memory(0x2007);
+    assert_eq!(nes.read_cpu_memory(0x2007), 0x77);
+}
@@ -0,0 +1,25 @@
+use nes_rs::{BuiltinPalette, ControllerCallback, IndexFrame, Nes};
+
+const FRAME_COUNT: usize = 120;
+
+fn run_with_palette(rom: &[u8], palette: BuiltinPalette) -> IndexFrame {
+    let mut nes = Nes::new();
+    nes.load_rom(rom).unwrap();
+    nes.config().set_builtin_palette(palette);
+    nes.config().set_index_frame_enabled(true);
+    let callback: Option<&dyn ControllerCallback> = None;
+    for _ in 1..FRAME_COUNT {
+        nes.run_single_frame(callback).unwrap();
+    }
+    let frame = nes.run_single_frame(callback).unwrap();
+    frame.indices.clone().unwrap()
+}
+
+#[test]
+fn index_frame_is_palette_independent_test() {
+    let rom = std::fs::read("tests/nestest/nestest.nes").unwrap();
+    let default_frame = run_with_palette(&rom, BuiltinPalette::Default);
+    let fceux_frame = run_with_palette(&rom, BuiltinPalette::Fceux);
+    assert!(default_frame.get_indices().iter().any(|&index| index != 0));
+    assert_eq!(default_frame.get_indices(), fceux_frame.get_indices());
+}
@@ -0,0 +1,75 @@
+use nes_rs::{Error, apply_ips_patch, create_ips_patch};
+
+const IPS_EOF_OFFSET: usize = 0x454F46;
+
+fn create_image(size: usize) -> Vec<u8> {
+    (0..size).map(|i| (i * 7) as u8).collect()
+}
+
+fn assert_round_trip(original: &[u8], modified: &[u8]) {
+    let patch = create_ips_patch(original, modified);
+    assert_eq!(apply_ips_patch(original, &patch).unwrap(), modified);
+}
+
+#[test]
+fn ips_round_trip_test() {
+    let original = create_image(0x10000);
+    assert_round_trip(&original, &original);
+
+    let mut modified = original.clone();
+    modified[0] ^= 0xFF;
+    modified[10] ^= 0xFF;
+    modified[13] ^= 0xFF;
+    modified[0x8000..0x9000].fill(0xAA);
+    modified[0xFFFF] ^= 0xFF;
+    assert_round_trip(&original, &modified);
+
+    let mut grown = original.clone();
+    grown.extend_from_slice(&[1, 2, 3]);
+    assert_round_trip(&original, &grown);
+
+    let mut shrunk = modified.clone();
+    shrunk.truncate(0x9000);
+    assert_round_trip(&original, &shrunk);
+}
+
+#[test]
+fn ips_round_trip_at_eof_offset_test() {
+    let original = create_image(IPS_EOF_OFFSET + 0x10);
+    let mut modified = original.clone();
+    modified[IPS_EOF_OFFSET] ^= 0xFF;
+    assert_round_trip(&original, &modified);
+}
+
+#[test]
+fn ips_rle_record_test() {
+    let original = vec![0; 8];
+    let patch = b"PATCH\x00\x00\x02\x00\x00\x00\x04\xEEEOF";
+    assert_eq!(
+        apply_ips_patch(&original, patch).unwrap(),
+        [0, 0, 0xEE, 0xEE, 0xEE, 0xEE, 0, 0]
+    );
+}
+
+#[test]
+fn ips_corrupted_patch_test() {
+    let original = create_image(0x100);
+    let mut modified = original.clone();
+    modified[0x10..0x20].fill(0);
+    let patch = create_ips_patch(&original, &modified);
+
+    let is_corrupted = |patch: &[u8]| {
+        matches!(
+            apply_ips_patch(&original, patch),
+            Err(Error::IpsPatchCorrupted(_))
+        )
+    };
+    assert!(is_corrupted(b""));
+    assert!(is_corrupted(b"PATCX"));
+    assert!(is_corrupted(&patch[1..]));
+    assert!(is_corrupted(&patch[..patch.len() - 3]));
+    for size in 5..patch.len() - 3 {
+        assert!(is_corrupted(&patch[..size]));
+    }
+    assert!(is_corrupted(b"PATCH\x00\x00\x02\x00\x00\x00"));
+}
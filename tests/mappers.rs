@@ -0,0 +1,676 @@
+use nes_rs::{ControllerCallback, ControllerId, Error, Nes, StdNesControllerButton, ZapperTarget};
+
+const PRG_ROM_BANK_SIZE: usize = 0x2000;
+const CHR_ROM_BANK_SIZE: usize = 0x0400;
+const PRG_ROM_UNIT_SIZE: usize = 0x4000;
+const CHR_ROM_UNIT_SIZE: usize = 0x2000;
+const CONFLICT_FREE_OFFSET: usize = 0x0FFF;
+const PROGRAM_OFFSET: usize = 0x1000;
+const PROGRAM_ADDRESS: u16 = 0xF000;
+const INTERRUPT_OFFSET: usize = 0x1FF9;
+const INTERRUPT_ADDRESS: u16 = 0xFFF9;
+const RESULTS_ADDRESS: u16 = 0x0300;
+
+const EEPROM_CONTROL_REGISTER: u16 = 0x800D;
+const EEPROM_DATA_ADDRESS: u16 = 0x6000;
+const EEPROM_24C02_WRITE: u8 = 0xA0;
+const EEPROM_24C02_READ: u8 = 0xA1;
+
+// 6502 test program. It runs from the upper half of every 8KB PRG-ROM bank, so bank switches do
+// not pull the code away, and stores every value it reads to internal RAM from $0300 on.
+struct Program {
+    code: Vec<u8>,
+    result_count: u16,
+}
+
+impl Program {
+    fn new() -> Self {
+        // SEI, CLD, LDX #$FF, TXS
+        Self {
+            code: vec![0x78, 0xD8, 0xA2, 0xFF, 0x9A],
+            result_count: 0,
+        }
+    }
+
+    fn instruction(&mut self, opcode: u8, address: u16) {
+        self.code.push(opcode);
+        self.code.extend(address.to_le_bytes());
+    }
+
+    fn next_result_address(&mut self) -> u16 {
+        self.result_count += 1;
+        RESULTS_ADDRESS + self.result_count - 1
+    }
+
+    fn write(&mut self, address: u16, value: u8) {
+        // LDA #value, STA address
+        self.code.extend([0xA9, value]);
+        self.instruction(0x8D, address);
+    }
+
+    fn read(&mut self, address: u16) {
+        // LDA address, STA result
+        self.instruction(0xAD, address);
+        let result_address = self.next_result_address();
+        self.instruction(0x8D, result_address);
+    }
+
+    fn set_vram_address(&mut self, address: u16) {
+        self.instruction(0xAD, 0x2002);
+        self.write(0x2006, (address >> 8) as u8);
+        self.write(0x2006, address as u8);
+    }
+
+    fn read_vram(&mut self, address: u16) {
+        self.set_vram_address(address);
+        self.instruction(0xAD, 0x2007);
+        self.read(0x2007);
+    }
+
+    fn write_vram(&mut self, address: u16, value: u8) {
+        self.set_vram_address(address);
+        self.write(0x2007, value);
+    }
+
+    fn assemble(&self) -> Vec<u8> {
+        let mut code = self.code.clone();
+        // JMP to itself once done.
+        let end_address = PROGRAM_ADDRESS + code.len() as u16;
+        code.push(0x4C);
+        code.extend(end_address.to_le_bytes());
+        code
+    }
+}
+
+// NES 2.0 image where every ROM byte below the program holds the number of its 8KB PRG-ROM or
+// 1KB CHR-ROM bank. One PRG-ROM byte per bank is 0xFF instead, so writes there avoid bus conflicts.
+fn create_rom(
+    mapper: u8,
+    submapper: u8,
+    flag_6: u8,
+    prg_rom_units: u8,
+    chr_rom_units: u8,
+    program: &Program,
+) -> Vec<u8> {
+    let mut rom = vec![
+        b'N',
+        b'E',
+        b'S',
+        0x1A,
+        prg_rom_units,
+        chr_rom_units,
+        mapper << 4 | flag_6,
+        mapper & 0xF0 | 0x08,
+        submapper << 4,
+    ];
+    rom.resize(0x10, 0x00);
+    let code = program.assemble();
+    for bank in 0..prg_rom_units as usize * PRG_ROM_UNIT_SIZE / PRG_ROM_BANK_SIZE {
+        let mut prg_rom_bank = vec![bank as u8; PRG_ROM_BANK_SIZE];
+        prg_rom_bank[CONFLICT_FREE_OFFSET] = 0xFF;
+        prg_rom_bank[PROGRAM_OFFSET..PROGRAM_OFFSET + code.len()].copy_from_slice(&code);
+        // RTI for the NMI and IRQ vectors, then the NMI, reset and IRQ vectors.
+        prg_rom_bank[INTERRUPT_OFFSET] = 0x40;
+        for (i, address) in [INTERRUPT_ADDRESS, PROGRAM_ADDRESS, INTERRUPT_ADDRESS]
+            .into_iter()
+            .enumerate()
+        {
+            let offset = PRG_ROM_BANK_SIZE - 6 + i * 2;
+            prg_rom_bank[offset..offset + 2].copy_from_slice(&address.to_le_bytes());
+        }
+        rom.extend(prg_rom_bank);
+    }
+    let chr_rom_size = chr_rom_units as usize * CHR_ROM_UNIT_SIZE;
+    rom.extend((0..chr_rom_size).map(|i| (i / CHR_ROM_BANK_SIZE) as u8));
+    rom
+}
+
+fn create_nes(rom: &[u8]) -> Nes {
+    let mut nes = Nes::new();
+    nes.load_rom(rom).unwrap();
+    nes
+}
+
+fn run_frames(nes: &mut Nes, callback: Option<&dyn ControllerCallback>) {
+    for _ in 0..2 {
+        nes.run_single_frame(callback).unwrap();
+    }
+}
+
+fn get_results(nes: &Nes, program: &Program) -> Vec<u8> {
+    let state = serde_json::to_value(nes).unwrap();
+    let ram = &state["ram"]["memory"]["memory"];
+    (0..program.result_count)
+        .map(|i| ram[(RESULTS_ADDRESS + i) as usize].as_u64().unwrap() as u8)
+        .collect()
+}
+
+fn run_program(rom: &[u8], program: &Program) -> Vec<u8> {
+    let mut nes = create_nes(rom);
+    run_frames(&mut nes, None);
+    get_results(&nes, program)
+}
+
+fn set_eeprom_lines(program: &mut Program, scl: bool, sda: bool) {
+    program.write(EEPROM_CONTROL_REGISTER, (scl as u8) << 5 | (sda as u8) << 6);
+}
+
+fn start_eeprom(program: &mut Program) {
+    set_eeprom_lines(program, false, true);
+    set_eeprom_lines(program, true, true);
+    set_eeprom_lines(program, true, false);
+}
+
+fn stop_eeprom(program: &mut Program) {
+    set_eeprom_lines(program, false, false);
+    set_eeprom_lines(program, true, false);
+    set_eeprom_lines(program, true, true);
+}
+
+// Writes a byte and stores the acknowledge bit, 0x00 when the EEPROM pulled SDA low.
+fn write_eeprom_byte(program: &mut Program, byte: u8) {
+    for bit in (0..8).rev() {
+        let sda = byte & 1 << bit != 0;
+        set_eeprom_lines(program, false, sda);
+        set_eeprom_lines(program, true, sda);
+    }
+    set_eeprom_lines(program, false, true);
+    // LDA $6000, AND #$10, STA result
+    program.instruction(0xAD, EEPROM_DATA_ADDRESS);
+    program.code.extend([0x29, 0x10]);
+    let result_address = program.next_result_address();
+    program.instruction(0x8D, result_address);
+    set_eeprom_lines(program, true, true);
+}
+
+// Reads a byte without acknowledging it and stores it.
+fn read_eeprom_byte(program: &mut Program) {
+    let result_address = program.next_result_address();
+    for _ in 0..8 {
+        set_eeprom_lines(program, false, true);
+        // LDA $6000, AND #$10, CMP #$10, ROL result
+        program.instruction(0xAD, EEPROM_DATA_ADDRESS);
+        program.code.extend([0x29, 0x10, 0xC9, 0x10]);
+        program.instruction(0x2E, result_address);
+        set_eeprom_lines(program, true, true);
+    }
+    set_eeprom_lines(program, false, true);
+    set_eeprom_lines(program, true, true);
+}
+
+#[test]
+fn bandai_fcg_banking_test() {
+    let mut program = Program::new();
+    program.read(0x8000);
+    program.read(0xC000);
+    program.write(0x6008, 0x03);
+    program.read(0x8000);
+    program.write(0x8008, 0x03);
+    program.read(0x8000);
+    program.read(0xA000);
+    program.write(0x8005, 0x21);
+    program.read_vram(0x1400);
+    let rom = create_rom(16, 5, 0, 16, 32, &program);
+    assert_eq!(run_program(&rom, &program), [0, 30, 0, 6, 7, 0x21]);
+
+    // FCG-1/2 boards only decode the registers at $6000-$7FFF.
+    let mut program = Program::new();
+    program.write(0x8008, 0x03);
+    program.read(0x8000);
+    program.write(0x6008, 0x03);
+    program.read(0x8000);
+    let rom = create_rom(16, 4, 0, 16, 32, &program);
+    assert_eq!(run_program(&rom, &program), [0, 6]);
+}
+
+#[test]
+fn bandai_fcg_eeprom_test() {
+    let mut program = Program::new();
+    start_eeprom(&mut program);
+    write_eeprom_byte(&mut program, EEPROM_24C02_WRITE);
+    write_eeprom_byte(&mut program, 0x10);
+    write_eeprom_byte(&mut program, 0x5A);
+    write_eeprom_byte(&mut program, 0xC3);
+    stop_eeprom(&mut program);
+
+    start_eeprom(&mut program);
+    write_eeprom_byte(&mut program, EEPROM_24C02_WRITE);
+    write_eeprom_byte(&mut program, 0x10);
+    start_eeprom(&mut program);
+    write_eeprom_byte(&mut program, EEPROM_24C02_READ);
+    read_eeprom_byte(&mut program);
+    stop_eeprom(&mut program);
+
+    // Current address reads continue after the last byte read.
+    start_eeprom(&mut program);
+    write_eeprom_byte(&mut program, EEPROM_24C02_READ);
+    read_eeprom_byte(&mut program);
+    stop_eeprom(&mut program);
+
+    start_eeprom(&mut program);
+    write_eeprom_byte(&mut program, 0x50);
+    stop_eeprom(&mut program);
+
+    let rom = create_rom(16, 5, 0, 16, 32, &program);
+    assert_eq!(
+        run_program(&rom, &program),
+        [0, 0, 0, 0, 0, 0, 0, 0x5A, 0, 0xC3, 0x10]
+    );
+}
+
+#[test]
+fn bandai_lz93d50_prg_ram_test() {
+    let mut program = Program::new();
+    program.write(0x6000, 0x77);
+    program.read(0x6000);
+    program.write(0x800D, 0x20);
+    program.write(0x6000, 0x77);
+    program.read(0x6000);
+    // The outer PRG bank comes from the CHR registers and the fixed bank is always bank 15.
+    program.read(0xC000);
+    program.write(0x8000, 0x01);
+    program.read(0x8000);
+    program.read(0xC000);
+    let rom = create_rom(153, 0, 0, 32, 0, &program);
+    assert_eq!(run_program(&rom, &program), [0x00, 0x77, 30, 32, 62]);
+}
+
+#[test]
+fn datach_barcode_test() {
+    let program = Program::new();
+    let mut nes = create_nes(&create_rom(157, 0, 0, 16, 0, &program));
+    assert!(nes.scan_barcode("4902425521016").is_ok());
+    assert!(nes.scan_barcode("49024255").is_ok());
+    assert!(matches!(
+        nes.scan_barcode("490242552101"),
+        Err(Error::InvalidBarcode(_))
+    ));
+    assert!(matches!(
+        nes.scan_barcode("490242552101A"),
+        Err(Error::InvalidBarcode(_))
+    ));
+
+    let mut nes = create_nes(&create_rom(16, 5, 0, 16, 32, &program));
+    assert!(matches!(
+        nes.scan_barcode("4902425521016"),
+        Err(Error::BarcodeReaderUnavailable)
+    ));
+}
+
+#[test]
+fn discrete_mappers_banking_test() {
+    // Mapper, submapper, register write and the resulting 8KB PRG-ROM bank at $8000 and
+    // 1KB CHR-ROM bank at $0000.
+    let cases = [
+        (11, 0, 0xFFFF, 0x32, 8, 24),
+        (34, 2, 0xFFFF, 0x02, 8, 0),
+        (38, 0, 0x7000, 0x0E, 8, 24),
+        (79, 0, 0x4100, 0x0D, 4, 40),
+        (140, 0, 0x6000, 0x23, 8, 24),
+    ];
+    for (mapper, submapper, address, value, prg_bank, chr_bank) in cases {
+        let mut program = Program::new();
+        program.write(address, value);
+        program.read(0x8000);
+        program.read_vram(0x0000);
+        let rom = create_rom(mapper, submapper, 0, 8, 8, &program);
+        let results = run_program(&rom, &program);
+        assert_eq!(results, [prg_bank, chr_bank], "mapper {}", mapper);
+    }
+}
+
+#[test]
+fn unrom_180_test() {
+    let mut program = Program::new();
+    program.read(0xC000);
+    program.write(0xFFFF, 0x03);
+    program.read(0x8000);
+    program.read(0xC000);
+    let rom = create_rom(180, 0, 0, 8, 1, &program);
+    assert_eq!(run_program(&rom, &program), [0, 0, 6]);
+}
+
+#[test]
+fn cprom_test() {
+    let mut program = Program::new();
+    for bank in 0..4 {
+        program.write(0xFFFF, bank);
+        program.write_vram(0x1000, 0x10 + bank);
+    }
+    // $0000-$0FFF is fixed to the first 4KB bank.
+    for bank in 0..4 {
+        program.write(0xFFFF, bank);
+        program.read_vram(0x1000);
+        program.read_vram(0x0000);
+    }
+    let rom = create_rom(13, 0, 0, 2, 0, &program);
+    assert_eq!(
+        run_program(&rom, &program),
+        [0x10, 0x10, 0x11, 0x10, 0x12, 0x10, 0x13, 0x10]
+    );
+}
+
+#[test]
+fn nina_001_test() {
+    let mut program = Program::new();
+    program.write(0x7FFD, 0x01);
+    program.write(0x7FFE, 0x03);
+    program.write(0x7FFF, 0x05);
+    program.read(0x8000);
+    program.read_vram(0x0000);
+    program.read_vram(0x1000);
+    // The registers are written through to PRG-RAM.
+    program.read(0x7FFE);
+    let rom = create_rom(34, 1, 0, 4, 8, &program);
+    assert_eq!(run_program(&rom, &program), [4, 12, 20, 0x03]);
+}
+
+#[test]
+fn namco_108_test() {
+    let mut program = Program::new();
+    program.read(0xC000);
+    program.read(0xE000);
+    program.write(0x8000, 0x06);
+    program.write(0x8001, 0x05);
+    program.write(0x8000, 0x07);
+    program.write(0x8001, 0x09);
+    program.read(0x8000);
+    program.read(0xA000);
+
+    program.write(0x8000, 0x00);
+    program.write(0x8001, 0x05);
+    program.write(0x8000, 0x02);
+    program.write(0x8001, 0x09);
+    program.read_vram(0x0000);
+    program.read_vram(0x0400);
+    program.read_vram(0x1000);
+    let rom = create_rom(206, 0, 0, 8, 8, &program);
+    assert_eq!(run_program(&rom, &program), [14, 15, 5, 9, 4, 5, 9]);
+}
+
+#[test]
+fn mmc6_prg_ram_protect_test() {
+    let mut program = Program::new();
+    program.write(0x8000, 0x20);
+    program.write(0xA001, 0xF0);
+    program.write(0x7000, 0x55);
+    program.write(0x7200, 0x66);
+    program.read(0x7000);
+    program.read(0x7200);
+    // A disabled half reads 0 while the other one is enabled, open bus once both are disabled.
+    program.write(0xA001, 0x20);
+    program.read(0x7000);
+    program.read(0x7200);
+    program.write(0xA001, 0x00);
+    program.read(0x7000);
+    program.read(0x7200);
+    let rom = create_rom(4, 1, 0, 2, 1, &program);
+    assert_eq!(
+        run_program(&rom, &program),
+        [0x55, 0x66, 0x55, 0x00, 0x70, 0x72]
+    );
+}
+
+#[test]
+fn bus_conflict_test() {
+    // $C000 holds 8KB bank 30 (0b11110), so the UNROM write of 0x0F selects 16KB bank 14, not 15.
+    let read_unrom = |submapper: u8, address: u16| {
+        let mut program = Program::new();
+        program.write(address, 0x0F);
+        program.read(0x8000);
+        run_program(&create_rom(2, submapper, 0, 16, 1, &program), &program)
+    };
+    assert_eq!(read_unrom(0, 0xC000), [30]);
+    assert_eq!(read_unrom(1, 0xC000), [30]);
+    assert_eq!(read_unrom(2, 0xC000), [28]);
+    // Writes over a 0xFF byte are unaffected.
+    assert_eq!(read_unrom(2, 0xCFFF), [30]);
+
+    // $C000 holds 8KB bank 2, the CNROM write of 0x03 selects CHR bank 2 instead of 3.
+    let read_cnrom = |submapper: u8| {
+        let mut program = Program::new();
+        program.write(0xC000, 0x03);
+        program.read_vram(0x0000);
+        run_program(&create_rom(3, submapper, 0, 2, 4, &program), &program)
+    };
+    assert_eq!(read_cnrom(0), [24]);
+    assert_eq!(read_cnrom(2), [16]);
+
+    // GxROM always has bus conflicts, its submappers do not describe them.
+    let read_gxrom = |submapper: u8| {
+        let mut program = Program::new();
+        program.write(0xE000, 0x33);
+        program.read(0x8000);
+        program.read_vram(0x0000);
+        run_program(&create_rom(66, submapper, 0, 8, 4, &program), &program)
+    };
+    assert_eq!(read_gxrom(0), [0, 24]);
+    assert_eq!(read_gxrom(1), [0, 24]);
+}
+
+const NAMETABLES: [u16; 4] = [0x2000, 0x2400, 0x2800, 0x2C00];
+const VERTICAL: u8 = 0x01;
+const FOUR_SCREEN: u8 = 0x08;
+
+fn write_nametables(program: &mut Program) {
+    for (i, address) in NAMETABLES.into_iter().enumerate() {
+        program.write_vram(address, 0x10 + i as u8);
+    }
+}
+
+fn read_nametables(program: &mut Program) {
+    for address in NAMETABLES {
+        program.read_vram(address);
+    }
+}
+
+struct ButtonA;
+
+impl ControllerCallback for ButtonA {
+    fn is_button_pressed(&self, id: ControllerId, button: StdNesControllerButton) -> bool {
+        id == ControllerId::Controller1 && button == StdNesControllerButton::A
+    }
+
+    fn is_zapper_trigger_pressed(&self, _id: ControllerId) -> Option<ZapperTarget> {
+        None
+    }
+}
+
+fn wait_for_button_a(program: &mut Program) {
+    let loop_address = PROGRAM_ADDRESS + program.code.len() as u16;
+    program.write(0x4016, 0x01);
+    program.write(0x4016, 0x00);
+    // LDA $4016, AND #$01, BEQ loop
+    program.instruction(0xAD, 0x4016);
+    program.code.extend([0x29, 0x01, 0xF0]);
+    let offset = loop_address.wrapping_sub(PROGRAM_ADDRESS + program.code.len() as u16 + 1);
+    program.code.push(offset as u8);
+}
+
+#[test]
+fn four_screen_mirroring_test() {
+    for (mapper, prg_rom_units, chr_rom_units) in [(0, 2, 1), (4, 8, 8), (30, 8, 0), (206, 8, 8)] {
+        let mut program = Program::new();
+        write_nametables(&mut program);
+        read_nametables(&mut program);
+        let read = |flag_6: u8| {
+            let rom = create_rom(mapper, 0, flag_6, prg_rom_units, chr_rom_units, &program);
+            run_program(&rom, &program)
+        };
+        let (vertical, four_screen) = (read(VERTICAL), read(VERTICAL | FOUR_SCREEN));
+        assert_eq!(vertical, [0x12, 0x13, 0x12, 0x13], "mapper {}", mapper);
+        assert_eq!(four_screen, [0x10, 0x11, 0x12, 0x13], "mapper {}", mapper);
+    }
+
+    // MMC3 mirroring writes have no effect on four-screen boards, and the nametables survive a
+    // save state round trip.
+    let mut program = Program::new();
+    program.write(0xA000, 0x01);
+    write_nametables(&mut program);
+    read_nametables(&mut program);
+    wait_for_button_a(&mut program);
+    read_nametables(&mut program);
+    let rom = create_rom(4, 0, FOUR_SCREEN, 8, 8, &program);
+    let mut nes = create_nes(&rom);
+    run_frames(&mut nes, None);
+    let state = nes.save_state().unwrap();
+
+    let mut nes = create_nes(&rom);
+    nes.load_state(state).unwrap();
+    run_frames(&mut nes, Some(&ButtonA));
+    assert_eq!(
+        get_results(&nes, &program),
+        [0x10, 0x11, 0x12, 0x13].repeat(2)
+    );
+}
+
+#[test]
+fn nrom_multicarts_test() {
+    // Mapper 200 and 201 latch the bank from the address, 203 from the value.
+    let mut program = Program::new();
+    program.write(0x800D, 0x00);
+    program.read(0x8000);
+    program.read(0xC000);
+    program.read_vram(0x0000);
+    write_nametables(&mut program);
+    read_nametables(&mut program);
+    let rom = create_rom(200, 0, 0, 8, 8, &program);
+    assert_eq!(
+        run_program(&rom, &program),
+        [10, 10, 40, 0x11, 0x11, 0x13, 0x13]
+    );
+
+    let mut program = Program::new();
+    program.write(0x8003, 0x00);
+    program.read(0x8000);
+    program.read(0xC000);
+    program.read_vram(0x0000);
+    let rom = create_rom(201, 0, 0, 8, 4, &program);
+    assert_eq!(run_program(&rom, &program), [12, 14, 24]);
+
+    let mut program = Program::new();
+    program.write(0x8000, 0x0E);
+    program.read(0x8000);
+    program.read(0xC000);
+    program.read_vram(0x0000);
+    let rom = create_rom(203, 0, 0, 8, 4, &program);
+    assert_eq!(run_program(&rom, &program), [6, 6, 16]);
+}
+
+#[test]
+fn action_52_test() {
+    let mut program = Program::new();
+    // Chip 1, 16KB bank 5 mirrored at $8000 and $C000.
+    program.write(0x8000 | 1 << 11 | 5 << 6 | 1 << 5, 0x00);
+    program.read(0x8000);
+    program.read(0xC000);
+    // Chip 3 is the second 512KB chip of the board, 32KB bank 1 of it.
+    program.write(0x8000 | 3 << 11 | 2 << 6, 0x00);
+    program.read(0x8000);
+    program.read(0xC000);
+    // CHR bank 6 comes from both the address and the value, bit 13 selects horizontal mirroring.
+    program.write(0xA001, 0x02);
+    program.read_vram(0x0000);
+    write_nametables(&mut program);
+    read_nametables(&mut program);
+
+    program.write(0x5FF1, 0xAB);
+    program.read(0x5FF1);
+    program.read(0x4021);
+    let rom = create_rom(228, 0, 0, 96, 8, &program);
+    assert_eq!(
+        run_program(&rom, &program),
+        [74, 74, 132, 134, 48, 0x11, 0x11, 0x13, 0x13, 0x0B, 0x0B]
+    );
+}
+
+#[test]
+fn camerica_quattro_test() {
+    // The Aladdin Deck Enhancer (submapper 1) swaps the outer bank bits.
+    for (submapper, outer_bank) in [(0, 0x10), (1, 0x08)] {
+        let mut program = Program::new();
+        program.write(0x8000, outer_bank);
+        program.write(0xC000, 0x01);
+        program.read(0x8000);
+        program.read(0xC000);
+        let rom = create_rom(232, submapper, 0, 16, 1, &program);
+        assert_eq!(run_program(&rom, &program), [18, 22]);
+    }
+}
+
+#[test]
+fn fire_hawk_mirroring_test() {
+    let mut program = Program::new();
+    write_nametables(&mut program);
+    read_nametables(&mut program);
+    program.write(0x8000, 0x10);
+    program.write_vram(0x2000, 0x22);
+    read_nametables(&mut program);
+    program.write(0x8000, 0x00);
+    read_nametables(&mut program);
+    let rom = create_rom(71, 1, VERTICAL, 8, 0, &program);
+    let results = run_program(&rom, &program);
+    assert_eq!(results, [[0x13; 4], [0x22; 4], [0x13; 4]].concat());
+
+    // Other mapper 71 boards keep the header mirroring for $8000-$8FFF writes.
+    let mut program = Program::new();
+    program.write(0x8000, 0x10);
+    write_nametables(&mut program);
+    read_nametables(&mut program);
+    let rom = create_rom(71, 0, VERTICAL, 8, 0, &program);
+    assert_eq!(run_program(&rom, &program), [0x12, 0x13, 0x12, 0x13]);
+}
+
+const TRAINER_FLAG: u8 = 0x04;
+const TRAINER_SIZE: usize = 0x200;
+const TRAINER_ADDRESS: u16 = 0x7000;
+
+fn create_rom_with_trainer(
+    mapper: u8,
+    prg_rom_units: u8,
+    chr_rom_units: u8,
+    program: &Program,
+) -> Vec<u8> {
+    let mut rom = create_rom(
+        mapper,
+        0,
+        TRAINER_FLAG,
+        prg_rom_units,
+        chr_rom_units,
+        program,
+    );
+    let trainer = (0..TRAINER_SIZE).map(|i| (i * 3) as u8);
+    rom.splice(0x10..0x10, trainer);
+    rom
+}
+
+#[test]
+fn trainer_test() {
+    let offsets = [0x000, 0x001, 0x0FF, 0x100, 0x1FE, 0x1FF];
+    let mut program = Program::new();
+    for offset in offsets {
+        program.read(TRAINER_ADDRESS + offset);
+    }
+    // The PRG banks still come right after the trainer.
+    program.read(0x8000);
+    program.write(TRAINER_ADDRESS, 0x55);
+    program.read(TRAINER_ADDRESS);
+    let mut expected: Vec<u8> = offsets.iter().map(|&offset| (offset * 3) as u8).collect();
+    expected.extend([0, 0x55]);
+
+    for (mapper, prg_rom_units, chr_rom_units) in [(1, 8, 2), (3, 2, 4), (4, 8, 8), (34, 8, 4)] {
+        let rom = create_rom_with_trainer(mapper, prg_rom_units, chr_rom_units, &program);
+        let mut nes = create_nes(&rom);
+        run_frames(&mut nes, None);
+        assert_eq!(get_results(&nes, &program), expected, "mapper {}", mapper);
+        nes.power_cycle();
+        run_frames(&mut nes, None);
+        assert_eq!(get_results(&nes, &program), expected, "mapper {}", mapper);
+    }
+
+    let rom = create_rom_with_trainer(0, 1, 1, &program);
+    assert!(matches!(
+        Nes::new().load_rom(&rom[..0x10 + 0x100]),
+        Err(Error::NesRomTrainerTooShort(0x100))
+    ));
+}
@@ -1,4 +1,5 @@
 mod common;
+use common::run_short_test_with_rom;
 use common::run_simple_short_test;
 
 #[test]
@@ -19,10 +20,14 @@ fn a12_clocking() {
 fn scanline_timing() {
     run_simple_short_test("tests/mmc3_irq_tests/4.Scanline_timing.nes");
 }
-#[ignore]
 #[test]
 fn mmc3_rev_a() {
-    run_simple_short_test("tests/mmc3_irq_tests/5.MMC3_rev_A.nes");
+    let rom_path = "tests/mmc3_irq_tests/5.MMC3_rev_A.nes";
+    let mut rom = std::fs::read(rom_path).unwrap();
+    // Mark the iNES header as NES 2.0 with submapper 4 to select the revision A IRQ behavior.
+    rom[7] = (rom[7] & 0xF0) | 0x08;
+    rom[8] = 0x40;
+    run_short_test_with_rom(rom_path, &rom);
 }
 #[test]
 fn mmc3_rev_b() {
@@ -0,0 +1,164 @@
+use nes_rs::{ControllerCallback, Error, Nes};
+
+const LOAD_ADDRESS: u16 = 0x8000;
+const INIT_ADDRESS: u16 = 0x8000;
+const PLAY_ADDRESS: u16 = 0x8003;
+const NSF_HEADER_SIZE: usize = 0x80;
+const REGION_PAL: u8 = 0x01;
+
+// INIT stores the region passed in X at $00, PLAY returns right away.
+const PROGRAM: [u8; 4] = [0x86, 0x00, 0x60, 0x60];
+
+fn write_u16(data: &mut [u8], index: usize, value: u16) {
+    data[index..index + 2].copy_from_slice(&value.to_le_bytes());
+}
+
+fn write_string(data: &mut [u8], index: usize, value: &str) {
+    data[index..index + value.len()].copy_from_slice(value.as_bytes());
+}
+
+fn create_nsf(load_address: u16, region: u8) -> Vec<u8> {
+    let mut nsf = vec![0; NSF_HEADER_SIZE];
+    nsf[..5].copy_from_slice(b"NESM\x1A");
+    nsf[0x05] = 1;
+    nsf[0x06] = 3;
+    nsf[0x07] = 2;
+    write_u16(&mut nsf, 0x08, load_address);
+    write_u16(&mut nsf, 0x0A, INIT_ADDRESS);
+    write_u16(&mut nsf, 0x0C, PLAY_ADDRESS);
+    write_string(&mut nsf, 0x0E, "Title");
+    write_string(&mut nsf, 0x2E, "Artist");
+    write_string(&mut nsf, 0x4E, "Copyright");
+    write_u16(&mut nsf, 0x6E, 16639);
+    write_u16(&mut nsf, 0x78, 19997);
+    nsf[0x7A] = region;
+    nsf.extend_from_slice(&PROGRAM);
+    nsf
+}
+
+fn add_chunk(nsfe: &mut Vec<u8>, id: &[u8], data: &[u8]) {
+    nsfe.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    nsfe.extend_from_slice(id);
+    nsfe.extend_from_slice(data);
+}
+
+fn create_nsfe(chunks: &[&[u8]]) -> Vec<u8> {
+    let mut info = Vec::new();
+    info.extend_from_slice(&LOAD_ADDRESS.to_le_bytes());
+    info.extend_from_slice(&INIT_ADDRESS.to_le_bytes());
+    info.extend_from_slice(&PLAY_ADDRESS.to_le_bytes());
+    info.extend_from_slice(&[REGION_PAL, 0x00, 0x02, 0x01]);
+
+    let mut nsfe = b"NSFE".to_vec();
+    for &chunk in chunks {
+        match chunk {
+            b"INFO" => add_chunk(&mut nsfe, chunk, &info),
+            b"DATA" => add_chunk(&mut nsfe, chunk, &PROGRAM),
+            b"auth" => add_chunk(&mut nsfe, chunk, b"Title\0Artist\0Copyright\0"),
+            b"tlbl" => add_chunk(&mut nsfe, chunk, b"First\0Second\0"),
+            _ => add_chunk(&mut nsfe, chunk, &[]),
+        }
+    }
+    nsfe
+}
+
+fn load_nsf(nsf: &[u8]) -> Result<Nes, Error> {
+    let mut nes = Nes::new();
+    nes.load_nsf(nsf)?;
+    Ok(nes)
+}
+
+fn get_init_region(nsf: &[u8]) -> u8 {
+    let mut nes = load_nsf(nsf).unwrap();
+    let callback: Option<&dyn ControllerCallback> = None;
+    nes.run_single_frame(callback).unwrap();
+    nes.read_cpu_memory(0x0000)
+}
+
+#[test]
+fn nsf_header_test() {
+    let nes = load_nsf(&create_nsf(LOAD_ADDRESS, 0)).unwrap();
+    let info = nes.get_nsf_info().unwrap();
+    assert_eq!(info.title, "Title");
+    assert_eq!(info.artist, "Artist");
+    assert_eq!(info.copyright, "Copyright");
+    assert_eq!(info.track_count, 3);
+    assert_eq!(info.current_track, 1);
+    assert!(info.track_labels.is_empty());
+}
+
+#[test]
+fn nsf_region_test() {
+    assert_eq!(get_init_region(&create_nsf(LOAD_ADDRESS, 0)), 0x00);
+    assert_eq!(get_init_region(&create_nsf(LOAD_ADDRESS, REGION_PAL)), 0x01);
+    assert_eq!(get_init_region(&create_nsf(LOAD_ADDRESS, 0b10)), 0x00);
+}
+
+#[test]
+fn nsf_errors_test() {
+    let nsf = create_nsf(LOAD_ADDRESS, 0);
+    assert!(matches!(
+        load_nsf(&nsf[..0x40]),
+        Err(Error::NsfHeaderTooShort(0x40))
+    ));
+    assert!(matches!(load_nsf(&nsf[1..]), Err(Error::UnknownNsfFormat)));
+    assert!(matches!(
+        load_nsf(&create_nsf(0x4000, 0)),
+        Err(Error::NsfInvalidLoadAddress(0x4000))
+    ));
+    let mut no_tracks = nsf.clone();
+    no_tracks[0x06] = 0;
+    assert!(matches!(load_nsf(&no_tracks), Err(Error::UnknownNsfFormat)));
+}
+
+#[test]
+fn nsfe_chunks_test() {
+    let nsfe = create_nsfe(&[b"INFO", b"DATA", b"auth", b"tlbl", b"NEND"]);
+    let nes = load_nsf(&nsfe).unwrap();
+    let info = nes.get_nsf_info().unwrap();
+    assert_eq!(info.title, "Title");
+    assert_eq!(info.artist, "Artist");
+    assert_eq!(info.copyright, "Copyright");
+    assert_eq!(info.track_count, 2);
+    assert_eq!(info.current_track, 1);
+    assert_eq!(info.track_labels, ["First", "Second"]);
+    assert_eq!(get_init_region(&nsfe), 0x01);
+}
+
+#[test]
+fn nsfe_errors_test() {
+    let is_missing = |nsfe: &[u8], chunk: &str| match load_nsf(nsfe) {
+        Err(Error::NsfeMissingChunk(id)) => id == chunk,
+        _ => false,
+    };
+    assert!(is_missing(&create_nsfe(&[b"INFO", b"DATA"]), "NEND"));
+    assert!(is_missing(&create_nsfe(&[b"DATA", b"NEND"]), "INFO"));
+    assert!(is_missing(&create_nsfe(&[b"INFO", b"NEND"]), "DATA"));
+    assert!(is_missing(&create_nsfe(&[b"BANK", b"INFO"]), "INFO"));
+
+    let nsfe = create_nsfe(&[b"INFO", b"DATA", b"NEND"]);
+    assert!(matches!(
+        load_nsf(&nsfe[..nsfe.len() - 12]),
+        Err(Error::NsfeChunkCorrupted(id)) if id == "DATA"
+    ));
+    assert!(matches!(
+        load_nsf(&create_nsfe(&[b"RATE", b"INFO", b"DATA", b"NEND"])),
+        Err(Error::NsfeChunkCorrupted(id)) if id == "RATE"
+    ));
+}
+
+#[test]
+fn nsf2_data_length_test() {
+    let trailing_data_address = LOAD_ADDRESS + PROGRAM.len() as u16;
+    let mut nsf = create_nsf(LOAD_ADDRESS, 0);
+    nsf.extend_from_slice(b"auth\xAA\xAA");
+
+    let nes = load_nsf(&nsf).unwrap();
+    assert_eq!(nes.peek_cpu_memory(trailing_data_address), b'a');
+
+    nsf[0x05] = 2;
+    nsf[0x7D] = PROGRAM.len() as u8;
+    let nes = load_nsf(&nsf).unwrap();
+    assert_eq!(nes.peek_cpu_memory(trailing_data_address), 0x00);
+    assert_eq!(get_init_region(&nsf), 0x00);
+}
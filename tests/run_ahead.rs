@@ -0,0 +1,76 @@
+use nes_rs::{ControllerCallback, MAX_RUN_AHEAD_FRAMES, Nes, Snapshot};
+
+const ROM_PATH: &str = "tests/cpu_interrupts_v2/1-cli_latency.nes";
+const FRAME_COUNT: usize = 60;
+const RUN_AHEAD_FRAMES: u8 = 2;
+
+fn create_nes() -> Nes {
+    let rom = std::fs::read(ROM_PATH).unwrap();
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+    nes
+}
+
+fn run_frame(nes: &mut Nes) -> (Vec<u8>, Vec<f32>) {
+    let callback: Option<&dyn ControllerCallback> = None;
+    let frame = nes.run_single_frame(callback).unwrap();
+    (
+        frame.video.get_pixels().to_vec(),
+        frame.audio.get_samples().to_vec(),
+    )
+}
+
+fn run_ahead_test(second_instance: bool) {
+    let mut nes = create_nes();
+    let frames: Vec<_> = (0..FRAME_COUNT + RUN_AHEAD_FRAMES as usize)
+        .map(|_| run_frame(&mut nes))
+        .collect();
+
+    let mut nes = create_nes();
+    nes.config().set_run_ahead_frames(RUN_AHEAD_FRAMES);
+    nes.config()
+        .set_run_ahead_second_instance_enabled(second_instance);
+    for frame in 0..FRAME_COUNT {
+        let (video, audio) = run_frame(&mut nes);
+        assert!(video == frames[frame + RUN_AHEAD_FRAMES as usize].0);
+        assert!(audio == frames[frame].1);
+    }
+    assert_eq!(nes.get_playtime_frames(), FRAME_COUNT as u64);
+}
+
+#[test]
+fn run_ahead_single_instance_test() {
+    run_ahead_test(false);
+}
+
+#[test]
+fn run_ahead_second_instance_test() {
+    run_ahead_test(true);
+}
+
+#[test]
+fn run_ahead_config_test() {
+    let mut nes = Nes::new();
+    nes.config().set_run_ahead_frames(MAX_RUN_AHEAD_FRAMES + 1);
+    assert_eq!(nes.config().get_run_ahead_frames(), MAX_RUN_AHEAD_FRAMES);
+    assert!(!nes.config().is_run_ahead_second_instance_enabled());
+    nes.config().set_run_ahead_second_instance_enabled(true);
+    assert!(nes.config().is_run_ahead_second_instance_enabled());
+}
+
+#[test]
+fn snapshot_test() {
+    let mut nes = create_nes();
+    for _ in 0..10 {
+        run_frame(&mut nes);
+    }
+    let mut snapshot = Snapshot::default();
+    nes.save_snapshot(&mut snapshot);
+    let frames: Vec<_> = (0..10).map(|_| run_frame(&mut nes).0).collect();
+
+    nes.load_snapshot(&snapshot).unwrap();
+    assert_eq!(nes.get_playtime_frames(), 10);
+    for frame in frames {
+        assert!(run_frame(&mut nes).0 == frame);
+    }
+}
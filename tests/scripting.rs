@@ -0,0 +1,38 @@
+#![cfg(feature = "scripting")]
+use nes_rs::scripting::Script;
+use nes_rs::{ControllerCallback, ControllerId, Nes, StdNesControllerButton};
+
+#[test]
+fn script_frame_callback_test() {
+    let rom = std::fs::read("tests/nestest/nestest.nes").unwrap();
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+    let source = r#"
+        set_button(1, "start", true);
+        fn on_frame() {
+            write_memory(0x0301, get_frame());
+            draw_text(8, 8, "FRAME " + get_frame());
+            if get_frame() == 10 {
+                quit();
+            }
+        }
+    "#;
+    let mut script = Script::new(source, &mut nes).unwrap();
+    while !script.is_quit_requested() {
+        let callback = script.get_controller_callback(None);
+        assert!(
+            callback.is_button_pressed(ControllerId::Controller1, StdNesControllerButton::Start)
+        );
+        nes.run_single_frame(&callback).unwrap();
+        script.notify_frame(&mut nes).unwrap();
+    }
+    assert_eq!(nes.read_cpu_memory(0x0301), 10);
+}
+
+#[test]
+fn script_error_test() {
+    let mut nes = Nes::new();
+    assert!(Script::new("read_memory(0x10000);", &mut nes).is_err());
+    assert!(Script::new("set_button(3, \"A\", true);", &mut nes).is_err());
+    assert!(Script::new("fn on_frame( {", &mut nes).is_err());
+}
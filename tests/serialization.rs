@@ -1,20 +1,102 @@
-mod common;
-use common::nes_test::NesTest;
-use std::time::Duration;
-
-#[test]
-fn serialization_test() {
-    let rom_path = "tests/nestest/nestest.nes";
-    let test_fn = |nes_test: &mut NesTest| {
-        nes_test.run_for(Duration::from_secs(1));
-        nes_test.press_player_1_start();
-        nes_test.run_for(Duration::from_secs(3));
-        let serialized = nes_test.serialize_and_reset();
-        nes_test.deserialize(serialized);
-        nes_test.release_player_1_start();
-        nes_test.run_for(Duration::from_secs(1));
-    };
-
-    let mut nes_test = NesTest::new(rom_path, Some("official"), test_fn);
-    assert!(nes_test.run());
-}
+mod common;
+use common::nes_test::NesTest;
+use nes_rs::{
+    ControllerCallback, Error, Nes, SAVE_STATE_THUMBNAIL_HEIGHT, SAVE_STATE_THUMBNAIL_WIDTH,
+};
+use std::time::Duration;
+
+#[test]
+fn serialization_test() {
+    let rom_path = "tests/nestest/nestest.nes";
+    let test_fn = |nes_test: &mut NesTest| {
+        nes_test.run_for(Duration::from_secs(1));
+        nes_test.press_player_1_start();
+        nes_test.run_for(Duration::from_secs(3));
+        let serialized = nes_test.serialize_and_reset();
+        nes_test.deserialize(serialized);
+        nes_test.release_player_1_start();
+        nes_test.run_for(Duration::from_secs(1));
+    };
+
+    let mut nes_test = NesTest::new(rom_path, Some("official"), test_fn);
+    assert!(nes_test.run());
+}
+
+fn run_frame(nes: &mut Nes) -> Vec<u8> {
+    let callback: Option<&dyn ControllerCallback> = None;
+    let frame = nes.run_single_frame(callback).unwrap();
+    frame.video.get_pixels().to_vec()
+}
+
+#[test]
+fn save_state_header_test() {
+    let rom = std::fs::read("tests/nestest/nestest.nes").unwrap();
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+    for _ in 0..10 {
+        run_frame(&mut nes);
+    }
+
+    let state = nes.save_state_with_thumbnail().unwrap();
+    let info = Nes::get_save_state_info(&state).unwrap();
+    assert_eq!(info.version, 2);
+    assert_eq!(info.rom_crc32, Some(crc32fast::hash(&rom)));
+    assert_eq!(info.playtime_frames, Some(10));
+    assert!(info.timestamp.unwrap() > 0);
+    let thumbnail = info.thumbnail.unwrap();
+    assert_eq!(
+        thumbnail.len(),
+        SAVE_STATE_THUMBNAIL_WIDTH * SAVE_STATE_THUMBNAIL_HEIGHT * 3
+    );
+    assert!(
+        Nes::get_save_state_info(&nes.save_state().unwrap())
+            .unwrap()
+            .thumbnail
+            .is_none()
+    );
+
+    let frame = run_frame(&mut nes);
+    nes.load_state(state.clone()).unwrap();
+    assert_eq!(nes.get_playtime_frames(), 10);
+    assert_eq!(run_frame(&mut nes), frame);
+
+    let other_rom = std::fs::read("tests/cpu_interrupts_v2/1-cli_latency.nes").unwrap();
+    let mut other_nes = Nes::new();
+    other_nes.load_rom(&other_rom).unwrap();
+    assert!(matches!(
+        other_nes.load_state(state.clone()),
+        Err(Error::LoadStateRomMismatch(_, _))
+    ));
+
+    let no_rom_state = Nes::new().save_state().unwrap();
+    assert_eq!(
+        Nes::get_save_state_info(&no_rom_state).unwrap().rom_crc32,
+        None
+    );
+
+    let mut newer_state = state;
+    newer_state[8] = 0xFF;
+    assert!(matches!(
+        nes.load_state(newer_state),
+        Err(Error::LoadStateVersionMismatch(_, _))
+    ));
+}
+
+#[test]
+fn save_state_migration_test() {
+    let rom = std::fs::read("tests/nestest/nestest.nes").unwrap();
+    let state = std::fs::read("tests/nestest/nestest_v1.state").unwrap();
+    let info = Nes::get_save_state_info(&state).unwrap();
+    assert_eq!(info.version, 1);
+    assert_eq!(info.rom_crc32, None);
+
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+    nes.load_state(state).unwrap();
+    let migrated = nes.save_state().unwrap();
+    assert_eq!(Nes::get_save_state_info(&migrated).unwrap().version, 2);
+
+    let frame = run_frame(&mut nes);
+    nes.load_state(migrated).unwrap();
+    assert_eq!(run_frame(&mut nes), frame);
+}
@@ -0,0 +1,49 @@
+use nes_rs::{
+    ControllerCallback, Nes, Overscan, PIXEL_SIZE, PixelAspectRatio, VIDEO_FRAME_HEIGHT,
+    VIDEO_FRAME_WIDTH, VideoOutputSettings,
+};
+
+#[test]
+fn video_output_crop_and_scale_test() {
+    let rom = std::fs::read("tests/nestest/nestest.nes").unwrap();
+    let mut nes = Nes::new();
+    nes.load_rom(&rom).unwrap();
+    let callback: Option<&dyn ControllerCallback> = None;
+    for _ in 1..60 {
+        nes.run_single_frame(callback).unwrap();
+    }
+    let frame = nes.run_single_frame(callback).unwrap();
+
+    let full = VideoOutputSettings {
+        overscan: Overscan::none(),
+        ..Default::default()
+    }
+    .render(frame, 1);
+    assert_eq!(full.get_width(), VIDEO_FRAME_WIDTH);
+    assert_eq!(full.get_height(), VIDEO_FRAME_HEIGHT);
+    assert_eq!(full.get_pixels(), frame.video.get_pixels().as_slice());
+
+    let settings = VideoOutputSettings {
+        overscan: Overscan {
+            left: 8,
+            ..Default::default()
+        },
+        pixel_aspect_ratio: PixelAspectRatio::Ntsc,
+        integer_scaling: true,
+    };
+    assert_eq!(settings.get_output_size(), [283, 224]);
+    let image = settings.render(frame, 2);
+    assert_eq!(image.get_width(), 566);
+    assert_eq!(image.get_height(), 448);
+    for (image_x, image_y) in [(0, 0), (283, 100), (565, 447)] {
+        let x = 8 + image_x * 248 / 566;
+        let y = 8 + image_y / 2;
+        let (r, g, b) = frame.video.get_pixel(x as u8, y as u8);
+        let index = (image_y * image.get_width() + image_x) * PIXEL_SIZE;
+        assert_eq!(image.get_pixels()[index..index + PIXEL_SIZE], [r, g, b]);
+    }
+
+    let (position, size) = settings.get_viewport([1920.0, 1080.0]);
+    assert_eq!(size, [1132.0, 896.0]);
+    assert_eq!(position, [394.0, 92.0]);
+}
@@ -0,0 +1,166 @@
+use nes_rs::{
+    ControllerCallback, ControllerId, Nes, StdNesControllerButton, VsCoinSlot, ZapperTarget,
+};
+
+const PRG_ROM_SIZE: usize = 0x8000;
+const CHR_ROM_SIZE: usize = 0x2000;
+const RESET_VECTOR_OFFSET: usize = 0x7FFC;
+
+const VS_UNISYSTEM: u8 = 0x00;
+const RBI_BASEBALL_PROTECTION: u8 = 0x10;
+const TKO_BOXING_PROTECTION: u8 = 0x20;
+const SUPER_XEVIOUS_PROTECTION: u8 = 0x30;
+const RP2C03: u8 = 0x00;
+const RC2C05_01: u8 = 0x08;
+
+const DIP_SWITCHES: u8 = 0b1010_1101;
+
+// Strobes the controllers and stores the first $4016 and $4017 reads at $00 and $01.
+const READ_INPUTS_PROGRAM: [u8; 23] = [
+    0xA9, 0x01, 0x8D, 0x16, 0x40, 0xA9, 0x00, 0x8D, 0x16, 0x40, 0xAD, 0x16, 0x40, 0x85, 0x00, 0xAD,
+    0x17, 0x40, 0x85, 0x01, 0x4C, 0x14, 0x80,
+];
+
+struct VsCallback;
+
+impl ControllerCallback for VsCallback {
+    fn is_button_pressed(&self, id: ControllerId, button: StdNesControllerButton) -> bool {
+        id == ControllerId::Controller1 && button == StdNesControllerButton::A
+    }
+
+    fn is_zapper_trigger_pressed(&self, _id: ControllerId) -> Option<ZapperTarget> {
+        None
+    }
+
+    fn is_vs_coin_inserted(&self, slot: VsCoinSlot) -> bool {
+        slot == VsCoinSlot::Coin1
+    }
+
+    fn is_vs_service_button_pressed(&self) -> bool {
+        true
+    }
+
+    fn get_vs_dip_switches(&self) -> u8 {
+        DIP_SWITCHES
+    }
+}
+
+// NES 2.0 image for mapper 99 with byte 13 holding the VS. hardware and PPU types.
+fn create_vs_rom(vs_type: u8, program: &[u8]) -> Vec<u8> {
+    let mut rom = vec![
+        b'N', b'E', b'S', 0x1A, 0x02, 0x01, 0x30, 0x69, 0x00, 0x00, 0x00, 0x00, 0x00, vs_type,
+        0x00, 0x00,
+    ];
+    let mut prg_rom = vec![0; PRG_ROM_SIZE];
+    prg_rom[..program.len()].copy_from_slice(program);
+    prg_rom[RESET_VECTOR_OFFSET..RESET_VECTOR_OFFSET + 2].copy_from_slice(&[0x00, 0x80]);
+    rom.extend_from_slice(&prg_rom);
+    rom.extend_from_slice(&[0; CHR_ROM_SIZE]);
+    rom
+}
+
+fn create_nes(vs_type: u8, program: &[u8]) -> Nes {
+    let mut nes = Nes::new();
+    nes.load_rom(&create_vs_rom(vs_type, program)).unwrap();
+    nes
+}
+
+fn set_vram_address(nes: &mut Nes, address: u16) {
+    nes.read_cpu_memory(0x2002);
+    nes.write_cpu_memory(0x2006, (address >> 8) as u8);
+    nes.write_cpu_memory(0x2006, address as u8);
+}
+
+fn read_vram(nes: &mut Nes, address: u16) -> u8 {
+    set_vram_address(nes, address);
+    nes.read_cpu_memory(0x2007);
+    nes.read_cpu_memory(0x2007)
+}
+
+// Writes two bytes with the VRAM increment bit set through $2001 and returns where they landed.
+fn write_vram_with_increment_in_2001(vs_type: u8) -> (u8, u8) {
+    let mut nes = create_nes(vs_type, &[]);
+    nes.write_cpu_memory(0x2000, 0x00);
+    nes.write_cpu_memory(0x2001, 0x04);
+    set_vram_address(&mut nes, 0x2000);
+    nes.write_cpu_memory(0x2007, 0x11);
+    nes.write_cpu_memory(0x2007, 0x22);
+    nes.write_cpu_memory(0x2001, 0x00);
+    (read_vram(&mut nes, 0x2001), read_vram(&mut nes, 0x2020))
+}
+
+#[test]
+fn rc2c05_register_swap_test() {
+    assert_eq!(write_vram_with_increment_in_2001(RP2C03), (0x22, 0x00));
+    assert_eq!(write_vram_with_increment_in_2001(RC2C05_01), (0x00, 0x22));
+}
+
+#[test]
+fn rc2c05_status_id_test() {
+    let mut nes = create_nes(RC2C05_01, &[]);
+    assert_eq!(nes.read_cpu_memory(0x2002) & 0x1F, 0x1B);
+    nes.write_cpu_memory(0x2001, 0x1F);
+    assert_eq!(nes.read_cpu_memory(0x2002) & 0x1F, 0x1B);
+
+    let mut nes = create_nes(RP2C03, &[]);
+    nes.write_cpu_memory(0x2001, 0x1F);
+    assert_eq!(nes.read_cpu_memory(0x2002) & 0x1F, 0x1F);
+}
+
+#[test]
+fn vs_input_layout_test() {
+    let mut nes = create_nes(VS_UNISYSTEM | RP2C03, &READ_INPUTS_PROGRAM);
+    nes.run_single_frame(&VsCallback).unwrap();
+    // $4016: controller bit, service button, DIP switches 1-2 and coin slot 1.
+    assert_eq!(nes.read_cpu_memory(0x0000), 0b0010_1101);
+    // $4017: controller bit and DIP switches 3-8.
+    assert_eq!(nes.read_cpu_memory(0x0001), 0b1010_1100);
+}
+
+#[test]
+fn vs_counter_protection_test() {
+    let read_protection = |vs_type: u8| {
+        let mut nes = create_nes(vs_type | RP2C03, &[]);
+        nes.read_cpu_memory(0x5E01);
+        nes.read_cpu_memory(0x5E00);
+        (0..10)
+            .map(|_| nes.read_cpu_memory(0x5E01))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(read_protection(VS_UNISYSTEM), [0x00; 10]);
+    assert_eq!(
+        read_protection(RBI_BASEBALL_PROTECTION),
+        [0x00, 0x00, 0x00, 0x00, 0xB4, 0x00, 0x00, 0x00, 0x00, 0x6F]
+    );
+    assert_eq!(
+        read_protection(TKO_BOXING_PROTECTION),
+        [0xFF, 0xBF, 0xB7, 0x97, 0x97, 0x17, 0x57, 0x4F, 0x6F, 0x6B]
+    );
+}
+
+#[test]
+fn vs_super_xevious_protection_test() {
+    let mut nes = create_nes(SUPER_XEVIOUS_PROTECTION | RP2C03, &[]);
+    assert_eq!(nes.read_cpu_memory(0x54FF), 0x05);
+    assert_eq!(nes.read_cpu_memory(0x5678), 0x01);
+    assert_eq!(nes.read_cpu_memory(0x578F), 0x89);
+    assert_eq!(nes.read_cpu_memory(0x5567), 0x37);
+    assert_eq!(nes.read_cpu_memory(0x5678), 0x00);
+    assert_eq!(nes.read_cpu_memory(0x578F), 0xD1);
+    assert_eq!(nes.read_cpu_memory(0x5567), 0x3E);
+}
+
+#[test]
+fn vs_protection_peek_test() {
+    let mut nes = create_nes(TKO_BOXING_PROTECTION | RP2C03, &[]);
+    nes.read_cpu_memory(0x5E00);
+    assert_eq!(nes.peek_cpu_memory(0x5E01), 0xFF);
+    assert_eq!(nes.peek_cpu_memory(0x5E01), 0xFF);
+    assert_eq!(nes.read_cpu_memory(0x5E01), 0xFF);
+    assert_eq!(nes.peek_cpu_memory(0x5E01), 0xBF);
+    assert_eq!(nes.read_cpu_memory(0x5E01), 0xBF);
+
+    let mut nes = create_nes(SUPER_XEVIOUS_PROTECTION | RP2C03, &[]);
+    assert_eq!(nes.peek_cpu_memory(0x5567), 0x37);
+    assert_eq!(nes.read_cpu_memory(0x5678), 0x01);
+}